@@ -0,0 +1,103 @@
+//! Deteksi backlog RTU: setelah sambung ulang banyak RTU menguras buffer kejadian sekaligus,
+//! jendela k terisi terus dan tiap S-ACK kita keluar dengan jendela hampir penuh (w atau
+//! emergency, bukan t2). Laju I-frame RTU plus porsi S-ACK berjendela tinggi dalam `WINDOW`
+//! terakhir mengubah hitungan pemakaian jendela menjadi status "backlog RTU dicurigai".
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Jendela geser pengamatan laju & S-ACK.
+pub const WINDOW: Duration = Duration::from_secs(10);
+/// S-ACK "tinggi": emergency, atau jendela terpakai >= fraksi k ini saat dikirim.
+const HIGH_USAGE: f64 = 0.6;
+/// Minimal S-ACK tinggi dalam `WINDOW` sebelum dicurigai.
+const MIN_HIGH: usize = 5;
+/// Jendela jenuh harus bertahan selama ini: burst GI biasa selesai lebih cepat.
+const SUSTAIN: Duration = Duration::from_secs(5);
+
+/// Perubahan status untuk dilog pemanggil.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BacklogChange {
+    /// `high` (di antaranya `emergency`) dari `acks` S-ACK dalam `WINDOW`, laju I-frame/s saat itu.
+    Started { high: usize, emergency: usize, acks: usize, rate: f64 },
+    /// Lama episode dan I-frame yang diterima selama itu.
+    Ended { took: Duration, frames: u64 },
+}
+
+#[derive(Debug)]
+struct AckSample {
+    at: Instant,
+    high: bool,
+    emergency: bool,
+}
+
+#[derive(Debug)]
+pub struct Backlog {
+    i_recent: VecDeque<Instant>,
+    acks_recent: VecDeque<AckSample>,
+    i_total: u64,
+    /// Episode berjalan: mulai, `i_total` saat mulai.
+    active: Option<(Instant, u64)>,
+    pub episodes: u64,
+    pub total: Duration,
+}
+
+impl Backlog {
+    pub fn new() -> Self {
+        Backlog { i_recent: VecDeque::new(), acks_recent: VecDeque::new(), i_total: 0, active: None, episodes: 0, total: Duration::ZERO }
+    }
+
+    pub fn i_frame(&mut self, now: Instant) {
+        self.i_total += 1;
+        self.i_recent.push_back(now);
+    }
+
+    /// `reason` = kunci AckStats; `used` = I-frame yang di-ACK S-ACK ini, dari jendela `k`.
+    pub fn ack(&mut self, now: Instant, reason: &str, used: u16, k: u16) {
+        let emergency = reason == "emergency";
+        let high = emergency || used as f64 >= k as f64 * HIGH_USAGE;
+        self.acks_recent.push_back(AckSample { at: now, high, emergency });
+    }
+
+    pub fn suspected(&self) -> bool { self.active.is_some() }
+
+    /// I-frame/s rata-rata dalam `WINDOW` terakhir.
+    pub fn rate(&self, now: Instant) -> f64 {
+        let n = self.i_recent.iter().filter(|t| now.duration_since(**t) <= WINDOW).count();
+        n as f64 / WINDOW.as_secs_f64()
+    }
+
+    /// Dicurigai bila S-ACK tinggi >= MIN_HIGH, mayoritas dalam jendela, dan yang tertua sudah
+    /// berumur `SUSTAIN`; selesai bila tidak ada S-ACK tinggi lagi selama satu `WINDOW` penuh.
+    pub fn check(&mut self, now: Instant) -> Option<BacklogChange> {
+        while self.i_recent.front().is_some_and(|t| now.duration_since(*t) > WINDOW) { self.i_recent.pop_front(); }
+        while self.acks_recent.front().is_some_and(|a| now.duration_since(a.at) > WINDOW) { self.acks_recent.pop_front(); }
+        let acks = self.acks_recent.len();
+        let high = self.acks_recent.iter().filter(|a| a.high).count();
+        let emergency = self.acks_recent.iter().filter(|a| a.emergency).count();
+        let sustained = self.acks_recent.iter().find(|a| a.high).is_some_and(|a| now.duration_since(a.at) >= SUSTAIN);
+        match self.active {
+            None if high >= MIN_HIGH && high * 2 >= acks && sustained => {
+                self.active = Some((now, self.i_total));
+                self.episodes += 1;
+                Some(BacklogChange::Started { high, emergency, acks, rate: self.rate(now) })
+            }
+            Some(_) if high == 0 => self.end(now),
+            _ => None,
+        }
+    }
+
+    /// Sesi putus: jendela dikosongkan, episode berjalan ditutup.
+    pub fn reset(&mut self, now: Instant) -> Option<BacklogChange> {
+        self.i_recent.clear();
+        self.acks_recent.clear();
+        self.end(now)
+    }
+
+    fn end(&mut self, now: Instant) -> Option<BacklogChange> {
+        let (start, frames_at) = self.active.take()?;
+        let took = now.duration_since(start);
+        self.total += took;
+        Some(BacklogChange::Ended { took, frames: self.i_total - frames_at })
+    }
+}
@@ -0,0 +1,110 @@
+//! Mode `--bench`: ukur throughput jalur panas RX tanpa socket — potong APDU dari buffer
+//! (`take_one_apdu` + drain seperti read loop), klasifikasi APCI, dekode ASDU penuh — plus
+//! biaya `build_s_ack` dan `hex` baris log RX. Hanya std (`Instant`, `black_box`): angka
+//! pembanding antar commit di mesin yang sama, bukan statistik ala criterion — fungsi jalur
+//! panas ada di crate biner, bukan library, sehingga `benches/` criterion tidak bisa memanggilnya.
+//! Hanya dikompilasi dengan `--features bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{build_i_frame, build_s_ack, classify_apdu, hex, take_one_apdu, Frame};
+
+/// Ukuran potongan per `read()` di read loop.
+const CHUNK: usize = 4096;
+/// Berapa kali campuran frame diulang di buffer uji.
+const MIX_REPEAT: usize = 20_000;
+const S_ACK_ITERS: u32 = 5_000_000;
+
+// CP56Time2a 2024-03-15 10:20:30.500
+const T: [u8; 7] = [0x24, 0x77, 0x14, 0x0A, 0xAF, 0x03, 0x18];
+
+/// Campuran representatif satu RTU gardu: spontan status & ukur, burst GI berurutan (SQ=1),
+/// ukur bertime tag, S-ACK dan TESTFR dari RTU.
+fn frame_mix() -> Vec<Vec<u8>> {
+    let mut ns = 0u16;
+    let mut i_frame = |asdu: &[u8]| {
+        let f = build_i_frame(ns, 0, asdu);
+        ns = (ns + 1) & 0x7FFF;
+        f
+    };
+    let mut gi_burst = vec![0x0D, 0x80 | 30, 0x14, 0x00, 0x01, 0x00, 0xE8, 0x03, 0x00];
+    for i in 0..30u8 {
+        gi_burst.extend_from_slice(&(i as f32 * 1.5).to_le_bytes());
+        gi_burst.push(0x00);
+    }
+    let mut tf = vec![0x24, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD1, 0x07, 0x00, 0x00, 0x00, 0xA0, 0x40, 0x00];
+    tf.extend_from_slice(&T);
+    vec![
+        i_frame(&[0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD2, 0x07, 0x00, 0x01]),
+        i_frame(&[0x0D, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD0, 0x07, 0x00, 0x00, 0x00, 0x48, 0x42, 0x00]),
+        i_frame(&gi_burst),
+        i_frame(&tf),
+        i_frame(&[0x0B, 0x02, 0x01, 0x00, 0x01, 0x00, 0xB8, 0x0B, 0x00, 0x10, 0x27, 0x00, 0xB9, 0x0B, 0x00, 0xF0, 0xD8, 0x00]),
+        build_s_ack(1).to_vec(),
+        vec![0x68, 0x04, 0x43, 0x00, 0x00, 0x00],
+    ]
+}
+
+pub fn run() -> std::io::Result<()> {
+    let mix = frame_mix();
+    let stream: Vec<u8> = (0..MIX_REPEAT).flat_map(|_| mix.iter().flatten().copied()).collect();
+    let frames_expected = (mix.len() * MIX_REPEAT) as u64;
+    println!("Bench: {} frame ({} jenis campuran x {}), {} byte, potongan read {} byte.",
+        frames_expected, mix.len(), MIX_REPEAT, stream.len(), CHUNK);
+
+    // Pemanasan: cache & branch predictor
+    rx_path(&stream[..stream.len() / 10]);
+    let start = Instant::now();
+    let (frames, objects) = rx_path(&stream);
+    let rx = start.elapsed();
+    if frames != frames_expected {
+        return Err(std::io::Error::other(format!("bench: {} frame terpotong, diharapkan {}", frames, frames_expected)));
+    }
+    report("take+classify+decode", frames, rx, Some(stream.len()));
+    println!("      {} objek informasi didekode ({:.0} objek/s)", objects, objects as f64 / rx.as_secs_f64());
+
+    let start = Instant::now();
+    let mut nr = 0u16;
+    for _ in 0..S_ACK_ITERS {
+        black_box(build_s_ack(black_box(nr)));
+        nr = (nr + 1) & 0x7FFF;
+    }
+    report("build_s_ack", S_ACK_ITERS as u64, start.elapsed(), None);
+
+    let start = Instant::now();
+    let mut n = 0u64;
+    for f in mix.iter().cycle().take(frames_expected as usize) {
+        black_box(hex(black_box(f)));
+        n += 1;
+    }
+    report("hex (baris log RX)", n, start.elapsed(), None);
+    Ok(())
+}
+
+/// Sama seperti read loop: tambah potongan, ambil APDU utuh, drain yang terpakai.
+fn rx_path(stream: &[u8]) -> (u64, u64) {
+    let (mut frames, mut objects) = (0u64, 0u64);
+    let mut rx_buf: Vec<u8> = Vec::with_capacity(8192);
+    for chunk in stream.chunks(CHUNK) {
+        rx_buf.extend_from_slice(chunk);
+        while let Some((apdu, consumed)) = take_one_apdu(&rx_buf) {
+            // Ringkasan ASDU membawa dekode penuhnya, seperti yang dipakai read loop
+            if let Frame::I { asdu: Some(a), .. } = black_box(classify_apdu(apdu)) {
+                objects += black_box(a.full).objects.len() as u64;
+            }
+            frames += 1;
+            rx_buf.drain(0..consumed);
+        }
+    }
+    (frames, objects)
+}
+
+fn report(name: &str, ops: u64, took: Duration, bytes: Option<usize>) {
+    let secs = took.as_secs_f64();
+    println!(
+        "{:<22} {:>12.0} op/s  {:>8.1} ns/op{}",
+        name, ops as f64 / secs, secs * 1e9 / ops as f64,
+        bytes.map(|b| format!("  {:.1} MB/s", b as f64 / secs / 1e6)).unwrap_or_default()
+    );
+}
@@ -0,0 +1,427 @@
+//! Cache titik (casdu, ioa) -> nilai terakhir yang sudah diemit, plus filter deadband dan
+//! waktu sumber terakhir per casdu untuk deteksi lompatan jam RTU.
+
+use std::collections::{HashMap, HashSet};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::decode::{format_epoch_ms, InformationObject, ObjValue, Quality};
+use crate::stats::CasduStats;
+
+/// Ambang report-by-exception untuk besaran analog.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Deadband {
+    None,
+    Absolute(f64),
+    Percent(f64), // persen dari |nilai terakhir yang diemit|
+}
+
+impl Deadband {
+    /// "0.5" => Absolute(0.5), "2%" => Percent(2.0), "0"/"off" => None
+    pub fn parse(s: &str) -> Result<Deadband, String> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") { return Ok(Deadband::None); }
+        let (num, pct) = match s.strip_suffix('%') {
+            Some(n) => (n.trim(), true),
+            None => (s, false),
+        };
+        let v: f64 = num.parse().map_err(|_| format!("deadband tidak valid: '{}'", s))?;
+        if !v.is_finite() || v < 0.0 {
+            return Err(format!("deadband harus >= 0: '{}'", s));
+        }
+        Ok(match (v == 0.0, pct) {
+            (true, _) => Deadband::None,
+            (false, true) => Deadband::Percent(v),
+            (false, false) => Deadband::Absolute(v),
+        })
+    }
+
+    fn exceeded(&self, last: f64, now: f64) -> bool {
+        let delta = (now - last).abs();
+        match *self {
+            Deadband::None => delta != 0.0,
+            Deadband::Absolute(th) => delta > th,
+            Deadband::Percent(p) => delta > last.abs() * p / 100.0,
+        }
+    }
+}
+
+/// Kunci titik pada konfigurasi: casdu opsional (None = berlaku untuk semua casdu).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PointKey {
+    pub casdu: Option<u16>,
+    pub ioa: u32,
+}
+
+impl PointKey {
+    /// "1001" atau "14:1001"
+    pub fn parse(s: &str) -> Result<PointKey, String> {
+        let bad = || format!("alamat titik tidak valid: '{}'", s);
+        match s.split_once(':') {
+            Some((c, i)) => Ok(PointKey {
+                casdu: Some(c.trim().parse().map_err(|_| bad())?),
+                ioa: i.trim().parse().map_err(|_| bad())?,
+            }),
+            None => Ok(PointKey { casdu: None, ioa: s.trim().parse().map_err(|_| bad())? }),
+        }
+    }
+
+    pub fn matches(&self, casdu: u16, ioa: u32) -> bool {
+        self.ioa == ioa && self.casdu.is_none_or(|c| c == casdu)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlarmKind { High, Low }
+
+/// Alarm batas atas/bawah dengan histeresis: muncul saat melewati `set`,
+/// pulih hanya setelah kembali melewati `reset` (nilai teknik, setelah scaling).
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlarmDef {
+    pub name: String,
+    pub key: PointKey,
+    pub kind: AlarmKind,
+    pub set: f64,
+    pub reset: f64,
+}
+
+impl AlarmDef {
+    /// "14:2001, high, 90, 85" — reset opsional (= set, tanpa histeresis). Ambang persen
+    /// ("110%, 105%") relatif terhadap nominal di medan kelima: "1001, high, 110%, 105%, 20".
+    pub fn parse(name: &str, s: &str) -> Result<AlarmDef, String> {
+        let bad = |why: &str| format!("alarm '{}': {} ('{}')", name, why, s.trim());
+        let parts: Vec<&str> = s.split(',').map(str::trim).collect();
+        if !(3..=5).contains(&parts.len()) {
+            return Err(bad("harus <titik>, high|low, <set>[, <reset>[, <nominal>]]"));
+        }
+        let key = PointKey::parse(parts[0])?;
+        let kind = match parts[1] {
+            "high" => AlarmKind::High,
+            "low" => AlarmKind::Low,
+            _ => return Err(bad("jenis harus high|low")),
+        };
+        let nominal = match parts.get(4) {
+            Some(n) => Some(n.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| bad("nominal tidak valid"))?),
+            None => None,
+        };
+        let threshold = |t: &str| -> Result<f64, String> {
+            let (num, pct) = match t.strip_suffix('%') {
+                Some(n) => (n.trim(), true),
+                None => (t, false),
+            };
+            let v = num.parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(|| bad("ambang tidak valid"))?;
+            match (pct, nominal) {
+                (false, _) => Ok(v),
+                (true, Some(n)) => Ok(n * v / 100.0),
+                (true, None) => Err(bad("ambang persen butuh nominal")),
+            }
+        };
+        let set = threshold(parts[2])?;
+        let reset = match parts.get(3).filter(|p| !p.is_empty()) {
+            Some(r) => threshold(r)?,
+            None => set,
+        };
+        // Histeresis terbalik membuat alarm berkedip di antara kedua ambang
+        let ok = match kind {
+            AlarmKind::High => reset <= set,
+            AlarmKind::Low => reset >= set,
+        };
+        if !ok { return Err(bad("reset harus di sisi normal dari set")); }
+        Ok(AlarmDef { name: name.to_string(), key, kind, set, reset })
+    }
+}
+
+pub struct PointEntry {
+    pub value: ObjValue,
+    pub quality: Option<Quality>,
+    /// Update terakhir diterima, termasuk yang ditahan deadband (umur di tampilan follow).
+    pub seen: Instant,
+}
+
+pub struct PointCache {
+    points: HashMap<(u16, u32), PointEntry>,
+    default_deadband: Deadband,
+    overrides: HashMap<PointKey, Deadband>,
+    /// Alarm yang sedang aktif: (nama, casdu, ioa).
+    active_alarms: HashSet<(String, u16, u32)>,
+    /// Time tag terakhir per casdu (UTC ms) dan kapan diterima.
+    source_times: HashMap<u16, (i64, Instant)>,
+    /// SQ freeze terakhir per titik counter.
+    counter_seqs: HashMap<(u16, u32), u8>,
+    /// Titik, objek dan update terakhir per casdu (frame dihitung di statistik link).
+    casdus: BTreeMap<u16, CasduStats>,
+    pub suppressed: u64,
+    pub clock_jumps: u64,
+    pub counter_seq_gaps: u64,
+}
+
+impl PointCache {
+    pub fn new(default_deadband: Deadband, overrides: HashMap<PointKey, Deadband>) -> Self {
+        Self { points: HashMap::new(), default_deadband, overrides, active_alarms: HashSet::new(),
+            source_times: HashMap::new(), counter_seqs: HashMap::new(), casdus: BTreeMap::new(), suppressed: 0,
+            clock_jumps: 0, counter_seq_gaps: 0 }
+    }
+
+    /// Ganti ambang tanpa membuang nilai terakhir yang sudah diemit.
+    pub fn set_deadbands(&mut self, default_deadband: Deadband, overrides: HashMap<PointKey, Deadband>) {
+        self.default_deadband = default_deadband;
+        self.overrides = overrides;
+    }
+
+    fn deadband_for(&self, casdu: u16, ioa: u32) -> Deadband {
+        self.overrides.get(&PointKey { casdu: Some(casdu), ioa })
+            .or_else(|| self.overrides.get(&PointKey { casdu: None, ioa }))
+            .copied()
+            .unwrap_or(self.default_deadband)
+    }
+
+    /// Evaluasi satu alarm dengan histeresis: Some(true) = muncul, Some(false) = pulih,
+    /// None = status tidak berubah.
+    pub fn alarm_transition(&mut self, def: &AlarmDef, casdu: u16, ioa: u32, value: f64) -> Option<bool> {
+        let key = (def.name.clone(), casdu, ioa);
+        let active = self.active_alarms.contains(&key);
+        let (raise, clear) = match def.kind {
+            AlarmKind::High => (value >= def.set, value < def.reset),
+            AlarmKind::Low => (value <= def.set, value > def.reset),
+        };
+        if !active && raise {
+            self.active_alarms.insert(key);
+            Some(true)
+        } else if active && clear {
+            self.active_alarms.remove(&key);
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub fn active_alarms(&self) -> usize { self.active_alarms.len() }
+
+    pub fn casdus(&self) -> &BTreeMap<u16, CasduStats> { &self.casdus }
+
+    pub fn points(&self) -> &HashMap<(u16, u32), PointEntry> { &self.points }
+
+    /// Bandingkan time tag dengan time tag terakhir dari casdu yang sama: mundur, atau maju
+    /// melebihi waktu lokal yang berlalu lebih dari `max_jump` (None = hanya cek mundur, mis.
+    /// replay pcap yang tidak berjalan di waktu nyata). Time tag ini selalu jadi acuan berikutnya.
+    /// `now` = waktu terima lokal.
+    pub fn clock_jump(&mut self, casdu: u16, src_ms: i64, max_jump: Option<Duration>, now: Instant) -> Option<String> {
+        let (prev_ms, prev_at) = self.source_times.insert(casdu, (src_ms, now))?;
+        let src_delta = src_ms - prev_ms;
+        let local_delta = now.duration_since(prev_at).as_millis() as i64;
+        let why = if src_delta < 0 {
+            format!("mundur {:.3} s", -src_delta as f64 / 1000.0)
+        } else if max_jump.is_some_and(|j| src_delta - local_delta > j.as_millis() as i64) {
+            format!("maju {:.3} s dalam {:.3} s waktu lokal", src_delta as f64 / 1000.0, local_delta as f64 / 1000.0)
+        } else {
+            return None;
+        };
+        self.clock_jumps += 1;
+        Some(format!("{} ({} -> {})", why, format_epoch_ms(prev_ms), format_epoch_ms(src_ms)))
+    }
+
+    /// Bandingkan SQ counter dengan pembacaan terakhir titik yang sama: tiap freeze menaikkan SQ
+    /// tepat satu (modulo 32). SQ ini selalu jadi acuan berikutnya.
+    pub fn counter_seq(&mut self, casdu: u16, ioa: u32, seq: u8) -> Option<String> {
+        let prev = self.counter_seqs.insert((casdu, ioa), seq)?;
+        let why = match (seq + 32 - prev) % 32 {
+            1 => return None,
+            0 => format!("SQ tidak maju ({}), dibaca ulang tanpa freeze", seq),
+            n => format!("SQ {} -> {}: {} freeze terlewat", prev, seq, n - 1),
+        };
+        self.counter_seq_gaps += 1;
+        Some(why)
+    }
+
+    /// RTU restart: urutan SQ mulai lagi dari awal, bukan freeze yang hilang.
+    pub fn reset_counter_seqs(&mut self, casdu: u16) {
+        self.counter_seqs.retain(|(c, _), _| *c != casdu);
+    }
+
+    /// Nilai & kualitas terakhir yang diemit untuk titik ini.
+    pub fn last(&self, casdu: u16, ioa: u32) -> Option<(ObjValue, Option<Quality>)> {
+        self.points.get(&(casdu, ioa)).map(|e| (e.value, e.quality))
+    }
+
+    /// Perbarui cache; true bila nilai perlu diemit (lewat deadband atau kualitas berubah).
+    pub fn update(&mut self, casdu: u16, obj: &InformationObject) -> bool {
+        let activity = self.casdus.entry(casdu).or_default();
+        activity.updates += 1;
+        activity.last_update_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).ok();
+        if !self.points.contains_key(&(casdu, obj.ioa)) { activity.points += 1; }
+        let db = self.deadband_for(casdu, obj.ioa);
+        let emit = match self.points.get(&(casdu, obj.ioa)) {
+            None => true,
+            Some(last) if last.quality != obj.quality => true,
+            // Bergerak -> berhenti di posisi yang sama tetap harus terlihat
+            Some(last) if last.value.is_transient() != obj.value.is_transient() => true,
+            Some(last) => match (last.value.as_f64(), obj.value.as_f64()) {
+                (Some(a), Some(b)) => db.exceeded(a, b),
+                _ => last.value != obj.value,
+            },
+        };
+        let seen = Instant::now();
+        if emit {
+            self.points.insert((casdu, obj.ioa), PointEntry { value: obj.value, quality: obj.quality, seen });
+        } else if let Some(last) = self.points.get_mut(&(casdu, obj.ioa)) {
+            last.seen = seen;
+            self.suppressed += 1;
+        }
+        emit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float(ioa: u32, v: f32, quality: Quality) -> InformationObject {
+        InformationObject { ioa, value: ObjValue::Float(v), quality: Some(quality), time: None, bad_time: None }
+    }
+
+    /// Nilai berurutan ke satu titik; true = diemit.
+    fn feed(cache: &mut PointCache, casdu: u16, ioa: u32, values: &[f32]) -> Vec<bool> {
+        values.iter().map(|&v| cache.update(casdu, &float(ioa, v, Quality::default()))).collect()
+    }
+
+    #[test]
+    fn deadband_parse() {
+        assert_eq!(Deadband::parse("0.5"), Ok(Deadband::Absolute(0.5)));
+        assert_eq!(Deadband::parse(" 2 % "), Ok(Deadband::Percent(2.0)));
+        assert_eq!(Deadband::parse("0"), Ok(Deadband::None));
+        assert_eq!(Deadband::parse("off"), Ok(Deadband::None));
+        assert!(Deadband::parse("-1").is_err());
+        assert!(Deadband::parse("abc%").is_err());
+    }
+
+    #[test]
+    fn absolute_deadband_compares_against_last_emitted() {
+        let mut cache = PointCache::new(Deadband::Absolute(0.5), HashMap::new());
+        // 10.4 dan 10.5 ditahan (tidak > 0.5 dari 10.0); 10.6 lewat dan jadi acuan baru
+        assert_eq!(feed(&mut cache, 1, 100, &[10.0, 10.4, 10.5, 10.6, 10.2, 10.0]), [true, false, false, true, false, true]);
+        assert_eq!(cache.suppressed, 3);
+    }
+
+    #[test]
+    fn percent_deadband_scales_with_last_value() {
+        let mut cache = PointCache::new(Deadband::Percent(2.0), HashMap::new());
+        assert_eq!(feed(&mut cache, 1, 100, &[100.0, 101.5, 98.5, 102.5]), [true, false, false, true]);
+        // 2% dari 102.5 = 2.05
+        assert_eq!(feed(&mut cache, 1, 100, &[104.5, 104.6]), [false, true]);
+    }
+
+    #[test]
+    fn point_override_beats_ioa_override_beats_default() {
+        let overrides = HashMap::from([
+            (PointKey::parse("7:100").unwrap(), Deadband::Absolute(10.0)),
+            (PointKey::parse("100").unwrap(), Deadband::Absolute(1.0)),
+        ]);
+        let mut cache = PointCache::new(Deadband::None, overrides);
+        assert_eq!(feed(&mut cache, 7, 100, &[0.0, 5.0, 11.0]), [true, false, true]);
+        assert_eq!(feed(&mut cache, 1, 100, &[0.0, 0.5, 1.5]), [true, false, true]);
+        assert_eq!(feed(&mut cache, 1, 200, &[0.0, 0.01, 0.01]), [true, true, false]);
+    }
+
+    #[test]
+    fn quality_change_passes_deadband() {
+        let mut cache = PointCache::new(Deadband::Absolute(5.0), HashMap::new());
+        assert!(cache.update(1, &float(100, 1.0, Quality::default())));
+        assert!(cache.update(1, &float(100, 1.0, Quality { iv: true, ..Quality::default() })));
+        assert!(!cache.update(1, &float(100, 2.0, Quality { iv: true, ..Quality::default() })));
+    }
+
+    /// Nilai berurutan ke satu alarm; transisi per nilai.
+    fn alarm(cache: &mut PointCache, def: &AlarmDef, values: &[f64]) -> Vec<Option<bool>> {
+        values.iter().map(|&v| cache.alarm_transition(def, 1, 2001, v)).collect()
+    }
+
+    #[test]
+    fn high_alarm_hysteresis_without_chatter() {
+        let def = AlarmDef::parse("trafo", "1:2001, high, 90, 85").unwrap();
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        let got = alarm(&mut cache, &def, &[80.0, 90.0, 87.0, 91.0, 85.0, 89.9, 84.9, 88.0, 89.9, 90.0]);
+        assert_eq!(got, [None, Some(true), None, None, None, None, Some(false), None, None, Some(true)]);
+        assert_eq!(cache.active_alarms(), 1);
+    }
+
+    #[test]
+    fn low_alarm_hysteresis_without_chatter() {
+        let def = AlarmDef::parse("tegangan", "2001, low, 18, 20").unwrap();
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        let got = alarm(&mut cache, &def, &[21.0, 18.0, 19.0, 17.0, 20.0, 20.1, 19.5, 18.1, 18.0]);
+        assert_eq!(got, [None, Some(true), None, None, None, Some(false), None, None, Some(true)]);
+    }
+
+    #[test]
+    fn alarm_parse_rejects_inverted_hysteresis_and_resolves_percent() {
+        assert!(AlarmDef::parse("x", "1001, high, 85, 90").is_err());
+        assert!(AlarmDef::parse("x", "1001, low, 20, 18").is_err());
+        let def = AlarmDef::parse("x", "1001, high, 110%, 105%, 20").unwrap();
+        assert_eq!((def.set, def.reset), (22.0, 21.0));
+        // Tanpa reset: set = reset, pulih begitu di bawah set
+        let def = AlarmDef::parse("x", "1001, high, 90").unwrap();
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        assert_eq!(alarm(&mut cache, &def, &[90.0, 89.99]), [Some(true), Some(false)]);
+    }
+
+    #[test]
+    fn clock_jump_flags_forward_and_backward_but_not_normal_steps() {
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        let max = Some(Duration::from_secs(60));
+        let base = Instant::now();
+        let at = |s: u64| base + Duration::from_secs(s);
+        let t0 = 1_710_000_000_000i64;
+        assert_eq!(cache.clock_jump(1, t0, max, at(0)), None, "acuan pertama");
+        // Langkah normal: jam RTU maju seiring waktu lokal
+        assert_eq!(cache.clock_jump(1, t0 + 10_000, max, at(10)), None);
+        // Maju lebih cepat dari lokal tetapi dalam batas
+        assert_eq!(cache.clock_jump(1, t0 + 70_000, max, at(20)), None);
+        // Maju 1 jam dalam 1 detik lokal
+        let fwd = cache.clock_jump(1, t0 + 70_000 + 3_600_000, max, at(21)).unwrap();
+        assert!(fwd.starts_with("maju 3600.000 s dalam 1.000 s"), "{}", fwd);
+        // Mundur 5 menit
+        let back = cache.clock_jump(1, t0 + 70_000 + 3_300_000, max, at(22)).unwrap();
+        assert!(back.starts_with("mundur 300.000 s"), "{}", back);
+        assert_eq!(cache.clock_jumps, 2);
+        // Casdu lain punya acuannya sendiri
+        assert_eq!(cache.clock_jump(2, t0, max, at(23)), None);
+    }
+
+    #[test]
+    fn clock_jump_without_max_only_checks_backward() {
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        let base = Instant::now();
+        assert_eq!(cache.clock_jump(1, 0, None, base), None);
+        assert_eq!(cache.clock_jump(1, 86_400_000, None, base), None);
+        assert!(cache.clock_jump(1, 86_399_999, None, base).is_some());
+    }
+
+    #[test]
+    fn counter_seq_in_order_gap_repeat_and_wrap() {
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        let mut seq = |s: u8| cache.counter_seq(1, 4001, s);
+        assert_eq!(seq(29), None, "acuan pertama");
+        assert_eq!(seq(30), None);
+        assert_eq!(seq(31), None);
+        assert_eq!(seq(0), None, "31 -> 0 = wrap modulo 32");
+        assert_eq!(seq(3).as_deref(), Some("SQ 0 -> 3: 2 freeze terlewat"));
+        assert_eq!(seq(3).as_deref(), Some("SQ tidak maju (3), dibaca ulang tanpa freeze"));
+        assert_eq!(seq(30).as_deref(), Some("SQ 3 -> 30: 26 freeze terlewat"));
+        assert_eq!(seq(1).as_deref(), Some("SQ 30 -> 1: 2 freeze terlewat"), "celah melintasi wrap");
+        assert_eq!(cache.counter_seq_gaps, 4);
+    }
+
+    #[test]
+    fn counter_seq_is_per_point_and_reset_on_restart() {
+        let mut cache = PointCache::new(Deadband::None, HashMap::new());
+        assert_eq!(cache.counter_seq(1, 4001, 5), None);
+        assert_eq!(cache.counter_seq(1, 4002, 9), None);
+        assert_eq!(cache.counter_seq(2, 4001, 20), None);
+        assert_eq!(cache.counter_seq(1, 4001, 6), None);
+        // Restart RTU casdu 1: SQ mulai lagi tanpa dianggap celah; casdu 2 tetap
+        cache.reset_counter_seqs(1);
+        assert_eq!(cache.counter_seq(1, 4001, 0), None);
+        assert_eq!(cache.counter_seq(1, 4002, 0), None);
+        assert!(cache.counter_seq(2, 4001, 0).is_some());
+    }
+}
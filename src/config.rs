@@ -0,0 +1,1887 @@
+//! Konfigurasi runtime: argumen CLI + berkas situs (format INI sederhana, satu per RTU).
+//! Berkas dimuat lebih dulu (`Config::load_site`), opsi CLI menimpanya. Kesalahan sintaks,
+//! nilai, dan referensi silang antarseksi dilaporkan sekaligus, bukan berhenti di yang pertama.
+//!
+//! Contoh berkas:
+//! ```text
+//! # komentar
+//! [rtu]
+//! addr = 10.20.1.14:2404   # RTU tujuan (host:port); --rtu menimpa
+//! bind = 10.20.1.2         # alamat lokal sumber koneksi (opsional)
+//!
+//! [deadband]
+//! default = 0.5      # absolut
+//! 1001 = 2%          # per IOA (semua casdu), persen
+//! 14:2001 = 0.1      # per casdu:ioa
+//!
+//! [output]
+//! format = json      # text | json | csv | influx
+//! origin = spontaneous, interrogated
+//! annotate_hex = true  # hex RX dipecah per medan APCI/ASDU
+//! hexdump = true       # hex RX sebagai hexdump (offset | hex | ASCII)
+//! pretty = true        # tiap frame sebagai pohon bertingkat (commissioning)
+//! log_time = iso       # prefiks waktu host tiap baris log: iso (default) | epoch_ms | off
+//! log_format = json    # log operasional: text (default) | json (satu objek per kejadian)
+//! log_stream = stderr # tujuan log: stdout (default) | stderr (stdout hanya data) | auto
+//! log_changes_only = true  # jejak frame hanya bila ada perubahan / alarm / kualitas buruk
+//! invalid_as_null = true  # besaran terukur IV/OV -> null (Influx "NaN") di json/csv/influx
+//!
+//! [csv]
+//! delimiter = ;        # , ; | tab (default ,)
+//! quote = all          # minimal (RFC 4180, default) | all
+//! decimal = comma      # point (default) | comma
+//! timestamp = epoch_ms # kolom time_utc: iso (default) | epoch_ms | epoch_s
+//!
+//! [filter]
+//! casdu = 14             # hanya casdu ini (daftar dipisah koma)
+//! ioa_range = 1000-1999  # hanya IOA dalam rentang (daftar dipisah koma)
+//!
+//! [time]
+//! max_skew = 3600    # detik; off = nonaktif
+//! max_jump = 60      # detik; waktu sumber per casdu mundur / maju > ini melebihi jam lokal; off = nonaktif
+//! utc_offset = +07:00  # zona waktu standar RTU
+//! su_adds_hour = true  # bit SU=1 => offset + 1 jam
+//!
+//! [counters]
+//! check_seq = true   # peringatkan bila SQ integrated total (type 15/37) per IOA tidak naik satu
+//!                    # antar pembacaan: siklus freeze terlewat (default false)
+//!
+//! [stations]
+//! 14 = Feeder-3      # casdu -> nama gardu
+//!
+//! [points]
+//! 14:1001 = CB Q1    # casdu:ioa (atau ioa saja) -> nama titik
+//!                    # bila [points] dipakai, [scaling]/[alarms]/[deadband] per titik harus
+//!                    # merujuk titik bernama; bila [stations] dipakai, casdu harus terdaftar
+//!
+//! [scaling]
+//! 14:1001 = 0.015259, 0, kV   # value_eng = raw * scale + offset, satuan opsional
+//! 2001 = 1.0, -40, degC       # ioa saja = semua casdu
+//!
+//! [alarms]
+//! overtemp = 2001, high, 90, 85      # <nama> = <titik>, high|low, set[, reset] (nilai teknik)
+//! undervolt = 14:1001, low, 90%, 95%, 20  # persen dari nominal (medan kelima)
+//!
+//! [files]
+//! dir = /var/lib/iec104/files   # tujuan berkas dari RTU (F_FR..F_LS); butuh enable_tx
+//! fetch = 1:5000:2               # casdu:ioa:nof ditarik setelah STARTDT con (daftar dipisah koma)
+//! auto_fetch = true              # tarik berkas yang diumumkan direktori spontan (F_DR_TA_1)
+//!
+//! [sinks]
+//! soe = json:/var/log/iec104/soe.jsonl   # <nama> = <format>:<path>
+//! bulk = csv:/var/log/iec104/all.csv, batch=500, interval=2  # flush per 500 titik / 2 detik
+//!
+//! [delivery]
+//! queue = 10000      # kapasitas antrean ke thread sink; penuh = titik dibuang & dihitung
+//! retries = 2        # percobaan ulang tulis sink yang gagal
+//! backoff_ms = 200   # jeda awal retry, berlipat ganda tiap percobaan
+//! dead_letter = /var/log/iec104/dead.jsonl  # titik yang tetap gagal (JSON lines)
+//! shutdown_timeout = 10  # detik (boleh pecahan) menguras antrean & buffer batch saat berhenti
+//!
+//! [rotate]
+//! max_size = 10M     # rotasi berkas sink per ukuran (K/M/G); off = mati
+//! gzip = true        # kompres segmen lama ke .gz di latar (default false)
+//!
+//! [events]
+//! log = /var/log/iec104/events.log   # transisi titik status, perubahan IV, alarm
+//! link_log = /var/log/iec104/link.jsonl  # siklus hidup koneksi (JSON lines) untuk alarm NOC
+//!
+//! [tcp]
+//! read_timeout = 10     # detik; juga irama cek t2/watchdog saat idle (wajib > 0)
+//! write_timeout = off   # detik; off = blokir tanpa batas
+//! nodelay = true        # TCP_NODELAY
+//! keepalive_idle = 30   # detik tanpa lalu lintas sebelum probe SO_KEEPALIVE; off = mati (default)
+//! keepalive_interval = 5  # detik antar probe (default bawaan OS)
+//! keepalive_count = 3   # probe tak berbalas sebelum koneksi dinyatakan mati (default bawaan OS)
+//! max_buffer = 64K      # batas buffer RX; lebih = sampah dibuang sampai 0x68 berikutnya
+//! max_session = 6h      # STOPDT, putus & sambung ulang terjadwal setelah sesi selama ini; butuh enable_tx
+//!                       # (s/m/h/d, tanpa satuan = detik); off = mati (default)
+//!
+//! [tx]
+//! ack_mode = coalesced  # alias lama [ack] mode
+//! max_fps = 5        # batas frame keluar per detik (0 = tanpa batas)
+//! min_gap_ms = 100   # jeda minimum antar frame keluar
+//! max_outstanding = 12  # k: maks I-frame keluar belum di-ACK
+//! passive = true     # sniffer SPAN/tap: nol TX (tanpa STARTDT, S-ACK, TESTFR con)
+//! decode_only = true # dekode saja: tanpa S-ACK/koalescing/jendela (implisit pada passive)
+//! startdt = false    # listen-only: tanpa STARTDT act, tetap S-ACK (gateway yang memulai aliran sendiri)
+//! enable_tx = true   # buka jalur kirim (GI, TESTFR, STOPDT) pada build ACK-only; sama dgn --enable-tx
+//! auto_gi = true     # GI otomatis tiap STARTDT con (termasuk setelah reconnect) & saat watchdog no-data; butuh enable_tx
+//! originator = 3     # OA kita (0..255): ditulis ke ASDU keluar; balasan ber-OA lain tidak dicocokkan
+//! forbid_types = 45, 46, 47, 48  # tipe ASDU keluar yang diblok (default 45, 46, 47)
+//! # allow_types = 100, 103   # atau: HANYA tipe ini yang boleh keluar (eksklusif dgn forbid_types)
+//!
+//! [ack]
+//! mode = coalesced   # coalesced (w/t2/darurat) | immediate (S-ACK per I-frame)
+//! k = 12             # perkiraan k sisi RTU (jendela kirimnya sebelum menunggu ACK kita)
+//! w = 8              # S-ACK setelah w I-frame belum di-ACK (1..k)
+//! t2 = 10            # detik (boleh pecahan, mis. 0.5); S-ACK paling lambat t2 setelah I-frame pertama
+//! emergency = 0.85   # S-ACK darurat bila I-frame belum di-ACK mencapai fraksi k ini (0 < f <= 1)
+//!
+//! [health]
+//! addr = 0.0.0.0:8080    # /healthz & /readyz untuk probe orkestrasi, /metrics statistik koneksi
+//!
+//! [metrics]
+//! addr = 10.0.0.5:9104   # /metrics di listener sendiri (NIC/port lain); off = ikut [health]
+//!
+//! [debug]
+//! frame_history = 32     # N APDU terakhir dicetak saat error/putus (0 = mati)
+//! dump_trigger = /tmp/iec104.dump  # touch berkas ini untuk dump riwayat (atau kill -USR1 <pid>)
+//! stop_trigger = /run/iec104.stop  # touch berkas ini untuk berhenti rapi (sink dikuras dulu)
+//!
+//! [follow]
+//! enabled = true         # tabel titik digambar ulang di tempat (seperti top), log tidak menggulung
+//! points = 14:1001, 2001 # titik yang ditampilkan (ioa saja = semua casdu)
+//! casdu = 14             # plus semua titik casdu ini; points & casdu kosong = semua titik
+//! interval = 1           # detik (boleh pecahan) antar gambar ulang
+//!
+//! [parse]
+//! mode = strict          # lenient (default: catat & lanjut) | strict (tolak frame menyimpang)
+//!                        # | strict-disconnect (pelanggaran pertama menutup koneksi)
+//!
+//! [asdu]
+//! layout = legacy-101-tunneled  # preset panjang medan alamat: standard-104 (default) | siemens
+//!                               # | abb | legacy-101-tunneled (COT 1, CASDU 1, IOA 2)
+//! ioa_len = 3        # timpa satu medan dari preset: cot_len 1..2, casdu_len 1..2, ioa_len 1..3
+//!
+//! [watchdog]
+//! no_data_timeout = 60   # detik; off = mati
+//! no_data_action = gi    # log | reconnect | gi
+//!
+//! [simulate]
+//! listen = 0.0.0.0:2404  # subperintah simulate: RTU simulasi untuk uji sink/dashboard hilir
+//! casdu = 1
+//! points = 100:sp, 200:dp, 2000:float:230.5, 3000:scaled, 70:counter  # ioa:jenis[:nilai awal]
+//!                        # jenis: sp | dp | normalized | scaled | float | counter
+//! cycle = 2              # detik (boleh pecahan) antar ukur siklik; 0 = mati
+//! event_rate = 1         # rata-rata kejadian spontan bertime tag per detik; 0 = mati
+//! seed = 42              # lalu lintas acak yang sama tiap jalan (default dari jam)
+//!
+//! [testfr]
+//! enabled = true     # supervisi link saat idle (butuh TX: [tx] enable_tx pada build ACK-only)
+//! t3 = 20            # detik (boleh pecahan) tanpa frame masuk sebelum TESTFR act
+//! t1 = 15            # detik menunggu TESTFR con
+//! retries = 1        # TESTFR act tambahan tanpa con sebelum putus & sambung ulang
+//! ```
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime};
+
+use crate::cache::{AlarmDef, Deadband, PointKey};
+use crate::decode::{AsduLayout, Origin, RtuClock};
+use crate::filetx::FileRequest;
+use crate::logging::{LogFormat, LogStream, LogTime};
+use crate::output::{CsvOptions, OutputFormat, OutputOptions};
+use crate::simulate::SimPoint;
+use crate::sink::{RetryPolicy, Rotation, SinkSpec};
+
+/// Strategi S-ACK untuk I-frame yang diterima.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckMode {
+    /// ACK tiap I-frame (efektif w=1, tanpa tunggu t2) — untuk RTU/proxy yang menolak koalescing.
+    Immediate,
+    /// Koalescing Siemens: ACK saat w tercapai, t2 habis, atau jendela k hampir penuh.
+    Coalesced,
+}
+
+impl AckMode {
+    fn parse(s: &str) -> Result<AckMode, String> {
+        match s.trim() {
+            "immediate" => Ok(AckMode::Immediate),
+            "coalesced" => Ok(AckMode::Coalesced),
+            other => Err(format!("mode ACK tidak dikenal: '{}' (immediate|coalesced)", other)),
+        }
+    }
+}
+
+/// Ambang koalescing S-ACK untuk I-frame dari RTU. Default = nilai umum Siemens (k=12, w=8,
+/// t2=10 s); vendor lain (ABB, GE, Schneider) sering mengharapkan w/t2 berbeda.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AckPolicy {
+    pub mode: AckMode,
+    /// Perkiraan k sisi RTU: I-frame yang boleh dikirimnya sebelum menunggu ACK kita.
+    pub k: u16,
+    /// S-ACK setelah w I-frame diterima tanpa ACK.
+    pub w: u16,
+    /// S-ACK paling lambat t2 setelah I-frame pertama yang belum di-ACK.
+    pub t2: Duration,
+    /// S-ACK darurat bila I-frame belum di-ACK mencapai fraksi k ini.
+    pub emergency: f64,
+}
+
+impl Default for AckPolicy {
+    fn default() -> Self {
+        AckPolicy { mode: AckMode::Coalesced, k: 12, w: 8, t2: Duration::from_secs(10), emergency: 0.85 }
+    }
+}
+
+impl AckPolicy {
+    /// Jumlah I-frame belum di-ACK yang memicu S-ACK darurat (1..=k).
+    pub fn emergency_at(&self) -> u16 {
+        ((self.k as f64 * self.emergency).floor() as u16).clamp(1, self.k)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.w == 0 || self.w > self.k {
+            return Err(format!("ACK w={} harus 1..k (k={})", self.w, self.k));
+        }
+        // IEC 60870-5-104 5.1: t2 < t1, agar RTU tidak menutup koneksi sebelum ACK kita tiba
+        if self.t2.is_zero() || self.t2 >= crate::T1 {
+            return Err(format!("ACK t2={:?} harus > 0 dan < t1 ({:?})", self.t2, crate::T1));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for AckPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.mode {
+            AckMode::Immediate => write!(f, "immediate (S-ACK per I-frame)"),
+            AckMode::Coalesced => write!(
+                f, "coalesced k={} w={} t2={:?} darurat pada {} I-frame ({:.0}% k)",
+                self.k, self.w, self.t2, self.emergency_at(), self.emergency * 100.0
+            ),
+        }
+    }
+}
+
+/// Supervisi link saat idle (IEC 60870-5-104 5.2): t3 tanpa frame masuk -> TESTFR act; tanpa
+/// con dalam t1 kirim lagi sampai `retries` kali; tetap diam -> putus & sambung ulang. Tanpa
+/// ini link TCP yang hidup tetapi RTU-nya bisu bisa menggantung selamanya.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TestFrPolicy {
+    pub enabled: bool,
+    pub t3: Duration,
+    pub t1: Duration,
+    pub retries: u8,
+}
+
+impl Default for TestFrPolicy {
+    fn default() -> Self {
+        // Mati: TESTFR act butuh jalur kirim terbuka (--enable-tx)
+        TestFrPolicy { enabled: false, t3: Duration::from_secs(20), t1: Duration::from_secs(15), retries: 1 }
+    }
+}
+
+impl TestFrPolicy {
+    fn validate(&self) -> Result<(), String> {
+        if self.t3.is_zero() || self.t1.is_zero() {
+            return Err(format!("TESTFR t3={:?} dan t1={:?} harus > 0", self.t3, self.t1));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for TestFrPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !self.enabled { return write!(f, "mati"); }
+        write!(f, "t3={:?} t1={:?}, {} kirim ulang sebelum sambung ulang", self.t3, self.t1, self.retries)
+    }
+}
+
+/// SO_KEEPALIVE TCP: kernel mendeteksi peer mati (kabel putus, NAT lupa) lebih cepat dari timer
+/// aplikasi. `idle` None = keepalive mati (default, seperti sebelumnya); `interval`/`count` None =
+/// bawaan OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct KeepalivePolicy {
+    pub idle: Option<Duration>,
+    pub interval: Option<Duration>,
+    pub count: Option<u32>,
+}
+
+impl KeepalivePolicy {
+    fn validate(&self) -> Result<(), String> {
+        if self.idle.is_none() && (self.interval.is_some() || self.count.is_some()) {
+            return Err("keepalive_interval/keepalive_count butuh keepalive_idle".into());
+        }
+        if self.idle.is_some_and(|d| d.is_zero()) || self.interval.is_some_and(|d| d.is_zero()) {
+            return Err(format!("keepalive idle={:?} dan interval={:?} harus > 0", self.idle, self.interval));
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for KeepalivePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(idle) = self.idle else { return write!(f, "mati") };
+        let os = |v: Option<String>| v.unwrap_or_else(|| "bawaan OS".into());
+        write!(f, "idle={:?} interval={} count={}", idle, os(self.interval.map(|d| format!("{:?}", d))), os(self.count.map(|n| n.to_string())))
+    }
+}
+
+/// Tampilan follow: tabel titik terpilih (nilai terakhir dari cache, kualitas, umur) yang
+/// digambar ulang di tempat tiap `interval`. Tanpa `points` dan `casdus` semua titik tampil.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FollowPolicy {
+    pub enabled: bool,
+    pub points: Vec<PointKey>,
+    pub casdus: Vec<u16>,
+    pub interval: Duration,
+}
+
+impl Default for FollowPolicy {
+    fn default() -> Self {
+        FollowPolicy { enabled: false, points: Vec::new(), casdus: Vec::new(), interval: Duration::from_secs(1) }
+    }
+}
+
+impl FollowPolicy {
+    pub fn selects(&self, casdu: u16, ioa: u32) -> bool {
+        (self.points.is_empty() && self.casdus.is_empty())
+            || self.casdus.contains(&casdu)
+            || self.points.iter().any(|k| k.matches(casdu, ioa))
+    }
+}
+
+/// Subperintah `simulate`: RTU simulasi di `listen` dengan set titik, irama siklik dan laju
+/// kejadian spontan. `seed` tetap = lalu lintas yang sama tiap jalan (uji hilir yang bisa diulang).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimulatePolicy {
+    pub enabled: bool,
+    pub listen: String,
+    pub casdu: u16,
+    pub points: Vec<SimPoint>,
+    /// Jeda ukur siklik (COT 1); nol = mati.
+    pub cycle: Duration,
+    /// Rata-rata kejadian spontan per detik; 0 = mati.
+    pub event_rate: f64,
+    pub seed: Option<u64>,
+}
+
+impl Default for SimulatePolicy {
+    fn default() -> Self {
+        SimulatePolicy {
+            enabled: false, listen: "0.0.0.0:2404".into(), casdu: 1, points: SimPoint::defaults(),
+            cycle: Duration::from_secs(2), event_rate: 1.0, seed: None,
+        }
+    }
+}
+
+fn parse_sim_points(s: &str) -> Result<Vec<SimPoint>, String> {
+    let points: Vec<SimPoint> = s.split(',').filter(|t| !t.trim().is_empty()).map(SimPoint::parse).collect::<Result<_, _>>()?;
+    if points.is_empty() { return Err("daftar titik simulasi kosong".into()); }
+    Ok(points)
+}
+
+fn parse_sim_casdu(s: &str) -> Result<u16, String> {
+    s.trim().parse().map_err(|_| format!("casdu tidak valid: '{}'", s.trim()))
+}
+
+fn parse_seed(s: &str) -> Result<u64, String> {
+    s.trim().parse().map_err(|_| format!("seed tidak valid: '{}'", s.trim()))
+}
+
+/// Sikap terhadap frame yang menyimpang dari standar. Pemeriksaan yang sama di kedua mode;
+/// bedanya apa yang terjadi pada frame:
+///
+/// | Pemeriksaan                               | lenient (default)             | strict                 |
+/// |-------------------------------------------|-------------------------------|------------------------|
+/// | APCI tidak bisa diklasifikasi             | peringatan, dilewati          | error                  |
+/// | U-frame dengan kontrol tidak standar      | peringatan (banjir = putus)   | error                  |
+/// | I-frame tanpa ASDU utuh                   | peringatan                    | error                  |
+/// | ASDU pendek / lebih panjang dari VSQ      | objek yang utuh tetap dipakai | error, isi ditolak     |
+/// | ASDU malformed / objek tidak bisa didekode| dilewati, sisanya dipakai     | error, isi ditolak     |
+/// | type id tanpa decoder                     | peringatan                    | error, isi ditolak     |
+/// | N(S) celah (hanya pasif)                  | peringatan, ikut urutan RTU   | error, isi ditolak     |
+/// | N(R) RTU mundur / melompat                | peringatan                    | error (I-frame: isi ditolak) |
+///
+/// "Isi ditolak": objek tidak masuk cache, handler, sink, GI maupun transfer berkas, tetapi
+/// frame tetap dihitung untuk N(R)/ACK agar urutan tidak rusak. Di mode aktif N(S) celah dan
+/// N(R) di luar jendela selalu memutus koneksi (wajib menurut standar), di kedua mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseMode {
+    Lenient,
+    Strict,
+    /// Strict, dan pelanggaran pertama menutup koneksi (uji kesesuaian).
+    StrictDisconnect,
+}
+
+impl ParseMode {
+    fn parse(s: &str) -> Result<ParseMode, String> {
+        match s.trim() {
+            "lenient" => Ok(ParseMode::Lenient),
+            "strict" => Ok(ParseMode::Strict),
+            "strict-disconnect" => Ok(ParseMode::StrictDisconnect),
+            other => Err(format!("mode parsing tidak dikenal: '{}' (lenient|strict|strict-disconnect)", other)),
+        }
+    }
+
+    pub fn strict(self) -> bool { self != ParseMode::Lenient }
+}
+
+/// Preset panjang medan alamat ASDU (COT/CASDU/IOA) per vendor atau profil. SICAM (Siemens)
+/// dan RTU560 (ABB) memakai profil standar 104 di jalur TCP; namanya tetap diterima agar
+/// berkas situs mencatat perangkat yang dimaksud. Gateway yang meneruskan ASDU 101 apa adanya
+/// ke dalam 104 membawa medan pendek 101.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutPreset {
+    Standard104,
+    Siemens,
+    Abb,
+    Legacy101Tunneled,
+}
+
+impl LayoutPreset {
+    fn parse(s: &str) -> Result<LayoutPreset, String> {
+        match s.trim() {
+            "standard-104" => Ok(LayoutPreset::Standard104),
+            "siemens" => Ok(LayoutPreset::Siemens),
+            "abb" => Ok(LayoutPreset::Abb),
+            "legacy-101-tunneled" => Ok(LayoutPreset::Legacy101Tunneled),
+            other => Err(format!(
+                "preset tata letak ASDU tidak dikenal: '{}' (standard-104|siemens|abb|legacy-101-tunneled)", other
+            )),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LayoutPreset::Standard104 => "standard-104",
+            LayoutPreset::Siemens => "siemens",
+            LayoutPreset::Abb => "abb",
+            LayoutPreset::Legacy101Tunneled => "legacy-101-tunneled",
+        }
+    }
+
+    pub fn layout(self) -> AsduLayout {
+        match self {
+            LayoutPreset::Standard104 | LayoutPreset::Siemens | LayoutPreset::Abb => AsduLayout::IEC104,
+            LayoutPreset::Legacy101Tunneled => AsduLayout { cot: 1, casdu: 1, ioa: 2 },
+        }
+    }
+}
+
+/// Tata letak ASDU untuk dekode RX: preset, lalu medan yang ditimpa satu per satu
+/// (`[asdu] cot_len` / `--cot-len` dan seterusnya).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsduLayoutPolicy {
+    pub preset: LayoutPreset,
+    pub cot: Option<usize>,
+    pub casdu: Option<usize>,
+    pub ioa: Option<usize>,
+}
+
+impl Default for AsduLayoutPolicy {
+    fn default() -> Self {
+        AsduLayoutPolicy { preset: LayoutPreset::Standard104, cot: None, casdu: None, ioa: None }
+    }
+}
+
+impl AsduLayoutPolicy {
+    pub fn layout(&self) -> AsduLayout {
+        let base = self.preset.layout();
+        AsduLayout {
+            cot: self.cot.unwrap_or(base.cot),
+            casdu: self.casdu.unwrap_or(base.casdu),
+            ioa: self.ioa.unwrap_or(base.ioa),
+        }
+    }
+
+    /// Ada medan yang ditimpa dari preset?
+    pub fn overridden(&self) -> bool { self.layout() != self.preset.layout() }
+
+    fn validate(&self) -> Result<(), String> {
+        self.layout().validate().map_err(|e| format!("tata letak ASDU {}: {}", self.preset.name(), e))
+    }
+}
+
+impl std::fmt::Display for AsduLayoutPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let l = self.layout();
+        write!(f, "{}{}: COT {} byte, CASDU {} byte, IOA {} byte",
+            self.preset.name(), if self.overridden() { " (ditimpa)" } else { "" }, l.cot, l.casdu, l.ioa)
+    }
+}
+
+/// Gatekeeper tipe ASDU keluar: daftar terlarang, atau daftar izin (selain itu diblok).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypePolicy {
+    Forbid(Vec<u8>),
+    Allow(Vec<u8>),
+}
+
+impl Default for TypePolicy {
+    fn default() -> Self { TypePolicy::Forbid(crate::FORBIDDEN_TYPE_IDS.to_vec()) }
+}
+
+impl TypePolicy {
+    pub fn permits(&self, type_id: u8) -> bool {
+        match self {
+            TypePolicy::Forbid(ids) => !ids.contains(&type_id),
+            TypePolicy::Allow(ids) => ids.contains(&type_id),
+        }
+    }
+
+    /// "45, 46" -> daftar tipe; hanya tipe yang didefinisikan IEC 60870-5-104.
+    fn parse_list(s: &str) -> Result<Vec<u8>, String> {
+        let mut ids = Vec::new();
+        for t in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            let id: u8 = t.parse().map_err(|_| format!("type id tidak valid: '{}'", t))?;
+            if !is_iec104_type(id) {
+                return Err(format!("type id {} tidak didefinisikan IEC 60870-5-104", id));
+            }
+            if !ids.contains(&id) { ids.push(id); }
+        }
+        Ok(ids)
+    }
+}
+
+impl std::fmt::Display for TypePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (kind, ids) = match self {
+            TypePolicy::Forbid(ids) => ("blok", ids),
+            TypePolicy::Allow(ids) => ("izinkan hanya", ids),
+        };
+        let list: Vec<String> = ids.iter().map(u8::to_string).collect();
+        write!(f, "{} [{}]", kind, list.join(", "))
+    }
+}
+
+/// Tipe ASDU yang didefinisikan standar (IEC 60870-5-101/104, kompatibel 104).
+fn is_iec104_type(id: u8) -> bool {
+    matches!(id, 1 | 3 | 5 | 7 | 9 | 11 | 13 | 15 | 20 | 21 | 30..=40 | 45..=51 | 58..=64 | 70 | 100..=107 | 110..=113 | 120..=127)
+}
+
+/// Tindakan saat watchdog "tidak ada data" terpicu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoDataAction { Log, Reconnect, Gi }
+
+impl NoDataAction {
+    fn parse(s: &str) -> Result<NoDataAction, String> {
+        match s.trim() {
+            "log" => Ok(NoDataAction::Log),
+            "reconnect" => Ok(NoDataAction::Reconnect),
+            "gi" => Ok(NoDataAction::Gi),
+            other => Err(format!("aksi watchdog tidak dikenal: '{}' (log|reconnect|gi)", other)),
+        }
+    }
+}
+
+/// Nama manusiawi untuk casdu dan titik, dipakai di log & keluaran.
+#[derive(Debug, Default, PartialEq)]
+pub struct Names {
+    stations: HashMap<u16, String>,
+    points: HashMap<PointKey, String>,
+}
+
+impl Names {
+    pub fn station(&self, casdu: u16) -> Option<&str> {
+        self.stations.get(&casdu).map(String::as_str)
+    }
+
+    /// casdu:ioa spesifik lebih dulu, lalu ioa untuk semua casdu.
+    pub fn point(&self, casdu: u16, ioa: u32) -> Option<&str> {
+        self.points
+            .get(&PointKey { casdu: Some(casdu), ioa })
+            .or_else(|| self.points.get(&PointKey { casdu: None, ioa }))
+            .map(String::as_str)
+    }
+
+    /// Nama untuk kunci konfigurasi; "ioa saja" hanya cocok dengan entri "ioa saja".
+    pub fn point_for(&self, k: PointKey) -> Option<&str> {
+        match k.casdu {
+            Some(c) => self.point(c, k.ioa),
+            None => self.points.get(&k).map(String::as_str),
+        }
+    }
+
+    /// Ada nama untuk alamat ini; "ioa saja" cukup dinamai di salah satu casdu.
+    fn has_point(&self, k: PointKey) -> bool {
+        match k.casdu {
+            Some(c) => self.point(c, k.ioa).is_some(),
+            None => self.points.keys().any(|p| p.ioa == k.ioa),
+        }
+    }
+}
+
+/// Transformasi linear raw -> satuan teknik untuk satu titik.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scaling {
+    pub scale: f64,
+    pub offset: f64,
+    pub unit: Option<String>,
+}
+
+impl Scaling {
+    /// "0.015259, 0, kV" atau "0.015259" (offset 0, tanpa satuan)
+    fn parse(s: &str) -> Result<Scaling, String> {
+        let bad = || format!("scaling tidak valid: '{}' (scale[, offset[, satuan]])", s.trim());
+        let mut parts = s.split(',').map(str::trim);
+        let scale: f64 = parts.next().filter(|p| !p.is_empty()).ok_or_else(bad)?.parse().map_err(|_| bad())?;
+        let offset: f64 = match parts.next() {
+            Some(p) if !p.is_empty() => p.parse().map_err(|_| bad())?,
+            _ => 0.0,
+        };
+        let unit = parts.next().filter(|p| !p.is_empty()).map(String::from);
+        if !scale.is_finite() || !offset.is_finite() || parts.next().is_some() { return Err(bad()); }
+        Ok(Scaling { scale, offset, unit })
+    }
+
+    pub fn apply(&self, raw: f64) -> f64 { raw * self.scale + self.offset }
+}
+
+/// Tabel scaling per titik; titik tanpa entri memakai identitas (scale 1, offset 0).
+#[derive(Debug, Default, PartialEq)]
+pub struct ScalingTable {
+    entries: HashMap<PointKey, Scaling>,
+}
+
+impl ScalingTable {
+    /// casdu:ioa spesifik lebih dulu, lalu ioa untuk semua casdu.
+    pub fn get(&self, casdu: u16, ioa: u32) -> Option<&Scaling> {
+        self.entries
+            .get(&PointKey { casdu: Some(casdu), ioa })
+            .or_else(|| self.entries.get(&PointKey { casdu: None, ioa }))
+    }
+}
+
+/// Filter alamat setelah dekode; kosong = semua lolos. ACK link layer tidak terpengaruh.
+#[derive(Debug, Default, PartialEq)]
+pub struct AddressFilter {
+    pub casdus: Vec<u16>,
+    pub ioa_ranges: Vec<(u32, u32)>,
+}
+
+impl AddressFilter {
+    pub fn matches(&self, casdu: u16, ioa: u32) -> bool {
+        (self.casdus.is_empty() || self.casdus.contains(&casdu))
+            && (self.ioa_ranges.is_empty() || self.ioa_ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&ioa)))
+    }
+}
+
+pub struct Config {
+    /// Alamat RTU tujuan (host:port).
+    pub rtu_addr: String,
+    /// Alamat lokal sumber koneksi (host multi-NIC); port 0 = bebas.
+    pub bind: Option<SocketAddr>,
+    /// Timeout baca socket; sekaligus batas jeda antar cek t2/t1/watchdog saat link sepi.
+    pub read_timeout: Duration,
+    pub write_timeout: Option<Duration>,
+    pub nodelay: bool,
+    /// SO_KEEPALIVE idle/interval/count ([tcp] keepalive_*).
+    pub keepalive: KeepalivePolicy,
+    /// Batas byte tertahan di buffer RX (sisa di luar APDU utuh).
+    pub max_buffer_bytes: usize,
+    /// Umur sesi maksimum: setelahnya STOPDT, putus dan sambung ulang (firmware gateway yang
+    /// bocor sumber daya); None = sesi tanpa batas.
+    pub max_session: Option<Duration>,
+    /// Mode pengembangan: jalankan RTU tiruan di alamat ini alih-alih klien.
+    pub serve: Option<String>,
+    /// Mode REPL: ketik APDU hex untuk dikirim ke RTU.
+    pub interactive: bool,
+    /// Jalankan vektor uji kesesuaian decoder lalu keluar.
+    pub self_test: bool,
+    /// Ukur throughput jalur RX (potong/klasifikasi/dekode) lalu keluar.
+    pub bench: bool,
+    /// Nilai frame usulan dari stdin dengan gatekeeper lalu keluar; tidak ada koneksi.
+    pub dry_run: bool,
+    /// Subperintah `snapshot`: STARTDT, GI, kumpulkan sampai act-term, cetak tabel titik, STOPDT.
+    pub snapshot: bool,
+    /// Batas waktu snapshot dari connect sampai act-term.
+    pub snapshot_timeout: Duration,
+    pub deadband: Deadband,
+    pub point_deadbands: HashMap<PointKey, Deadband>,
+    pub format: OutputFormat,
+    pub csv: CsvOptions,
+    /// Besaran terukur berkualitas IV/OV diekspor sebagai null, bukan angka.
+    pub invalid_as_null: bool,
+    /// Hex RX ditampilkan dengan batas medan APCI/ASDU.
+    pub annotate_hex: bool,
+    /// Hex RX sebagai hexdump klasik dengan gutter ASCII (menang atas annotate_hex).
+    pub hexdump: bool,
+    /// Tiap frame dicetak sebagai pohon bertingkat (APCI, header ASDU, objek).
+    pub pretty: bool,
+    /// Prefiks stempel waktu host di tiap baris log.
+    pub log_time: LogTime,
+    /// Log operasional sebagai teks atau JSON per kejadian.
+    pub log_format: LogFormat,
+    /// Tujuan log operasional; rekaman titik selalu ke stdout.
+    pub log_stream: LogStream,
+    /// Jejak per frame (RX/TX, rincian) hanya untuk frame berisi perubahan nilai/status lewat
+    /// deadband, lintasan ambang alarm, atau kualitas buruk; frame siklik rutin tidak dilog.
+    pub log_changes_only: bool,
+    /// Kategori asal yang diemit; kosong = semua.
+    pub origins: Vec<Origin>,
+    pub filter: AddressFilter,
+    /// Selisih maksimum waktu sumber vs jam lokal sebelum ditandai mencurigakan; None = cek mati.
+    pub max_clock_skew: Option<Duration>,
+    /// Lompatan maju waktu sumber antar kejadian satu casdu (di atas waktu lokal yang berlalu)
+    /// yang diperingatkan; mundur selalu diperingatkan. None = cek mati.
+    pub max_clock_jump: Option<Duration>,
+    /// Peringatan bila SQ counter per titik tidak naik tepat satu antar pembacaan.
+    pub check_counter_seq: bool,
+    /// Lenient (catat & lanjut) atau strict (tolak frame menyimpang); lihat `ParseMode`.
+    pub parse_mode: ParseMode,
+    /// Panjang medan alamat ASDU yang diterima (`[asdu]`); ASDU keluar selalu profil 104.
+    pub asdu: AsduLayoutPolicy,
+    pub rtu_clock: RtuClock,
+    pub names: Names,
+    pub scaling: ScalingTable,
+    /// Alarm batas atas/bawah per titik, dievaluasi sebelum deadband.
+    pub alarms: Vec<AlarmDef>,
+    pub sinks: Vec<SinkSpec>,
+    pub rotation: Rotation,
+    pub sink_queue: usize,
+    pub sink_retry: RetryPolicy,
+    pub dead_letter: Option<String>,
+    /// Batas waktu menguras sink saat proses berhenti; sisanya dilaporkan hilang.
+    pub sink_shutdown_timeout: Duration,
+    /// Direktori berkas hasil transfer; None = transfer berkas mati.
+    pub file_dir: Option<String>,
+    pub fetch_files: Vec<FileRequest>,
+    pub file_auto_fetch: bool,
+    /// Berkas log kejadian (transisi status & alarm); None = mati.
+    pub event_log: Option<String>,
+    /// Berkas JSON lines kejadian koneksi (connected, STARTDT, putus, reconnect, ...).
+    pub link_events: Option<String>,
+    /// Rate limiter TX; S-ACK dan U con tidak terkena batas.
+    pub tx_max_fps: f64,
+    pub tx_min_gap: Duration,
+    /// k sisi kirim: I-frame berikutnya diantre bila sudah sebanyak ini belum di-ACK.
+    pub max_outstanding_tx: u16,
+    /// Kapan I-frame dari RTU di-S-ACK: mode, w, t2, ambang darurat.
+    pub ack: AckPolicy,
+    /// Originator address kita di bus multi-master; None = OA 0 dan semua balasan dianggap milik kita.
+    pub originator: Option<u8>,
+    /// Tipe ASDU keluar yang boleh/dilarang gatekeeper (default: blok 45/46/47); ikut dimuat ulang.
+    pub out_types: TypePolicy,
+    /// Mode pasif: tidak ada byte keluar sama sekali; lebih ketat dari ACK-only.
+    pub passive: bool,
+    /// Buka jalur kirim pada build `ACK_ONLY`: I-frame (GI, berkas) dan U-frame act (TESTFR,
+    /// STOPDT) lewat gatekeeper, tetap dengan kebijakan tipe keluar. Tidak dimuat ulang saat jalan.
+    pub enable_tx: bool,
+    /// Hanya dekode: S-ACK, koalescing w/t2, jendela dan I-frame keluar dilewati seluruhnya.
+    /// Implisit pada `passive`/pcap; sendiri (sesi aktif) STARTDT tetap dikirim.
+    pub decode_only: bool,
+    /// Kirim STARTDT act setelah connect. false = listen-only: kita tidak memulai transfer
+    /// data tetapi tetap meng-ACK I-frame yang datang — beda dengan `passive` yang nol TX.
+    pub startdt: bool,
+    /// GI otomatis setelah STARTDT con (tiap sesi, jadi juga setelah reconnect) dan saat
+    /// watchdog no-data berbunyi; tidak pernah tumpang tindih dengan GI yang masih berjalan.
+    pub auto_gi: bool,
+    /// Supervisi TESTFR saat idle: t3, t1, kirim ulang, lalu sambung ulang.
+    pub testfr: TestFrPolicy,
+    /// Tabel titik live di terminal alih-alih log yang menggulung.
+    pub follow: FollowPolicy,
+    pub simulate: SimulatePolicy,
+    /// Baca aliran dari berkas pcap alih-alih TCP ke RTU (memaksa mode pasif).
+    pub pcap: Option<String>,
+    /// Sambung ulang juga setelah peer menutup / kesalahan baca (kesalahan urutan & t1 selalu).
+    pub reconnect: bool,
+    /// Interval baris statistik berkala; nol = mati.
+    pub stats_interval: Duration,
+    /// Watchdog: alarm bila tidak ada I-frame selama ini; None = mati.
+    pub no_data_timeout: Option<Duration>,
+    pub no_data_action: NoDataAction,
+    /// Alamat endpoint HTTP /healthz, /readyz (& /metrics bila `metrics_addr` kosong); None = mati.
+    pub health_addr: Option<String>,
+    /// Alamat listener /metrics tersendiri; None = /metrics ikut endpoint health.
+    pub metrics_addr: Option<String>,
+    /// Kapasitas riwayat APDU mentah untuk post-mortem; 0 = mati.
+    pub frame_history: usize,
+    /// Berkas pemicu dump riwayat manual (dihapus setelah dump).
+    pub dump_trigger: Option<String>,
+    /// Berkas pemicu berhenti rapi (pengganti SIGTERM: std tanpa unsafe tidak bisa menangkap sinyal).
+    pub stop_trigger: Option<String>,
+    /// Berkas --config dan mtime-nya saat terakhir dimuat (untuk muat ulang saat berubah).
+    config_path: Option<String>,
+    config_mtime: Option<SystemTime>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            rtu_addr: crate::RTU_ADDR.to_string(),
+            bind: None,
+            read_timeout: Duration::from_secs(10),
+            write_timeout: None,
+            nodelay: true,
+            keepalive: KeepalivePolicy::default(),
+            max_buffer_bytes: 64 * 1024,
+            max_session: None,
+            serve: None,
+            interactive: false,
+            self_test: false,
+            bench: false,
+            dry_run: false,
+            snapshot: false,
+            snapshot_timeout: Duration::from_secs(60),
+            deadband: Deadband::None,
+            point_deadbands: HashMap::new(),
+            format: OutputFormat::Text,
+            csv: CsvOptions::default(),
+            invalid_as_null: false,
+            annotate_hex: false,
+            pretty: false,
+            log_time: LogTime::Iso,
+            log_format: LogFormat::Text,
+            log_stream: LogStream::Stdout,
+            log_changes_only: false,
+            hexdump: false,
+            origins: Vec::new(),
+            filter: AddressFilter::default(),
+            max_clock_skew: Some(Duration::from_secs(3600)),
+            max_clock_jump: Some(Duration::from_secs(60)),
+            check_counter_seq: false,
+            parse_mode: ParseMode::Lenient,
+            asdu: AsduLayoutPolicy::default(),
+            rtu_clock: RtuClock::default(),
+            names: Names::default(),
+            scaling: ScalingTable::default(),
+            alarms: Vec::new(),
+            sinks: Vec::new(),
+            rotation: Rotation::default(),
+            sink_queue: 10_000,
+            sink_retry: RetryPolicy::default(),
+            dead_letter: None,
+            sink_shutdown_timeout: Duration::from_secs(10),
+            event_log: None,
+            link_events: None,
+            file_dir: None,
+            fetch_files: Vec::new(),
+            file_auto_fetch: true,
+            tx_max_fps: 10.0,
+            tx_min_gap: Duration::from_millis(0),
+            max_outstanding_tx: 12,
+            ack: AckPolicy::default(),
+            originator: None,
+            out_types: TypePolicy::default(),
+            passive: false,
+            enable_tx: false,
+            decode_only: false,
+            startdt: true,
+            auto_gi: false,
+            testfr: TestFrPolicy::default(),
+            follow: FollowPolicy::default(),
+            simulate: SimulatePolicy::default(),
+            pcap: None,
+            reconnect: false,
+            stats_interval: Duration::ZERO,
+            no_data_timeout: None,
+            no_data_action: NoDataAction::Log,
+            health_addr: None,
+            metrics_addr: None,
+            frame_history: 32,
+            dump_trigger: None,
+            stop_trigger: None,
+            config_path: None,
+            config_mtime: None,
+        }
+    }
+}
+
+fn parse_oa(s: &str) -> Result<u8, String> {
+    s.trim().parse().map_err(|_| format!("originator address tidak valid: '{}' (0..255)", s.trim()))
+}
+
+/// Detik (bilangan bulat) atau "off".
+fn parse_secs_opt(s: &str) -> Result<Option<Duration>, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("off") { return Ok(None); }
+    s.parse::<u64>().map(|v| Some(Duration::from_secs(v))).map_err(|_| format!("durasi (detik) tidak valid: '{}'", s))
+}
+
+/// Rentang panjang: "6h", "90m", "2d", "3600" (detik) atau "off"; nol tidak berarti.
+fn parse_span(s: &str) -> Result<Option<Duration>, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("off") { return Ok(None); }
+    let bad = || format!("durasi tidak valid: '{}' (mis. 6h, 90m, 2d, 3600; off = mati)", s);
+    let (num, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "s"),
+    };
+    let mult = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86_400,
+        _ => return Err(bad()),
+    };
+    match num.trim().parse::<u64>() {
+        Ok(v) if v > 0 => v.checked_mul(mult).map(|v| Some(Duration::from_secs(v))).ok_or_else(bad),
+        _ => Err(bad()),
+    }
+}
+
+/// Alamat listener HTTP host:port, atau "off" untuk mematikannya.
+fn parse_server_addr(s: &str) -> Result<Option<String>, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("off") { return Ok(None); }
+    match s.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => Ok(Some(s.to_string())),
+        _ => Err(format!("alamat server tidak valid: '{}' (host:port atau off)", s)),
+    }
+}
+
+/// Sisa sah setelah APDU utuh diambil hanya sepotong APDU (<= 255 byte); batas di bawah
+/// itu membuang frame yang sedang tiba.
+fn parse_max_buffer(s: &str) -> Result<usize, String> {
+    match Rotation::parse_size(s)? {
+        n if n >= 512 => Ok(n as usize),
+        _ => Err(format!("max_buffer minimal 512 byte: '{}'", s.trim())),
+    }
+}
+
+/// Timeout baca harus > 0: tanpa itu read loop tidak pernah bangun untuk t2/t1/watchdog.
+fn parse_read_timeout(s: &str) -> Result<Duration, String> {
+    match parse_secs_opt(s)? {
+        Some(d) if !d.is_zero() => Ok(d),
+        _ => Err(format!("timeout baca harus > 0 detik: '{}'", s.trim())),
+    }
+}
+
+/// "+07:00", "-03:30", "+7", "Z" -> menit
+fn parse_utc_offset(s: &str) -> Result<i32, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("z") || s.eq_ignore_ascii_case("utc") { return Ok(0); }
+    let bad = || format!("offset UTC tidak valid: '{}' (contoh +07:00)", s);
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => (1, s),
+    };
+    let (h, m) = rest.split_once(':').unwrap_or((rest, "0"));
+    let h: i32 = h.parse().map_err(|_| bad())?;
+    let m: i32 = m.parse().map_err(|_| bad())?;
+    if h > 14 || m > 59 { return Err(bad()); }
+    Ok(sign * (h * 60 + m))
+}
+
+fn parse_fps(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v >= 0.0 => Ok(v),
+        _ => Err(format!("laju frame tidak valid: '{}'", s)),
+    }
+}
+
+fn parse_millis(s: &str) -> Result<Duration, String> {
+    s.trim().parse::<u64>().map(Duration::from_millis).map_err(|_| format!("durasi (ms) tidak valid: '{}'", s))
+}
+
+/// w valid: 1..=32767 (batas atas k diperiksa AckPolicy::validate)
+fn parse_w(s: &str) -> Result<u16, String> {
+    match s.trim().parse::<u16>() {
+        Ok(v) if (1..=32767).contains(&v) => Ok(v),
+        _ => Err(format!("w tidak valid: '{}' (1..k)", s.trim())),
+    }
+}
+
+/// Detik, boleh pecahan: "10", "0.5"
+fn parse_secs_frac(s: &str) -> Result<Duration, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v.is_finite() && v >= 0.0 => Ok(Duration::from_secs_f64(v)),
+        _ => Err(format!("durasi (detik) tidak valid: '{}'", s.trim())),
+    }
+}
+
+/// Fraksi 0 < f <= 1
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    match s.trim().parse::<f64>() {
+        Ok(v) if v > 0.0 && v <= 1.0 => Ok(v),
+        _ => Err(format!("fraksi tidak valid: '{}' (0 < f <= 1)", s.trim())),
+    }
+}
+
+/// Kirim ulang TESTFR: 0..=10
+fn parse_retries(s: &str) -> Result<u8, String> {
+    match s.trim().parse::<u8>() {
+        Ok(v) if v <= 10 => Ok(v),
+        _ => Err(format!("retries tidak valid: '{}' (0..10)", s.trim())),
+    }
+}
+
+/// TCP_KEEPCNT valid: 1..=127 (batas Linux)
+fn parse_keepalive_count(s: &str) -> Result<u32, String> {
+    match s.trim().parse::<u32>() {
+        Ok(v) if (1..=127).contains(&v) => Ok(v),
+        _ => Err(format!("keepalive_count tidak valid: '{}' (1..127)", s.trim())),
+    }
+}
+
+/// k valid: 1..=32767
+fn parse_k(s: &str) -> Result<u16, String> {
+    match s.trim().parse::<u16>() {
+        Ok(v) if (1..=32767).contains(&v) => Ok(v),
+        _ => Err(format!("k tidak valid: '{}' (1..32767)", s)),
+    }
+}
+
+fn parse_bool(s: &str) -> Result<bool, String> {
+    match s.trim() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" => Ok(false),
+        other => Err(format!("boolean tidak valid: '{}'", other)),
+    }
+}
+
+/// "10.0.0.5", "10.0.0.5:0", "::1" atau "[::1]:0"
+fn parse_bind(s: &str) -> Result<SocketAddr, String> {
+    let s = s.trim();
+    s.parse::<SocketAddr>()
+        .or_else(|_| s.parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 0)))
+        .map_err(|_| format!("alamat bind tidak valid: '{}'", s))
+}
+
+fn parse_count(s: &str) -> Result<usize, String> {
+    s.trim().parse().map_err(|_| format!("jumlah tidak valid: '{}'", s))
+}
+
+fn parse_points(s: &str) -> Result<Vec<PointKey>, String> {
+    s.split(',').filter(|t| !t.trim().is_empty()).map(PointKey::parse).collect()
+}
+
+fn parse_casdus(s: &str) -> Result<Vec<u16>, String> {
+    s.split(',')
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| t.trim().parse().map_err(|_| format!("casdu tidak valid: '{}'", t.trim())))
+        .collect()
+}
+
+/// "1000-1999" atau "1000", dipisah koma.
+fn parse_ioa_ranges(s: &str) -> Result<Vec<(u32, u32)>, String> {
+    s.split(',')
+        .filter(|t| !t.trim().is_empty())
+        .map(|t| {
+            let bad = || format!("rentang IOA tidak valid: '{}'", t.trim());
+            let (lo, hi) = t.split_once('-').unwrap_or((t, t));
+            let lo: u32 = lo.trim().parse().map_err(|_| bad())?;
+            let hi: u32 = hi.trim().parse().map_err(|_| bad())?;
+            if lo > hi || hi > 0xFF_FFFF { return Err(bad()); }
+            Ok((lo, hi))
+        })
+        .collect()
+}
+
+fn parse_origins(s: &str) -> Result<Vec<Origin>, String> {
+    s.split(',').filter(|t| !t.trim().is_empty()).map(Origin::parse).collect()
+}
+
+const USAGE: &str = "\
+Pemakaian: iec104_client [snapshot | simulate] [opsi]
+  snapshot              sekali jalan: STARTDT, GI, kumpulkan nilai sampai act-term, cetak tabel
+                        titik (format --format), STOPDT, keluar 0; gagal/timeout = keluar 1
+  --snapshot-timeout <s>  batas waktu snapshot dari connect sampai act-term (default 60)
+  simulate              RTU simulasi untuk uji sink/dashboard hilir: ukur siklik, kejadian
+                        spontan bertime tag, jawab GI/CI, konfirmasi perintah ([simulate])
+  --sim-listen <addr>   alamat dengar simulasi (default 0.0.0.0:2404)
+  --sim-casdu <n>       casdu simulasi (default 1)
+  --sim-points <list>   titik simulasi ioa:jenis[:nilai awal], mis. 100:sp,2000:float:230.5
+                        (jenis sp|dp|normalized|scaled|float|counter)
+  --sim-cycle <s>       jeda ukur siklik, boleh pecahan; 0 = mati (default 2)
+  --sim-event-rate <n>  rata-rata kejadian spontan per detik; 0 = mati (default 1)
+  --sim-seed <n>        seed acak tetap: lalu lintas sama tiap jalan
+  --config <berkas>     berkas situs per RTU (INI); semua kesalahan dilaporkan sekaligus.
+                        Filter/ambang dimuat ulang tanpa putus saat SIGHUP atau berkas berubah
+  --rtu <host:port>     alamat RTU tujuan: IPv4, hostname, atau [IPv6]:port (default 192.168.11.93:2404)
+  --read-timeout <s>    timeout baca socket, juga irama cek timer saat idle (default 10)
+  --write-timeout <s>   timeout tulis socket; off = tanpa batas (default off)
+  --tcp-nodelay <b>     TCP_NODELAY true|false (default true)
+  --tcp-keepalive-idle <s>      SO_KEEPALIVE: probe setelah s detik sepi; off = mati (default off)
+  --tcp-keepalive-interval <s>  jeda antar probe keepalive (default bawaan OS)
+  --tcp-keepalive-count <n>     probe tak berbalas sebelum putus, 1..127 (default bawaan OS)
+  --max-buffer <n>      batas buffer RX (K/M), lebih = sampah dibuang sampai 0x68 (default 64K)
+  --max-session <d>     siklus koneksi terjadwal: STOPDT, putus, sambung ulang setelah sesi
+                        selama d, mis. 6h, 90m, 3600 (default off; butuh --enable-tx)
+  --bind <ip[:port]>    alamat lokal sumber koneksi (keluarga harus sama dengan target)
+  --serve <addr>        mode pengembangan: jalankan RTU tiruan di addr, bukan klien
+  --interactive         REPL: ketik APDU hex untuk dikirim (awali '!' untuk paksa kirim)
+  --self-test           jalankan vektor uji kesesuaian decoder lalu keluar
+  --bench               ukur throughput parse/ACK lalu keluar; hanya pada build
+                        cargo run --release --features bench
+  --dry-run             nilai frame usulan dari stdin (APDU hex | asdu <hex> | gi <casdu> <qoi>)
+                        dengan gatekeeper & kebijakan tipe dari konfigurasi; tidak ada yang dikirim
+  --deadband <v>        deadband global, absolut (0.5) atau persen (2%)
+  --format <f>          format keluaran titik: text | json | csv | influx
+  --invalid-as-null     nilai ukur berkualitas IV/OV -> null (Influx: NaN) di json/csv/influx
+  --annotate-hex        pecah hex RX per medan (APCI | header ASDU | objek)
+  --hexdump             hex RX sebagai hexdump: offset, 16 byte, gutter ASCII
+  --pretty              cetak tiap frame sebagai pohon: APCI, header ASDU, objek
+  --log-time <f>        prefiks waktu host tiap baris log: iso | epoch_ms | off (default iso)
+  --log-format <f>      log operasional: text | json (ts, level, msg, rtu + medan bertipe)
+  --log-stream <s>      tujuan log: stdout (default) | stderr (stdout hanya data JSON/CSV,
+                        untuk | jq) | auto (stderr bila stdout dipipe/dialihkan)
+  --log-changes-only    jejak frame hanya bila ada perubahan lewat deadband, lintasan alarm
+                        atau kualitas buruk (operasi harian tanpa banjir data siklik)
+  --origin <list>       hanya emit kategori asal tertentu, mis. spontaneous,interrogated
+  --casdu <list>        hanya emit objek dari casdu ini, mis. 14 atau 14,15
+  --ioa-range <list>    hanya emit IOA dalam rentang, mis. 1000-1999
+                        (periodic, background, spontaneous, requested, interrogated, other)
+  --max-clock-skew <s>  tandai waktu sumber mencurigakan bila selisih > s detik (default 3600, off)
+  --strict              tolak frame menyimpang (ASDU pendek/malformed, type tanpa decoder,
+                        celah N(S) pasif, N(R) aneh, APCI/U tidak dikenal) sebagai error
+  --strict-disconnect   seperti --strict, dan pelanggaran pertama menutup koneksi
+  --asdu-layout <p>     preset panjang medan alamat ASDU: standard-104 (default), siemens, abb,
+                        legacy-101-tunneled (COT 1, CASDU 1, IOA 2)
+  --cot-len <n>         timpa panjang COT dari preset (1..2)
+  --casdu-len <n>       timpa panjang CASDU dari preset (1..2)
+  --ioa-len <n>         timpa panjang IOA dari preset (1..3)
+  --max-clock-jump <s>  peringatkan waktu sumber per casdu yang mundur atau maju > s detik
+                        melebihi jam lokal antar kejadian (default 60, off)
+  --check-counter-seq   peringatkan bila SQ counter (type 15/37) per IOA tidak naik satu antar
+                        pembacaan (siklus freeze terlewat)
+  --rtu-utc-offset <o>  zona waktu standar RTU, mis. +07:00 (default UTC)
+  --su-adds-hour        bit SU=1 menambah 1 jam pada offset RTU
+  --sink <fmt:path>     tambah sink berkas (json|csv|text), boleh berulang;
+                        opsional ',batch=N,interval=S' untuk flush berkelompok
+  --dead-letter <path>  titik yang gagal ditulis sink setelah retry -> JSON lines di path
+  --rotate-size <n>     rotasi berkas sink setelah n byte (K/M/G), mis. 10M (default off)
+  --rotate-gzip         kompres segmen sink yang dirotasi ke .gz
+  --file-dir <dir>      simpan berkas yang ditarik dari RTU (transfer tipe 120..126) ke dir
+  --fetch-file <c:i:n>  tarik berkas casdu:ioa:nof setelah STARTDT con, boleh berulang
+  --event-log <path>    catat transisi titik status (1/3/30/31) & perubahan IV ke berkas
+  --link-events <path>  kejadian koneksi (connected, startdt, putus, reconnect) sebagai JSON lines
+  --tx-max-fps <n>      batas frame keluar per detik (default 10, 0 = tanpa batas)
+  --tx-min-gap <ms>     jeda minimum antar frame keluar (default 0)
+  --max-outstanding-tx <k>  maks I-frame keluar belum di-ACK (default 12)
+  --ack-mode <m>        immediate (S-ACK per I-frame) | coalesced (w/t2, default)
+  --ack-k <n>           perkiraan k sisi RTU untuk jendela & ACK darurat (default 12)
+  --ack-w <n>           S-ACK setelah n I-frame belum di-ACK, 1..k (default 8)
+  --ack-t2 <s>          S-ACK paling lambat s detik, boleh pecahan, < t1 (default 10)
+  --ack-emergency <f>   S-ACK darurat pada fraksi k ini belum di-ACK, 0..1 (default 0.85)
+  --originator <oa>     OA kita (0..255) untuk bus multi-master: balasan ber-OA lain hanya diaudit
+  --forbid-types <list> tipe ASDU keluar yang diblok gatekeeper (default 45,46,47)
+  --allow-types <list>  hanya tipe ASDU ini yang boleh keluar (ganti --forbid-types)
+  --no-startdt          listen-only: tanpa STARTDT act, I-frame yang datang tetap di-ACK
+                        (untuk gateway yang memulai aliran sendiri; --passive = nol TX)
+  --auto-gi             GI otomatis tiap STARTDT con & saat watchdog no-data (butuh --enable-tx)
+  --enable-tx           buka jalur kirim pada build ACK-only (GI, TESTFR, STOPDT, snapshot);
+                        kebijakan tipe keluar tetap berlaku
+  --passive             sniffer pasif (port SPAN/tap): nol TX, tanpa STARTDT/S-ACK/TESTFR con
+  --decode-only         dekode & sink saja: tanpa S-ACK, koalescing w/t2 dan jendela
+                        (implisit pada --passive/--pcap; RTU aktif berhenti setelah k I-frame)
+  --pcap <berkas>       dekode aliran RTU dari capture pcap (port dari --rtu), implisit --passive
+  --reconnect           sambung ulang setelah koneksi putus (kesalahan urutan/t1: selalu)
+  --stats-interval <s>  cetak baris statistik tiap s detik (0 = mati)
+  --no-data-timeout <s> alarm bila tidak ada I-frame selama s detik (default off)
+  --no-data-action <a>  log | reconnect | gi (default log)
+  --testfr              supervisi idle: TESTFR act setelah t3, kirim ulang bila tanpa con
+                        dalam t1, lalu sambung ulang (butuh --enable-tx)
+  --t3 <s>              idle sebelum TESTFR act, boleh pecahan (default 20)
+  --testfr-t1 <s>       tunggu TESTFR con, boleh pecahan (default 15)
+  --testfr-retries <n>  TESTFR act tambahan sebelum sambung ulang (default 1)
+  --health-addr <addr>  endpoint HTTP /healthz, /readyz, /metrics, /stations & /config, mis. 0.0.0.0:8080 (off = mati)
+  --metrics-addr <addr> /metrics di listener sendiri, mis. 10.0.0.5:9104 (off = ikut health)
+  --frame-history <n>   simpan n APDU terakhir, dicetak saat error/putus (default 32)
+  --dump-trigger <path> cetak riwayat APDU saat berkas ini muncul (lalu dihapus); di unix
+                        juga saat SIGUSR1 (kill -USR1 <pid>)
+  --stop-trigger <path> berhenti rapi saat berkas ini muncul (lalu dihapus): sink dikuras,
+                        berkas ditutup; SIGTERM/SIGKILL tetap membunuh tanpa menguras
+  --follow              tabel titik live digambar ulang di tempat (seperti top) alih-alih
+                        log menggulung; log terakhir di bawah tabel, error tetap ke stderr
+  --follow-points <list>  titik di tabel follow, mis. 14:1001,2001 (default semua)
+  --follow-casdu <list> plus semua titik casdu ini, mis. 14,15
+  --follow-interval <s> jeda gambar ulang tabel follow, boleh pecahan (default 1)
+  -h, --help            tampilkan bantuan ini";
+
+/// Diset penangan SIGHUP (`Config::watch_sighup`), dikosongkan `reload_if_changed`.
+static SIGHUP: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+impl Config {
+    /// Gatekeeper memblok semua I-frame & U-frame act selain STARTDT: konstanta build `ACK_ONLY`
+    /// yang tidak dibuka `enable_tx`.
+    pub fn ack_only(&self) -> bool {
+        crate::ACK_ONLY && !self.enable_tx
+    }
+
+    /// Susun konfigurasi dari argumen proses. CLI menimpa nilai dari berkas.
+    pub fn from_args() -> Result<Config, String> {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        // Berkas situs dimuat lebih dulu agar semua opsi CLI menimpanya
+        let mut cfg = match args.iter().position(|a| a == "--config") {
+            Some(i) => {
+                let path = args.get(i + 1).ok_or("--config butuh nilai")?;
+                Config::load_site(path).map_err(|errs| {
+                    format!("{}: {} kesalahan konfigurasi\n  {}", path, errs.len(), errs.join("\n  "))
+                })?
+            }
+            None => Config::default(),
+        };
+        let mut cli_deadband: Option<Deadband> = None;
+        let mut cli_format: Option<OutputFormat> = None;
+        let mut cli_invalid_as_null = false;
+        let mut cli_log_changes_only = false;
+        let mut cli_log_time: Option<LogTime> = None;
+        let mut cli_log_format: Option<LogFormat> = None;
+        let mut cli_log_stream: Option<LogStream> = None;
+        let mut cli_origins: Option<Vec<Origin>> = None;
+        let mut cli_casdus: Option<Vec<u16>> = None;
+        let mut cli_ioa_ranges: Option<Vec<(u32, u32)>> = None;
+        let mut cli_skew: Option<Option<Duration>> = None;
+        let mut cli_jump: Option<Option<Duration>> = None;
+        let mut cli_parse_mode: Option<ParseMode> = None;
+        let mut cli_layout: Option<LayoutPreset> = None;
+        let mut cli_cot_len: Option<usize> = None;
+        let mut cli_casdu_len: Option<usize> = None;
+        let mut cli_ioa_len: Option<usize> = None;
+        let mut cli_offset: Option<i32> = None;
+        let mut cli_su = false;
+        let mut cli_counter_seq = false;
+        let mut cli_auto_gi = false;
+        let mut cli_no_startdt = false;
+        let mut cli_sinks: Vec<SinkSpec> = Vec::new();
+        let mut cli_event_log: Option<String> = None;
+        let mut cli_link_events: Option<String> = None;
+        let mut cli_dead_letter: Option<String> = None;
+        let mut cli_rotate_size: Option<u64> = None;
+        let mut cli_rotate_gzip = false;
+        let mut cli_fetch: Vec<FileRequest> = Vec::new();
+        let mut cli_fps: Option<f64> = None;
+        let mut cli_gap: Option<Duration> = None;
+        let mut cli_k: Option<u16> = None;
+        let mut cli_ack_mode: Option<AckMode> = None;
+        let mut cli_ack_k: Option<u16> = None;
+        let mut cli_ack_w: Option<u16> = None;
+        let mut cli_ack_t2: Option<Duration> = None;
+        let mut cli_ack_emergency: Option<f64> = None;
+        let mut cli_testfr = false;
+        let mut cli_follow_points: Option<Vec<PointKey>> = None;
+        let mut cli_follow_casdus: Option<Vec<u16>> = None;
+        let mut cli_t3: Option<Duration> = None;
+        let mut cli_testfr_t1: Option<Duration> = None;
+        let mut cli_testfr_retries: Option<u8> = None;
+        let mut cli_out_types: Option<TypePolicy> = None;
+        let mut cli_originator: Option<u8> = None;
+        let mut cli_read_timeout: Option<Duration> = None;
+        let mut cli_write_timeout: Option<Option<Duration>> = None;
+        let mut cli_nodelay: Option<bool> = None;
+        let mut cli_keepalive_idle: Option<Option<Duration>> = None;
+        let mut cli_keepalive_interval: Option<Option<Duration>> = None;
+        let mut cli_keepalive_count: Option<u32> = None;
+        let mut cli_max_buffer: Option<usize> = None;
+        let mut cli_health_addr: Option<Option<String>> = None;
+        let mut cli_metrics_addr: Option<Option<String>> = None;
+
+        let mut it = args.iter();
+        while let Some(a) = it.next() {
+            let mut val = |name: &str| it.next().cloned().ok_or_else(|| format!("{} butuh nilai", name));
+            match a.as_str() {
+                "--config" => { val(a)?; }
+                "--rtu" => cfg.rtu_addr = val(a)?,
+                "--bind" => cfg.bind = Some(parse_bind(&val(a)?)?),
+                "--read-timeout" => cli_read_timeout = Some(parse_read_timeout(&val(a)?)?),
+                "--write-timeout" => cli_write_timeout = Some(parse_secs_opt(&val(a)?)?),
+                "--tcp-nodelay" => cli_nodelay = Some(parse_bool(&val(a)?)?),
+                "--tcp-keepalive-idle" => cli_keepalive_idle = Some(parse_secs_opt(&val(a)?)?),
+                "--tcp-keepalive-interval" => cli_keepalive_interval = Some(parse_secs_opt(&val(a)?)?),
+                "--tcp-keepalive-count" => cli_keepalive_count = Some(parse_keepalive_count(&val(a)?)?),
+                "--max-buffer" => cli_max_buffer = Some(parse_max_buffer(&val(a)?)?),
+                "--max-session" => cfg.max_session = parse_span(&val(a)?)?,
+                "--serve" => cfg.serve = Some(val(a)?),
+                "--interactive" => cfg.interactive = true,
+                "--self-test" => cfg.self_test = true,
+                "--bench" if !cfg!(feature = "bench") => return Err("--bench hanya ada di build dengan --features bench (cargo run --release --features bench -- --bench)".into()),
+                "--bench" => cfg.bench = true,
+                "--dry-run" => cfg.dry_run = true,
+                "snapshot" => cfg.snapshot = true,
+                "simulate" => cfg.simulate.enabled = true,
+                "--sim-listen" => cfg.simulate.listen = parse_server_addr(&val(a)?)?.ok_or("--sim-listen butuh host:port")?,
+                "--sim-casdu" => cfg.simulate.casdu = parse_sim_casdu(&val(a)?)?,
+                "--sim-points" => cfg.simulate.points = parse_sim_points(&val(a)?)?,
+                "--sim-cycle" => cfg.simulate.cycle = parse_secs_frac(&val(a)?)?,
+                "--sim-event-rate" => cfg.simulate.event_rate = parse_fps(&val(a)?)?,
+                "--sim-seed" => cfg.simulate.seed = Some(parse_seed(&val(a)?)?),
+                "--snapshot-timeout" => {
+                    cfg.snapshot_timeout = parse_secs_opt(&val(a)?)?.filter(|d| !d.is_zero()).ok_or("--snapshot-timeout harus > 0 detik")?;
+                }
+                "--deadband" => cli_deadband = Some(Deadband::parse(&val(a)?)?),
+                "--format" => cli_format = Some(OutputFormat::parse(&val(a)?)?),
+                "--invalid-as-null" => cli_invalid_as_null = true,
+                "--log-changes-only" => cli_log_changes_only = true,
+                "--annotate-hex" => cfg.annotate_hex = true,
+                "--pretty" => cfg.pretty = true,
+                "--log-time" => cli_log_time = Some(LogTime::parse(&val(a)?)?),
+                "--log-format" => cli_log_format = Some(LogFormat::parse(&val(a)?)?),
+                "--log-stream" => cli_log_stream = Some(LogStream::parse(&val(a)?)?),
+                "--hexdump" => cfg.hexdump = true,
+                "--origin" => cli_origins = Some(parse_origins(&val(a)?)?),
+                "--casdu" => cli_casdus = Some(parse_casdus(&val(a)?)?),
+                "--ioa-range" => cli_ioa_ranges = Some(parse_ioa_ranges(&val(a)?)?),
+                "--max-clock-skew" => cli_skew = Some(parse_secs_opt(&val(a)?)?),
+                "--max-clock-jump" => cli_jump = Some(parse_secs_opt(&val(a)?)?),
+                "--strict" => cli_parse_mode = Some(ParseMode::Strict),
+                "--strict-disconnect" => cli_parse_mode = Some(ParseMode::StrictDisconnect),
+                "--asdu-layout" => cli_layout = Some(LayoutPreset::parse(&val(a)?)?),
+                "--cot-len" => cli_cot_len = Some(parse_count(&val(a)?)?),
+                "--casdu-len" => cli_casdu_len = Some(parse_count(&val(a)?)?),
+                "--ioa-len" => cli_ioa_len = Some(parse_count(&val(a)?)?),
+                "--rtu-utc-offset" => cli_offset = Some(parse_utc_offset(&val(a)?)?),
+                "--su-adds-hour" => cli_su = true,
+                "--check-counter-seq" => cli_counter_seq = true,
+                "--tx-max-fps" => cli_fps = Some(parse_fps(&val(a)?)?),
+                "--tx-min-gap" => cli_gap = Some(parse_millis(&val(a)?)?),
+                "--max-outstanding-tx" => cli_k = Some(parse_k(&val(a)?)?),
+                "--ack-mode" => cli_ack_mode = Some(AckMode::parse(&val(a)?)?),
+                "--ack-k" => cli_ack_k = Some(parse_k(&val(a)?)?),
+                "--ack-w" => cli_ack_w = Some(parse_w(&val(a)?)?),
+                "--ack-t2" => cli_ack_t2 = Some(parse_secs_frac(&val(a)?)?),
+                "--ack-emergency" => cli_ack_emergency = Some(parse_fraction(&val(a)?)?),
+                "--reconnect" => cfg.reconnect = true,
+                "--originator" => cli_originator = Some(parse_oa(&val(a)?)?),
+                "--forbid-types" | "--allow-types" => {
+                    if cli_out_types.is_some() {
+                        return Err("--forbid-types dan --allow-types tidak boleh digabung".into());
+                    }
+                    let ids = TypePolicy::parse_list(&val(a)?)?;
+                    cli_out_types = Some(if a == "--forbid-types" { TypePolicy::Forbid(ids) } else { TypePolicy::Allow(ids) });
+                }
+                "--passive" => cfg.passive = true,
+                "--enable-tx" => cfg.enable_tx = true,
+                "--decode-only" => cfg.decode_only = true,
+                "--auto-gi" => cli_auto_gi = true,
+                "--no-startdt" => cli_no_startdt = true,
+                "--pcap" => cfg.pcap = Some(val(a)?),
+                "--no-data-timeout" => cfg.no_data_timeout = parse_secs_opt(&val(a)?)?,
+                "--no-data-action" => cfg.no_data_action = NoDataAction::parse(&val(a)?)?,
+                "--testfr" => cli_testfr = true,
+                "--t3" => cli_t3 = Some(parse_secs_frac(&val(a)?)?),
+                "--testfr-t1" => cli_testfr_t1 = Some(parse_secs_frac(&val(a)?)?),
+                "--testfr-retries" => cli_testfr_retries = Some(parse_retries(&val(a)?)?),
+                "--stats-interval" => cfg.stats_interval = parse_secs_opt(&val(a)?)?.unwrap_or(Duration::ZERO),
+                "--health-addr" => cli_health_addr = Some(parse_server_addr(&val(a)?)?),
+                "--metrics-addr" => cli_metrics_addr = Some(parse_server_addr(&val(a)?)?),
+                "--frame-history" => cfg.frame_history = parse_count(&val(a)?)?,
+                "--dump-trigger" => cfg.dump_trigger = Some(val(a)?),
+                "--stop-trigger" => cfg.stop_trigger = Some(val(a)?),
+                "--follow" => cfg.follow.enabled = true,
+                "--follow-points" => cli_follow_points = Some(parse_points(&val(a)?)?),
+                "--follow-casdu" => cli_follow_casdus = Some(parse_casdus(&val(a)?)?),
+                "--follow-interval" => cfg.follow.interval = parse_secs_frac(&val(a)?)?,
+                "--dead-letter" => cli_dead_letter = Some(val(a)?),
+                "--rotate-size" => cli_rotate_size = Some(Rotation::parse_size(&val(a)?)?),
+                "--rotate-gzip" => cli_rotate_gzip = true,
+                "--file-dir" => cfg.file_dir = Some(val(a)?),
+                "--fetch-file" => cli_fetch.push(FileRequest::parse(&val(a)?)?),
+                "--event-log" => cli_event_log = Some(val(a)?),
+                "--link-events" => cli_link_events = Some(val(a)?),
+                "--sink" => {
+                    let name = format!("cli{}", cli_sinks.len() + 1);
+                    cli_sinks.push(SinkSpec::parse(&name, &val(a)?)?);
+                }
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    std::process::exit(0);
+                }
+                other => return Err(format!("opsi tidak dikenal: {}\n{}", other, USAGE)),
+            }
+        }
+
+        if let Some(db) = cli_deadband { cfg.deadband = db; }
+        if let Some(f) = cli_format { cfg.format = f; }
+        if cli_invalid_as_null { cfg.invalid_as_null = true; }
+        if cli_log_changes_only { cfg.log_changes_only = true; }
+        if let Some(t) = cli_log_time { cfg.log_time = t; }
+        if let Some(f) = cli_log_format { cfg.log_format = f; }
+        if let Some(s) = cli_log_stream { cfg.log_stream = s; }
+        if let Some(o) = cli_origins { cfg.origins = o; }
+        if let Some(c) = cli_casdus { cfg.filter.casdus = c; }
+        if let Some(r) = cli_ioa_ranges { cfg.filter.ioa_ranges = r; }
+        if let Some(sk) = cli_skew { cfg.max_clock_skew = sk; }
+        if let Some(j) = cli_jump { cfg.max_clock_jump = j; }
+        if let Some(m) = cli_parse_mode { cfg.parse_mode = m; }
+        // Preset CLI menggantikan preset berkas; penimpaan per medan berlaku di atas keduanya
+        if let Some(p) = cli_layout { cfg.asdu.preset = p; }
+        if let Some(n) = cli_cot_len { cfg.asdu.cot = Some(n); }
+        if let Some(n) = cli_casdu_len { cfg.asdu.casdu = Some(n); }
+        if let Some(n) = cli_ioa_len { cfg.asdu.ioa = Some(n); }
+        cfg.asdu.validate()?;
+        if let Some(o) = cli_offset { cfg.rtu_clock.utc_offset_min = o; }
+        if cli_su { cfg.rtu_clock.su_adds_hour = true; }
+        if cli_counter_seq { cfg.check_counter_seq = true; }
+        cfg.sinks.extend(cli_sinks);
+        if let Some(p) = cli_event_log { cfg.event_log = Some(p); }
+        if let Some(p) = cli_link_events { cfg.link_events = Some(p); }
+        if let Some(p) = cli_dead_letter { cfg.dead_letter = Some(p); }
+        if let Some(n) = cli_rotate_size { cfg.rotation.max_bytes = n; }
+        if cli_rotate_gzip { cfg.rotation.gzip = true; }
+        cfg.fetch_files.extend(cli_fetch);
+        if !cfg.fetch_files.is_empty() && cfg.file_dir.is_none() {
+            return Err("--fetch-file butuh --file-dir (atau [files] dir)".into());
+        }
+        if let Some(v) = cli_fps { cfg.tx_max_fps = v; }
+        if let Some(v) = cli_gap { cfg.tx_min_gap = v; }
+        if let Some(v) = cli_k { cfg.max_outstanding_tx = v; }
+        if let Some(m) = cli_ack_mode { cfg.ack.mode = m; }
+        if let Some(k) = cli_ack_k { cfg.ack.k = k; }
+        if let Some(w) = cli_ack_w { cfg.ack.w = w; }
+        if let Some(t) = cli_ack_t2 { cfg.ack.t2 = t; }
+        if let Some(f) = cli_ack_emergency { cfg.ack.emergency = f; }
+        cfg.ack.validate()?;
+        if let Some(t) = cli_out_types { cfg.out_types = t; }
+        if let Some(oa) = cli_originator { cfg.originator = Some(oa); }
+        if let Some(t) = cli_read_timeout { cfg.read_timeout = t; }
+        if let Some(t) = cli_write_timeout { cfg.write_timeout = t; }
+        if let Some(b) = cli_nodelay { cfg.nodelay = b; }
+        if let Some(t) = cli_keepalive_idle { cfg.keepalive.idle = t; }
+        if let Some(t) = cli_keepalive_interval { cfg.keepalive.interval = t; }
+        if let Some(n) = cli_keepalive_count { cfg.keepalive.count = Some(n); }
+        cfg.keepalive.validate()?;
+        if let Some(n) = cli_max_buffer { cfg.max_buffer_bytes = n; }
+        if let Some(a) = cli_health_addr { cfg.health_addr = a; }
+        if let Some(a) = cli_metrics_addr { cfg.metrics_addr = a; }
+        cfg.check_server_binds()?;
+        if cli_auto_gi { cfg.auto_gi = true; }
+        if cli_no_startdt { cfg.startdt = false; }
+        if cfg.pcap.is_some() { cfg.passive = true; }
+        if cfg.passive { cfg.decode_only = true; }
+        if cfg.decode_only && !cfg.passive && (cfg.auto_gi || cfg.snapshot) {
+            return Err("auto GI/snapshot butuh S-ACK & jendela; tidak bisa digabung --decode-only".into());
+        }
+        if cfg.auto_gi && cfg.passive {
+            return Err("auto GI butuh TX; tidak bisa digabung --passive/--pcap".into());
+        }
+        if cfg.auto_gi && cfg.ack_only() {
+            return Err("auto GI butuh TX (GI act) tetapi build ini ACK-only; tambahkan --enable-tx atau [tx] enable_tx = true".into());
+        }
+        if cli_testfr { cfg.testfr.enabled = true; }
+        if let Some(t) = cli_t3 { cfg.testfr.t3 = t; }
+        if let Some(t) = cli_testfr_t1 { cfg.testfr.t1 = t; }
+        if let Some(n) = cli_testfr_retries { cfg.testfr.retries = n; }
+        cfg.testfr.validate()?;
+        if cfg.testfr.enabled && cfg.passive {
+            return Err("supervisi TESTFR butuh TX; tidak bisa digabung --passive/--pcap".into());
+        }
+        if cfg.testfr.enabled && cfg.ack_only() {
+            return Err("supervisi TESTFR butuh TX (TESTFR act) tetapi build ini ACK-only; tambahkan --enable-tx atau [tx] enable_tx = true".into());
+        }
+        if cfg.max_session.is_some() && !cfg.passive && cfg.ack_only() {
+            return Err("--max-session menutup sesi dengan STOPDT act tetapi build ini ACK-only; tambahkan --enable-tx atau [tx] enable_tx = true".into());
+        }
+        if cfg.snapshot {
+            if cfg.passive {
+                return Err("snapshot butuh TX (GI, STOPDT); tidak bisa digabung --passive/--pcap".into());
+            }
+            if cfg.ack_only() {
+                return Err("snapshot butuh TX (GI act, STOPDT) tetapi build ini ACK-only; tambahkan --enable-tx atau [tx] enable_tx = true".into());
+            }
+            if !cfg.startdt {
+                return Err("snapshot butuh STARTDT (GI dikirim setelah STARTDT con); tidak bisa digabung --no-startdt".into());
+            }
+            // Sekali jalan: hasilnya tabel dari satu sesi, bukan aliran lintas koneksi ulang
+            cfg.reconnect = false;
+        }
+        // Pembentuk GI/berkas hanya mengenal profil 104: ASDU keluar bermedan lain akan salah urai di RTU
+        if cfg.asdu.layout() != AsduLayout::IEC104 && (cfg.auto_gi || cfg.snapshot || cfg.file_dir.is_some()) {
+            return Err(format!(
+                "tata letak ASDU {} hanya untuk dekode RX; GI otomatis/snapshot/transfer berkas butuh standard-104", cfg.asdu
+            ));
+        }
+        if cfg.simulate.enabled && (cfg.snapshot || cfg.serve.is_some()) {
+            return Err("simulate adalah server RTU; tidak bisa digabung snapshot/--serve".into());
+        }
+        // Daftar CLI menggantikan daftar berkas (seperti --casdu untuk [filter])
+        if let Some(p) = cli_follow_points { cfg.follow.points = p; }
+        if let Some(c) = cli_follow_casdus { cfg.follow.casdus = c; }
+        if cfg.follow.enabled {
+            if cfg.follow.interval < Duration::from_millis(100) {
+                return Err("interval follow minimal 0.1 detik".into());
+            }
+            if cfg.snapshot || cfg.pcap.is_some() {
+                return Err("follow menggambar aliran langsung terus-menerus; tidak bisa digabung snapshot/--pcap".into());
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// Muat berkas situs: satu INI per RTU berisi koneksi, ACK, filter, nama/scaling/alarm titik
+    /// dan sink, di atas default. Semua kesalahan dikumpulkan — sintaks, nilai, lalu referensi
+    /// silang antarseksi — agar satu kali jalan cukup untuk membetulkan berkas.
+    pub fn load_site(path: &str) -> Result<Config, Vec<String>> {
+        let mut cfg = Config {
+            config_mtime: std::fs::metadata(path).and_then(|m| m.modified()).ok(),
+            ..Config::default()
+        };
+        let text = std::fs::read_to_string(path).map_err(|e| vec![format!("gagal baca {}: {}", path, e)])?;
+        let ini = Ini::parse(&text);
+        let mut errors = ini.errors.clone();
+        errors.extend(cfg.apply_ini(&ini));
+        errors.extend(cfg.check_site(&ini));
+        if !errors.is_empty() {
+            // Urut baris: kesalahan sintaks, nilai dan referensi silang berselang-seling
+            errors.sort_by_key(|e| e.strip_prefix("baris ").and_then(|r| r.split(':').next()?.parse::<usize>().ok()));
+            return Err(errors);
+        }
+        cfg.config_path = Some(path.to_string());
+        Ok(cfg)
+    }
+
+    /// Gagal cepat bila dua server HTTP kita berebut port yang sama (alamat sama, atau salah
+    /// satunya wildcard). Port 0 dipilih kernel, jadi tidak pernah bentrok.
+    fn check_server_binds(&self) -> Result<(), String> {
+        let servers: Vec<(&str, &String)> = [("health", &self.health_addr), ("metrics", &self.metrics_addr)]
+            .into_iter()
+            .filter_map(|(name, addr)| addr.as_ref().map(|a| (name, a)))
+            .collect();
+        let mut resolved: Vec<(&str, SocketAddr)> = Vec::new();
+        for (name, addr) in servers {
+            let sa = addr
+                .to_socket_addrs()
+                .ok()
+                .and_then(|mut it| it.next())
+                .ok_or_else(|| format!("alamat server {} tidak bisa di-resolve: '{}'", name, addr))?;
+            for &(other, osa) in &resolved {
+                if sa.port() != 0 && sa.port() == osa.port()
+                    && (sa.ip() == osa.ip() || sa.ip().is_unspecified() || osa.ip().is_unspecified())
+                {
+                    return Err(format!("server {} ({}) bentrok port dengan server {} ({})", name, sa, other, osa));
+                }
+            }
+            resolved.push((name, sa));
+        }
+        Ok(())
+    }
+
+    pub fn output_options(&self) -> OutputOptions {
+        OutputOptions { csv: self.csv, invalid_as_null: self.invalid_as_null }
+    }
+
+    /// Pasang penangan SIGHUP untuk muat ulang --config; false bila tanpa berkas atau bukan unix.
+    pub fn watch_sighup(&self) -> std::io::Result<bool> {
+        if self.config_path.is_none() { return Ok(false); }
+        #[cfg(unix)]
+        {
+            let flag = SIGHUP.get_or_init(|| Arc::new(AtomicBool::new(false)));
+            signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(flag))?;
+            Ok(true)
+        }
+        #[cfg(not(unix))]
+        Ok(false)
+    }
+
+    /// Muat ulang berkas --config setelah SIGHUP, atau bila mtime-nya berubah (juga tanpa
+    /// sinyal, mis. di luar unix). Dicek tiap putaran read loop, jadi paling lambat satu
+    /// read timeout. Hanya filter & ambang runtime yang ditukar; socket, state sequence, sink,
+    /// dan format tetap. Ok(daftar perubahan), atau Err dengan konfigurasi lama tetap berlaku.
+    pub fn reload_if_changed(&mut self) -> Option<Result<Vec<String>, String>> {
+        let path = self.config_path.as_ref()?;
+        let hup = SIGHUP.get().is_some_and(|f| f.swap(false, Ordering::Relaxed));
+        let mtime = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        if !hup && Some(mtime) == self.config_mtime { return None; }
+        // Catat walau gagal agar berkas rusak tidak dilaporkan tiap putaran
+        self.config_mtime = Some(mtime);
+        let new = match Config::from_args() {
+            Ok(c) => c,
+            Err(e) => return Some(Err(e)),
+        };
+        let mut changes = Vec::new();
+        macro_rules! swap {
+            ($($field:ident),*) => {$(
+                if self.$field != new.$field {
+                    changes.push(format!("{}: {:?} -> {:?}", stringify!($field), self.$field, new.$field));
+                    self.$field = new.$field;
+                }
+            )*};
+        }
+        swap!(deadband, point_deadbands, origins, filter, names, scaling, alarms, ack, max_clock_skew, max_clock_jump, check_counter_seq, no_data_timeout, no_data_action, auto_gi, testfr, out_types);
+        Some(Ok(changes))
+    }
+
+    /// Terapkan semua entri; kesalahan per entri dikumpulkan, entri lain tetap diterapkan.
+    fn apply_ini(&mut self, ini: &Ini) -> Vec<String> {
+        ini.entries.iter().filter_map(|e| self.apply_entry(ini, e).err()).collect()
+    }
+
+    fn apply_entry(&mut self, ini: &Ini, e: &IniEntry) -> Result<(), String> {
+        let at = |msg: String| format!("baris {}: {}", e.line, msg);
+        match e.section.as_str() {
+            "rtu" => match e.key.as_str() {
+                "addr" if e.value.is_empty() => return Err(at("[rtu] addr kosong".into())),
+                "addr" => self.rtu_addr = e.value.clone(),
+                "bind" => self.bind = Some(parse_bind(&e.value).map_err(at)?),
+                k => return Err(at(format!("kunci tidak dikenal di [rtu]: {}", k))),
+            },
+            "deadband" => {
+                let db = Deadband::parse(&e.value).map_err(at)?;
+                if e.key == "default" {
+                    self.deadband = db;
+                } else {
+                    self.point_deadbands.insert(PointKey::parse(&e.key).map_err(at)?, db);
+                }
+            }
+            "output" => match e.key.as_str() {
+                "format" => self.format = OutputFormat::parse(&e.value).map_err(at)?,
+                "annotate_hex" => self.annotate_hex = parse_bool(&e.value).map_err(at)?,
+                "pretty" => self.pretty = parse_bool(&e.value).map_err(at)?,
+                "hexdump" => self.hexdump = parse_bool(&e.value).map_err(at)?,
+                "log_time" => self.log_time = LogTime::parse(&e.value).map_err(at)?,
+                "log_format" => self.log_format = LogFormat::parse(&e.value).map_err(at)?,
+                "log_stream" => self.log_stream = LogStream::parse(&e.value).map_err(at)?,
+                "log_changes_only" => self.log_changes_only = parse_bool(&e.value).map_err(at)?,
+                "origin" => self.origins = parse_origins(&e.value).map_err(at)?,
+                "invalid_as_null" => self.invalid_as_null = parse_bool(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [output]: {}", k))),
+            },
+            "csv" => match e.key.as_str() {
+                "delimiter" => self.csv.delimiter = CsvOptions::parse_delimiter(&e.value).map_err(at)?,
+                "quote" => self.csv.quote_all = match e.value.as_str() {
+                    "minimal" => false,
+                    "all" => true,
+                    v => return Err(at(format!("quote harus minimal|all: '{}'", v))),
+                },
+                "decimal" => self.csv.decimal_comma = match e.value.as_str() {
+                    "point" => false,
+                    "comma" => true,
+                    v => return Err(at(format!("decimal harus point|comma: '{}'", v))),
+                },
+                "timestamp" => self.csv.timestamp = CsvOptions::parse_timestamp(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [csv]: {}", k))),
+            },
+            "filter" => match e.key.as_str() {
+                "casdu" => self.filter.casdus = parse_casdus(&e.value).map_err(at)?,
+                "ioa_range" => self.filter.ioa_ranges = parse_ioa_ranges(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [filter]: {}", k))),
+            },
+            "time" => match e.key.as_str() {
+                "max_skew" => self.max_clock_skew = parse_secs_opt(&e.value).map_err(at)?,
+                "max_jump" => self.max_clock_jump = parse_secs_opt(&e.value).map_err(at)?,
+                "utc_offset" => self.rtu_clock.utc_offset_min = parse_utc_offset(&e.value).map_err(at)?,
+                "su_adds_hour" => self.rtu_clock.su_adds_hour = parse_bool(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [time]: {}", k))),
+            },
+            "tcp" => match e.key.as_str() {
+                "read_timeout" => self.read_timeout = parse_read_timeout(&e.value).map_err(at)?,
+                "write_timeout" => self.write_timeout = parse_secs_opt(&e.value).map_err(at)?,
+                "nodelay" => self.nodelay = parse_bool(&e.value).map_err(at)?,
+                "keepalive_idle" => self.keepalive.idle = parse_secs_opt(&e.value).map_err(at)?,
+                "keepalive_interval" => self.keepalive.interval = parse_secs_opt(&e.value).map_err(at)?,
+                "keepalive_count" => self.keepalive.count = Some(parse_keepalive_count(&e.value).map_err(at)?),
+                "max_buffer" => self.max_buffer_bytes = parse_max_buffer(&e.value).map_err(at)?,
+                "max_session" => self.max_session = parse_span(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [tcp]: {}", k))),
+            },
+            "tx" => match e.key.as_str() {
+                "max_fps" => self.tx_max_fps = parse_fps(&e.value).map_err(at)?,
+                "min_gap_ms" => self.tx_min_gap = parse_millis(&e.value).map_err(at)?,
+                "max_outstanding" => self.max_outstanding_tx = parse_k(&e.value).map_err(at)?,
+                "ack_mode" => self.ack.mode = AckMode::parse(&e.value).map_err(at)?,
+                "passive" => self.passive = parse_bool(&e.value).map_err(at)?,
+                "enable_tx" => self.enable_tx = parse_bool(&e.value).map_err(at)?,
+                "decode_only" => self.decode_only = parse_bool(&e.value).map_err(at)?,
+                "startdt" => self.startdt = parse_bool(&e.value).map_err(at)?,
+                "auto_gi" => self.auto_gi = parse_bool(&e.value).map_err(at)?,
+                "originator" => self.originator = Some(parse_oa(&e.value).map_err(at)?),
+                "forbid_types" | "allow_types" => {
+                    if ini.entries.iter().any(|o| o.line < e.line && o.section == "tx" && matches!(o.key.as_str(), "forbid_types" | "allow_types")) {
+                        return Err(at("[tx] forbid_types dan allow_types tidak boleh digabung".into()));
+                    }
+                    let ids = TypePolicy::parse_list(&e.value).map_err(at)?;
+                    self.out_types = if e.key == "forbid_types" { TypePolicy::Forbid(ids) } else { TypePolicy::Allow(ids) };
+                }
+                k => return Err(at(format!("kunci tidak dikenal di [tx]: {}", k))),
+            },
+            "ack" => match e.key.as_str() {
+                "mode" => self.ack.mode = AckMode::parse(&e.value).map_err(at)?,
+                "k" => self.ack.k = parse_k(&e.value).map_err(at)?,
+                "w" => self.ack.w = parse_w(&e.value).map_err(at)?,
+                "t2" => self.ack.t2 = parse_secs_frac(&e.value).map_err(at)?,
+                "emergency" => self.ack.emergency = parse_fraction(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [ack]: {}", k))),
+            },
+            "parse" => match e.key.as_str() {
+                "mode" => self.parse_mode = ParseMode::parse(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [parse]: {}", k))),
+            },
+            "asdu" => match e.key.as_str() {
+                "layout" => self.asdu.preset = LayoutPreset::parse(&e.value).map_err(at)?,
+                "cot_len" => self.asdu.cot = Some(parse_count(&e.value).map_err(at)?),
+                "casdu_len" => self.asdu.casdu = Some(parse_count(&e.value).map_err(at)?),
+                "ioa_len" => self.asdu.ioa = Some(parse_count(&e.value).map_err(at)?),
+                k => return Err(at(format!("kunci tidak dikenal di [asdu]: {}", k))),
+            },
+            "counters" => match e.key.as_str() {
+                "check_seq" => self.check_counter_seq = parse_bool(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [counters]: {}", k))),
+            },
+            "watchdog" => match e.key.as_str() {
+                "no_data_timeout" => self.no_data_timeout = parse_secs_opt(&e.value).map_err(at)?,
+                "no_data_action" => self.no_data_action = NoDataAction::parse(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [watchdog]: {}", k))),
+            },
+            "testfr" => match e.key.as_str() {
+                "enabled" => self.testfr.enabled = parse_bool(&e.value).map_err(at)?,
+                "t3" => self.testfr.t3 = parse_secs_frac(&e.value).map_err(at)?,
+                "t1" => self.testfr.t1 = parse_secs_frac(&e.value).map_err(at)?,
+                "retries" => self.testfr.retries = parse_retries(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [testfr]: {}", k))),
+            },
+            "follow" => match e.key.as_str() {
+                "enabled" => self.follow.enabled = parse_bool(&e.value).map_err(at)?,
+                "points" => self.follow.points = parse_points(&e.value).map_err(at)?,
+                "casdu" => self.follow.casdus = parse_casdus(&e.value).map_err(at)?,
+                "interval" => self.follow.interval = parse_secs_frac(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [follow]: {}", k))),
+            },
+            "simulate" => match e.key.as_str() {
+                "listen" => self.simulate.listen = parse_server_addr(&e.value).map_err(at)?.ok_or_else(|| at("[simulate] listen butuh host:port".into()))?,
+                "casdu" => self.simulate.casdu = parse_sim_casdu(&e.value).map_err(at)?,
+                "points" => self.simulate.points = parse_sim_points(&e.value).map_err(at)?,
+                "cycle" => self.simulate.cycle = parse_secs_frac(&e.value).map_err(at)?,
+                "event_rate" => self.simulate.event_rate = parse_fps(&e.value).map_err(at)?,
+                "seed" => self.simulate.seed = Some(parse_seed(&e.value).map_err(at)?),
+                k => return Err(at(format!("kunci tidak dikenal di [simulate]: {}", k))),
+            },
+            "stations" => {
+                let casdu = e.key.parse().map_err(|_| at(format!("casdu tidak valid: '{}'", e.key)))?;
+                self.names.stations.insert(casdu, e.value.clone());
+            }
+            "points" => {
+                self.names.points.insert(PointKey::parse(&e.key).map_err(at)?, e.value.clone());
+            }
+            "scaling" => {
+                let sc = Scaling::parse(&e.value).map_err(at)?;
+                self.scaling.entries.insert(PointKey::parse(&e.key).map_err(at)?, sc);
+            }
+            "alarms" => self.alarms.push(AlarmDef::parse(&e.key, &e.value).map_err(at)?),
+            "health" => match e.key.as_str() {
+                "addr" => self.health_addr = parse_server_addr(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [health]: {}", k))),
+            },
+            "metrics" => match e.key.as_str() {
+                "addr" => self.metrics_addr = parse_server_addr(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [metrics]: {}", k))),
+            },
+            "debug" => match e.key.as_str() {
+                "frame_history" => self.frame_history = parse_count(&e.value).map_err(at)?,
+                "dump_trigger" => self.dump_trigger = Some(e.value.clone()),
+                "stop_trigger" => self.stop_trigger = Some(e.value.clone()),
+                k => return Err(at(format!("kunci tidak dikenal di [debug]: {}", k))),
+            },
+            "delivery" => match e.key.as_str() {
+                "queue" => self.sink_queue = parse_count(&e.value).map_err(at)?.max(1),
+                "retries" => self.sink_retry.retries = e.value.parse().map_err(|_| at(format!("retries tidak valid: '{}'", e.value)))?,
+                "backoff_ms" => self.sink_retry.backoff = parse_millis(&e.value).map_err(at)?,
+                "shutdown_timeout" => self.sink_shutdown_timeout = parse_secs_frac(&e.value).map_err(at)?,
+                "dead_letter" => self.dead_letter = Some(e.value.clone()),
+                k => return Err(at(format!("kunci tidak dikenal di [delivery]: {}", k))),
+            },
+            "rotate" => match e.key.as_str() {
+                "max_size" => self.rotation.max_bytes = Rotation::parse_size(&e.value).map_err(at)?,
+                "gzip" => self.rotation.gzip = parse_bool(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [rotate]: {}", k))),
+            },
+            "files" => match e.key.as_str() {
+                "dir" => self.file_dir = Some(e.value.clone()),
+                "fetch" => {
+                    for f in e.value.split(',').filter(|f| !f.trim().is_empty()) {
+                        self.fetch_files.push(FileRequest::parse(f).map_err(at)?);
+                    }
+                }
+                "auto_fetch" => self.file_auto_fetch = parse_bool(&e.value).map_err(at)?,
+                k => return Err(at(format!("kunci tidak dikenal di [files]: {}", k))),
+            },
+            "events" => match e.key.as_str() {
+                "log" => self.event_log = Some(e.value.clone()),
+                "link_log" => self.link_events = Some(e.value.clone()),
+                k => return Err(at(format!("kunci tidak dikenal di [events]: {}", k))),
+            },
+            "sinks" => self.sinks.push(SinkSpec::parse(&e.key, &e.value).map_err(at)?),
+            other => return Err(at(format!("seksi tidak dikenal: [{}]", other))),
+        }
+        Ok(())
+    }
+
+    /// Referensi silang antarseksi yang tidak terlihat per baris: alamat ganda di seksi peta,
+    /// scaling/alarm/deadband untuk titik tanpa nama (bila [points] dipakai), casdu tanpa
+    /// [stations] (bila dipakai), dan titik bernama atau titik [follow] yang tidak akan pernah
+    /// lolos [filter].
+    fn check_site(&self, ini: &Ini) -> Vec<String> {
+        let mut errors = Vec::new();
+        let mut seen: HashMap<(&str, String), usize> = HashMap::new();
+        for e in &ini.entries {
+            let at = |msg: String| format!("baris {}: {}", e.line, msg);
+            let key = match e.section.as_str() {
+                "points" | "scaling" => PointKey::parse(&e.key).ok(),
+                "deadband" if e.key != "default" => PointKey::parse(&e.key).ok(),
+                "alarms" => e.value.split(',').next().and_then(|p| PointKey::parse(p).ok()),
+                _ => None,
+            };
+            // Alamat dinormalisasi ("14:0100" = "14:100"); nama alarm/sink dan casdu apa adanya
+            let id = match (e.section.as_str(), key) {
+                ("points" | "scaling" | "deadband", Some(k)) => Some(point_label(k)),
+                ("stations" | "alarms" | "sinks", _) => Some(e.key.clone()),
+                _ => None,
+            };
+            if let Some(id) = id {
+                if let Some(first) = seen.insert((e.section.as_str(), id.clone()), e.line) {
+                    errors.push(at(format!("[{}] {} sudah didefinisikan di baris {}", e.section, id, first)));
+                }
+            }
+            // Titik follow yang tidak pernah lolos [filter] tidak akan pernah terisi di tabel
+            if e.section == "follow" && e.key == "points" {
+                for k in parse_points(&e.value).unwrap_or_default() {
+                    errors.extend(self.check_point_refs(k).into_iter().map(at));
+                }
+            }
+            let Some(k) = key else { continue };
+            if e.section != "points" && !self.names.points.is_empty() && !self.names.has_point(k) {
+                errors.push(at(format!("[{}] {} tidak punya nama di [points]", e.section, point_label(k))));
+            }
+            errors.extend(self.check_point_refs(k).into_iter().map(at));
+        }
+        errors
+    }
+
+    /// casdu titik harus ada di [stations] (bila dipakai) dan lolos [filter].
+    fn check_point_refs(&self, k: PointKey) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Some(c) = k.casdu {
+            if !self.names.stations.is_empty() && !self.names.stations.contains_key(&c) {
+                errors.push(format!("titik {}: casdu {} tidak ada di [stations]", point_label(k), c));
+            }
+            if !self.filter.casdus.is_empty() && !self.filter.casdus.contains(&c) {
+                errors.push(format!("titik {}: casdu {} tidak lolos [filter] casdu", point_label(k), c));
+            }
+        }
+        if !self.filter.ioa_ranges.is_empty() && !self.filter.ioa_ranges.iter().any(|&(lo, hi)| (lo..=hi).contains(&k.ioa)) {
+            errors.push(format!("titik {}: IOA {} tidak lolos [filter] ioa_range", point_label(k), k.ioa));
+        }
+        errors
+    }
+}
+
+/// "14:1001" atau "1001" (semua casdu).
+fn point_label(k: PointKey) -> String {
+    match k.casdu {
+        Some(c) => format!("{}:{}", c, k.ioa),
+        None => k.ioa.to_string(),
+    }
+}
+
+// ================= Parser INI minimal =================
+pub struct IniEntry {
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+pub struct Ini {
+    pub entries: Vec<IniEntry>,
+    /// Baris yang bukan seksi maupun 'kunci = nilai'; baris lain tetap diurai.
+    pub errors: Vec<String>,
+}
+
+impl Ini {
+    pub fn parse(text: &str) -> Ini {
+        let mut entries = Vec::new();
+        let mut errors = Vec::new();
+        let mut section = String::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue; }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                continue;
+            }
+            let Some((k, v)) = line.split_once('=') else {
+                errors.push(format!("baris {}: harus 'kunci = nilai'", i + 1));
+                continue;
+            };
+            entries.push(IniEntry { section: section.clone(), key: k.trim().to_string(), value: v.trim().to_string(), line: i + 1 });
+        }
+        Ini { entries, errors }
+    }
+}
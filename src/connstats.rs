@@ -0,0 +1,139 @@
+//! Statistik kualitas koneksi untuk tinjauan link-health: jumlah sambung ulang, waktu
+//! tersambung vs putus, waktu dari TCP connect ke I-frame pertama, dan alasan tiap putus.
+//! Dicetak di ringkasan keluar dan di `/metrics` endpoint health.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct ConnStats {
+    started: Instant,
+    /// TCP connect yang berhasil; yang pertama bukan sambung ulang.
+    pub connects: u64,
+    pub connect_failures: u64,
+    connected_total: Duration,
+    connected_since: Option<Instant>,
+    awaiting_first_data: bool,
+    /// Waktu ke data pertama per koneksi: terakhir, minimum, maksimum, jumlah, cacah.
+    first_data_last: Option<Duration>,
+    first_data_min: Option<Duration>,
+    first_data_max: Duration,
+    first_data_sum: Duration,
+    first_data_count: u64,
+    /// Kunci alasan putus (lihat `Disconnect::key`) -> cacah.
+    pub disconnects: BTreeMap<&'static str, u64>,
+}
+
+impl ConnStats {
+    pub fn new() -> Self {
+        ConnStats {
+            started: Instant::now(),
+            connects: 0,
+            connect_failures: 0,
+            connected_total: Duration::ZERO,
+            connected_since: None,
+            awaiting_first_data: false,
+            first_data_last: None,
+            first_data_min: None,
+            first_data_max: Duration::ZERO,
+            first_data_sum: Duration::ZERO,
+            first_data_count: 0,
+            disconnects: BTreeMap::new(),
+        }
+    }
+
+    pub fn reconnects(&self) -> u64 { self.connects.saturating_sub(1) }
+
+    pub fn is_connected(&self) -> bool { self.connected_since.is_some() }
+
+    pub fn connected(&mut self) {
+        self.connects += 1;
+        self.connected_since = Some(Instant::now());
+        self.awaiting_first_data = true;
+    }
+
+    /// Dipanggil untuk setiap I-frame; hanya yang pertama per koneksi yang dicatat.
+    /// Mengembalikan true bila ini data pertama (status berubah, perlu disalin ke health).
+    pub fn data(&mut self) -> bool {
+        let Some(since) = self.connected_since.filter(|_| self.awaiting_first_data) else { return false };
+        self.awaiting_first_data = false;
+        let d = since.elapsed();
+        self.first_data_last = Some(d);
+        self.first_data_min = Some(self.first_data_min.map_or(d, |m| m.min(d)));
+        self.first_data_max = self.first_data_max.max(d);
+        self.first_data_sum += d;
+        self.first_data_count += 1;
+        true
+    }
+
+    /// Koneksi berakhir dengan alasan `reason`; gagal connect dihitung terpisah.
+    pub fn disconnected(&mut self, reason: &'static str) {
+        match self.connected_since.take() {
+            Some(since) => {
+                self.connected_total += since.elapsed();
+                *self.disconnects.entry(reason).or_default() += 1;
+            }
+            None => self.connect_failures += 1,
+        }
+    }
+
+    pub fn connected_time(&self) -> Duration {
+        self.connected_total + self.connected_since.map_or(Duration::ZERO, |s| s.elapsed())
+    }
+
+    pub fn downtime(&self) -> Duration { self.started.elapsed().saturating_sub(self.connected_time()) }
+
+    fn first_data_avg(&self) -> Option<Duration> {
+        (self.first_data_count > 0).then(|| self.first_data_sum / self.first_data_count as u32)
+    }
+
+    /// Baris untuk ringkasan keluar (kosong bila belum pernah mencoba connect).
+    pub fn summary(&self) -> Vec<String> {
+        if self.connects + self.connect_failures == 0 { return Vec::new(); }
+        let up = self.connected_time().as_secs_f64();
+        let total = self.started.elapsed().as_secs_f64().max(f64::EPSILON);
+        let mut lines = vec![format!(
+            "koneksi: {} sambung ulang, {} gagal connect, tersambung {:.0}s / putus {:.0}s ({:.1}% tersedia)",
+            self.reconnects(), self.connect_failures, up, self.downtime().as_secs_f64(), 100.0 * up / total
+        )];
+        if let (Some(avg), Some(min)) = (self.first_data_avg(), self.first_data_min) {
+            lines.push(format!(
+                "waktu ke data pertama: rata-rata {:.0} ms, min {:.0} ms, maks {:.0} ms ({} koneksi)",
+                ms(avg), ms(min), ms(self.first_data_max), self.first_data_count
+            ));
+        }
+        if !self.disconnects.is_empty() {
+            let reasons: Vec<String> = self.disconnects.iter().map(|(k, n)| format!("{}={}", k, n)).collect();
+            lines.push(format!("alasan putus: {}", reasons.join(", ")));
+        }
+        lines
+    }
+
+    /// Format teks eksposisi Prometheus.
+    pub fn metrics(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, value: String| {
+            let _ = write!(out, "# HELP iec104_{name} {help}\n# TYPE iec104_{name} {kind}\niec104_{name} {value}\n");
+        };
+        metric("connected", "gauge", "1 bila TCP ke RTU tersambung", (self.is_connected() as u8).to_string());
+        metric("connects_total", "counter", "TCP connect yang berhasil", self.connects.to_string());
+        metric("reconnects_total", "counter", "TCP connect setelah yang pertama", self.reconnects().to_string());
+        metric("connect_failures_total", "counter", "percobaan connect yang gagal", self.connect_failures.to_string());
+        metric("connected_seconds_total", "counter", "total waktu tersambung", format!("{:.3}", self.connected_time().as_secs_f64()));
+        metric("downtime_seconds_total", "counter", "total waktu tidak tersambung", format!("{:.3}", self.downtime().as_secs_f64()));
+        if let Some(last) = self.first_data_last {
+            metric("first_data_seconds", "gauge", "waktu connect -> I-frame pertama, koneksi terakhir", format!("{:.3}", last.as_secs_f64()));
+        }
+        let _ = write!(
+            out,
+            "# HELP iec104_disconnects_total koneksi putus per alasan\n# TYPE iec104_disconnects_total counter\n"
+        );
+        for (reason, n) in &self.disconnects {
+            let _ = writeln!(out, "iec104_disconnects_total{{reason=\"{}\"}} {}", reason, n);
+        }
+        out
+    }
+}
+
+fn ms(d: Duration) -> f64 { d.as_secs_f64() * 1000.0 }
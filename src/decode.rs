@@ -0,0 +1,1003 @@
+//! Dekode objek informasi (IOA + nilai + kualitas) dari ASDU arah monitor.
+//!
+//! Titik masuk untuk alat offline dan pengguna library: `parse_asdu_full(bytes, layout)`,
+//! tanpa socket maupun state link. `decode_asdu` = profil standar 104 (`AsduLayout::IEC104`).
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Nilai hasil dekode satu objek informasi.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjValue {
+    SinglePoint(bool),
+    DoublePoint(u8), // 0=indeterminate, 1=OFF, 2=ON, 3=indeterminate
+    StepPos { pos: i8, transient: bool }, // VTI: -64..+63, bit 7 = transient
+    Bitstring(u32),
+    Normalized(f32), // -1.0 .. +1.0
+    Scaled(i16),
+    Float(f32),
+    /// Integrated total (BCR): nilai + SQ (5 bit), nomor urut yang naik tiap freeze.
+    Counter { value: i32, seq: u8 },
+    /// Perintah (45/46/47 dan versi bertime tag): state = SCS/DCS/RCS, qu = qualifier pulsa
+    SingleCommand { on: bool, select: bool, qu: Qu },
+    DoubleCommand { dcs: u8, select: bool, qu: Qu },
+    RegulatingStep { direction: StepDirection, select: bool, qu: Qu },
+    /// Set-point (48/49/50): QOS = select (bit S/E) + ql (QL, 7 bit)
+    SetpointNormalized { value: f32, select: bool, ql: Ql },
+    SetpointScaled { value: i16, select: bool, ql: Ql },
+    SetpointFloat { value: f32, select: bool, ql: Ql },
+    /// Bitstring 32 bit (51/64): BSI saja; standar tidak punya qualifier/S-E untuk tipe ini.
+    BitstringCommand(u32),
+    /// Parameter ukur (110/111/112): ambang, faktor smoothing, batas kirim; QPM menyatakan jenisnya.
+    ParameterNormalized { value: f32, qpm: Qpm },
+    ParameterScaled { value: i16, qpm: Qpm },
+    ParameterFloat { value: f32, qpm: Qpm },
+    EndOfInit { coi: u8 },
+    Interrogation { qoi: u8 },
+    CounterInterrogation { qcc: u8 },
+    ClockSync, // waktunya ada di InformationObject::time
+}
+
+/// QU (qualifier of command, 5 bit): bentuk keluaran perintah. Trip pulsa vs sinyal yang
+/// ditahan penting bagi engineer proteksi, jadi dibedakan eksplisit, bukan angka mentah.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Qu {
+    /// 0: tanpa definisi tambahan; durasi pulsa ditentukan RTU.
+    Unspecified,
+    /// 1: pulsa pendek (durasi dari parameter sistem RTU).
+    ShortPulse,
+    /// 2: pulsa panjang (durasi dari parameter sistem RTU).
+    LongPulse,
+    /// 3: keluaran persisten (ditahan sampai perintah berikutnya).
+    Persistent,
+    /// 4..15: dicadangkan standar.
+    Reserved(u8),
+    /// 16..31: dicadangkan untuk pemakaian khusus (private).
+    Private(u8),
+}
+
+impl Qu {
+    pub fn from_bits(qu: u8) -> Qu {
+        match qu & 0x1F {
+            0 => Qu::Unspecified,
+            1 => Qu::ShortPulse,
+            2 => Qu::LongPulse,
+            3 => Qu::Persistent,
+            n @ 4..=15 => Qu::Reserved(n),
+            n => Qu::Private(n),
+        }
+    }
+
+    /// Nama (`unspecified`, `short`, `long`, `persistent`) atau angka 0..31.
+    pub fn parse(s: &str) -> Result<Qu, String> {
+        match s.trim() {
+            "unspecified" => Ok(Qu::Unspecified),
+            "short" | "short-pulse" => Ok(Qu::ShortPulse),
+            "long" | "long-pulse" => Ok(Qu::LongPulse),
+            "persistent" => Ok(Qu::Persistent),
+            n => match n.parse::<u8>() {
+                Ok(v) if v <= 31 => Ok(Qu::from_bits(v)),
+                _ => Err(format!("qu tidak valid: '{}' (unspecified|short|long|persistent|0..31)", n)),
+            },
+        }
+    }
+
+    /// Nilai 5 bit untuk ditulis kembali ke byte SCO/DCO/RCO (bit 2..6).
+    pub fn bits(self) -> u8 {
+        match self {
+            Qu::Unspecified => 0,
+            Qu::ShortPulse => 1,
+            Qu::LongPulse => 2,
+            Qu::Persistent => 3,
+            Qu::Reserved(n) | Qu::Private(n) => n & 0x1F,
+        }
+    }
+}
+
+impl fmt::Display for Qu {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Qu::Unspecified => write!(f, "unspecified"),
+            Qu::ShortPulse => write!(f, "short-pulse"),
+            Qu::LongPulse => write!(f, "long-pulse"),
+            Qu::Persistent => write!(f, "persistent"),
+            Qu::Reserved(n) => write!(f, "reserved({})", n),
+            Qu::Private(n) => write!(f, "private({})", n),
+        }
+    }
+}
+
+/// QL (qualifier of set-point command, bit 0..6 QOS). Standar hanya mendefinisikan 0; sisanya
+/// dibedakan cadangan vs privat agar nilai vendor terlihat sebagai vendor, bukan galat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ql {
+    /// 0: bawaan.
+    Default,
+    /// 1..63: dicadangkan standar.
+    Reserved(u8),
+    /// 64..127: dicadangkan untuk pemakaian khusus (private).
+    Private(u8),
+}
+
+impl Ql {
+    pub fn from_bits(ql: u8) -> Ql {
+        match ql & 0x7F {
+            0 => Ql::Default,
+            n @ 1..=63 => Ql::Reserved(n),
+            n => Ql::Private(n),
+        }
+    }
+
+    /// Nilai 7 bit untuk ditulis kembali ke byte QOS (bit 0..6).
+    pub fn bits(self) -> u8 {
+        match self {
+            Ql::Default => 0,
+            Ql::Reserved(n) | Ql::Private(n) => n & 0x7F,
+        }
+    }
+}
+
+impl fmt::Display for Ql {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Ql::Default => write!(f, "default"),
+            Ql::Reserved(n) => write!(f, "reserved({})", n),
+            Ql::Private(n) => write!(f, "private({})", n),
+        }
+    }
+}
+
+/// KPA (kind of parameter, bit 0..5 QPM) parameter ukur 110..112.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kpa {
+    /// 0: tidak dipakai.
+    Unused,
+    /// 1: ambang (deadband) kirim.
+    Threshold,
+    /// 2: faktor smoothing (konstanta waktu filter).
+    Smoothing,
+    /// 3: batas bawah kirim nilai ukur.
+    LowLimit,
+    /// 4: batas atas kirim nilai ukur.
+    HighLimit,
+    /// 5..31: dicadangkan standar.
+    Reserved(u8),
+    /// 32..63: dicadangkan untuk pemakaian khusus (private).
+    Private(u8),
+}
+
+impl Kpa {
+    pub fn from_bits(kpa: u8) -> Kpa {
+        match kpa & 0x3F {
+            0 => Kpa::Unused,
+            1 => Kpa::Threshold,
+            2 => Kpa::Smoothing,
+            3 => Kpa::LowLimit,
+            4 => Kpa::HighLimit,
+            n @ 5..=31 => Kpa::Reserved(n),
+            n => Kpa::Private(n),
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            Kpa::Unused => 0,
+            Kpa::Threshold => 1,
+            Kpa::Smoothing => 2,
+            Kpa::LowLimit => 3,
+            Kpa::HighLimit => 4,
+            Kpa::Reserved(n) | Kpa::Private(n) => n & 0x3F,
+        }
+    }
+}
+
+impl fmt::Display for Kpa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kpa::Unused => write!(f, "unused"),
+            Kpa::Threshold => write!(f, "threshold"),
+            Kpa::Smoothing => write!(f, "smoothing"),
+            Kpa::LowLimit => write!(f, "low-limit"),
+            Kpa::HighLimit => write!(f, "high-limit"),
+            Kpa::Reserved(n) => write!(f, "reserved({})", n),
+            Kpa::Private(n) => write!(f, "private({})", n),
+        }
+    }
+}
+
+/// QPM (qualifier of parameter of measured values): KPA + LPC (bit 6: parameter lokal
+/// berubah) + POP (bit 7: parameter tidak beroperasi).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Qpm {
+    pub kpa: Kpa,
+    pub lpc: bool,
+    pub pop: bool,
+}
+
+impl Qpm {
+    pub fn from_byte(b: u8) -> Qpm {
+        Qpm { kpa: Kpa::from_bits(b), lpc: b & 0x40 != 0, pop: b & 0x80 != 0 }
+    }
+
+    pub fn byte(self) -> u8 {
+        (self.pop as u8) << 7 | (self.lpc as u8) << 6 | self.kpa.bits()
+    }
+}
+
+impl fmt::Display for Qpm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kpa)?;
+        if self.lpc { write!(f, " local-change")?; }
+        if self.pop { write!(f, " not-in-operation")?; }
+        Ok(())
+    }
+}
+
+/// RCS (2 bit) perintah step (47/60), mis. tap changer trafo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepDirection {
+    /// 1: satu step turun.
+    Lower,
+    /// 2: satu step naik.
+    Higher,
+    /// 0 dan 3: tidak diizinkan standar.
+    Invalid(u8),
+}
+
+impl StepDirection {
+    pub fn from_bits(rcs: u8) -> StepDirection {
+        match rcs & 0x03 {
+            1 => StepDirection::Lower,
+            2 => StepDirection::Higher,
+            n => StepDirection::Invalid(n),
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        match self {
+            StepDirection::Lower => 1,
+            StepDirection::Higher => 2,
+            StepDirection::Invalid(n) => n & 0x03,
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<StepDirection, String> {
+        match s.trim() {
+            "lower" => Ok(StepDirection::Lower),
+            "higher" => Ok(StepDirection::Higher),
+            other => Err(format!("arah step tidak valid: '{}' (lower|higher)", other)),
+        }
+    }
+}
+
+impl fmt::Display for StepDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepDirection::Lower => write!(f, "LOWER"),
+            StepDirection::Higher => write!(f, "HIGHER"),
+            StepDirection::Invalid(n) => write!(f, "INVALID({})", n),
+        }
+    }
+}
+
+impl ObjValue {
+    /// Qualifier pulsa bila nilai ini perintah SCO/DCO/RCO.
+    pub fn qu(&self) -> Option<Qu> {
+        match *self {
+            ObjValue::SingleCommand { qu, .. } | ObjValue::DoubleCommand { qu, .. } | ObjValue::RegulatingStep { qu, .. } => Some(qu),
+            _ => None,
+        }
+    }
+
+    /// Nomor urut freeze (SQ pada BCR) bila nilai ini counter.
+    pub fn counter_seq(&self) -> Option<u8> {
+        match *self {
+            ObjValue::Counter { seq, .. } => Some(seq),
+            _ => None,
+        }
+    }
+
+    /// QL bila nilai ini set-point (QOS).
+    pub fn ql(&self) -> Option<Ql> {
+        match *self {
+            ObjValue::SetpointNormalized { ql, .. } | ObjValue::SetpointScaled { ql, .. } | ObjValue::SetpointFloat { ql, .. } => Some(ql),
+            _ => None,
+        }
+    }
+
+    /// QPM bila nilai ini parameter ukur.
+    pub fn qpm(&self) -> Option<Qpm> {
+        match *self {
+            ObjValue::ParameterNormalized { qpm, .. } | ObjValue::ParameterScaled { qpm, .. } | ObjValue::ParameterFloat { qpm, .. } => Some(qpm),
+            _ => None,
+        }
+    }
+
+    /// Nilai numerik untuk perbandingan deadband (hanya besaran analog & counter).
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            // f32 -> f64 lewat representasi desimal terpendek: 1.2f32 jadi 1.2, bukan 1.2000000476837158
+            ObjValue::Normalized(v) | ObjValue::Float(v) => v.to_string().parse().ok(),
+            ObjValue::Scaled(v) => Some(v as f64),
+            ObjValue::Counter { value, .. } => Some(value as f64),
+            ObjValue::StepPos { pos, .. } => Some(pos as f64),
+            _ => None,
+        }
+    }
+
+    /// Step position sedang bergerak (bit T VTI): posisi belum stabil, jangan dipakai sebagai nilai akhir.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, ObjValue::StepPos { transient: true, .. })
+    }
+}
+
+impl fmt::Display for ObjValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let se = |s: bool| if s { "select" } else { "execute" };
+        match self {
+            ObjValue::SinglePoint(v) => write!(f, "{}", if *v { "ON" } else { "OFF" }),
+            ObjValue::DoublePoint(1) => write!(f, "OFF"),
+            ObjValue::DoublePoint(2) => write!(f, "ON"),
+            ObjValue::DoublePoint(v) => write!(f, "INDET({})", v),
+            ObjValue::StepPos { pos, transient } => write!(f, "{}{}", pos, if *transient { " (transient)" } else { "" }),
+            ObjValue::Bitstring(v) => write!(f, "0x{:08X}", v),
+            ObjValue::Normalized(v) => write!(f, "{:.5}", v),
+            ObjValue::Scaled(v) => write!(f, "{}", v),
+            ObjValue::Float(v) => write!(f, "{}", v),
+            ObjValue::Counter { value, seq } => write!(f, "{} SQ={}", value, seq),
+            ObjValue::SingleCommand { on, select, qu } => write!(f, "SC {} {} qu={}", if *on { "ON" } else { "OFF" }, se(*select), qu),
+            ObjValue::DoubleCommand { dcs, select, qu } => write!(f, "DC dcs={} {} qu={}", dcs, se(*select), qu),
+            ObjValue::RegulatingStep { direction, select, qu } => write!(f, "RC {} {} qu={}", direction, se(*select), qu),
+            ObjValue::SetpointNormalized { value, select, ql } => write!(f, "SP {:.5} {} ql={}", value, se(*select), ql),
+            ObjValue::SetpointScaled { value, select, ql } => write!(f, "SP {} {} ql={}", value, se(*select), ql),
+            ObjValue::SetpointFloat { value, select, ql } => write!(f, "SP {} {} ql={}", value, se(*select), ql),
+            ObjValue::ParameterNormalized { value, qpm } => write!(f, "PM {:.5} {}", value, qpm),
+            ObjValue::ParameterScaled { value, qpm } => write!(f, "PM {} {}", value, qpm),
+            ObjValue::ParameterFloat { value, qpm } => write!(f, "PM {} {}", value, qpm),
+            ObjValue::BitstringCommand(v) => write!(f, "BO 0x{:08X}", v),
+            ObjValue::EndOfInit { coi } => write!(f, "COI={}", coi),
+            ObjValue::Interrogation { qoi } => write!(f, "QOI={}", qoi),
+            ObjValue::CounterInterrogation { qcc } => write!(f, "QCC={}", qcc),
+            ObjValue::ClockSync => write!(f, "clock-sync"),
+        }
+    }
+}
+
+/// QOI: 20 = station (global), 21..36 = grup 1..16.
+pub fn describe_qoi(qoi: u8) -> String {
+    match qoi {
+        20 => "20 (station)".into(),
+        21..=36 => format!("{} (grup {})", qoi, qoi - 20),
+        _ => format!("{} (tidak standar)", qoi),
+    }
+}
+
+/// QCC: RQT (bit 0..5: 1..4 grup, 5 umum) + FRZ (bit 6..7).
+pub fn describe_qcc(qcc: u8) -> String {
+    let rqt = match qcc & 0x3F {
+        1..=4 => format!("grup {}", qcc & 0x3F),
+        5 => "umum".into(),
+        r => format!("RQT {} tidak standar", r),
+    };
+    let frz = match qcc >> 6 {
+        0 => "baca",
+        1 => "freeze tanpa reset",
+        2 => "freeze dengan reset",
+        _ => "reset",
+    };
+    format!("{} ({}, {})", qcc, rqt, frz)
+}
+
+/// COI (M_EI_NA_1): sebab inisialisasi (bit 0..6: 0 power-on lokal, 1 reset manual lokal,
+/// 2 reset remote, 3..31 cadangan standar, 32..127 privat) + BS1 (bit 7): parameter lokal
+/// berubah sejak sebelum restart.
+pub fn describe_coi(coi: u8) -> String {
+    let cause = match coi & 0x7F {
+        0 => "power-on lokal".into(),
+        1 => "reset manual lokal".into(),
+        2 => "reset remote".into(),
+        c @ 3..=31 => format!("sebab cadangan {}", c),
+        c => format!("sebab privat {}", c),
+    };
+    let params = if coi & 0x80 != 0 { "parameter lokal berubah" } else { "parameter tidak berubah" };
+    format!("{} ({}, {})", coi, cause, params)
+}
+
+/// Bit kualitas bersama SIQ/DIQ/QDS/BCR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Quality {
+    pub iv: bool, // invalid
+    pub nt: bool, // not topical
+    pub sb: bool, // substituted
+    pub bl: bool, // blocked
+    pub ov: bool, // overflow (hanya QDS): nilai di luar rentang, jangan dipercaya
+}
+
+impl Quality {
+    /// SIQ/DIQ: bit 0..1 adalah nilai, bukan OV.
+    fn from_byte(b: u8) -> Self {
+        Quality { bl: b & 0x10 != 0, sb: b & 0x20 != 0, nt: b & 0x40 != 0, iv: b & 0x80 != 0, ov: false }
+    }
+
+    /// QDS (besaran terukur, step position, bitstring): bit 0 = OV.
+    fn from_qds(b: u8) -> Self {
+        Quality { ov: b & 0x01 != 0, ..Quality::from_byte(b) }
+    }
+
+    pub fn is_good(&self) -> bool { !(self.iv || self.nt || self.sb || self.bl || self.ov) }
+}
+
+impl fmt::Display for Quality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_good() { return write!(f, "OK"); }
+        let flags: Vec<&str> = [(self.iv, "IV"), (self.nt, "NT"), (self.sb, "SB"), (self.bl, "BL"), (self.ov, "OV")]
+            .iter().filter(|(on, _)| *on).map(|(_, n)| *n).collect();
+        write!(f, "{}", flags.join("|"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InformationObject {
+    pub ioa: u32,
+    pub value: ObjValue,
+    pub quality: Option<Quality>, // None untuk perintah/sistem
+    pub time: Option<Cp56Time2a>,
+    /// Time tag ada tetapi medan kalendernya di luar rentang: `time` = None, nilai tetap dipakai.
+    pub bad_time: Option<String>,
+}
+
+/// ASDU terdekode penuh: header + semua objek informasi.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAsdu {
+    pub type_id: u8,
+    pub sq: bool,
+    pub num_objects: u8,
+    pub cot: u8,
+    pub negative: bool,
+    pub test: bool,
+    /// Originator address; 0 bila COT hanya satu oktet.
+    pub oa: u8,
+    pub casdu: u16,
+    /// IOA tepat setelah header (dasar SQ=1) bila byte-nya ada, juga untuk type tanpa decoder.
+    pub first_ioa: Option<u32>,
+    pub objects: Vec<InformationObject>,
+    /// Objek yang dilewati karena elemennya tidak bisa didekode (alasan per objek).
+    pub skipped: Vec<String>,
+    pub status: AsduStatus,
+}
+
+/// Hasil pemeriksaan struktur ASDU terhadap VSQ dan ukuran objek per type id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AsduStatus {
+    Ok,
+    /// VSQ menjanjikan lebih banyak byte dari yang ada; hanya objek utuh yang didekode.
+    Truncated { expected: usize, actual: usize },
+    /// Ada byte sisa setelah objek terakhir.
+    Oversized { expected: usize, actual: usize },
+    /// Bahkan satu objek pun tidak utuh / VSQ nol: jangan dipakai sink.
+    Malformed(String),
+}
+
+impl AsduStatus {
+    /// Objek dari ASDU ini boleh diteruskan ke cache/sink?
+    pub fn is_usable(&self) -> bool { !matches!(self, AsduStatus::Malformed(_)) }
+}
+
+/// Panjang medan alamat ASDU dalam oktet. Profil standar 104: COT 2, CASDU 2, IOA 3; perangkat
+/// berprofil 101 atau gateway yang dikonfigurasi lain memakai medan yang lebih pendek.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AsduLayout {
+    pub cot: usize,
+    pub casdu: usize,
+    pub ioa: usize,
+}
+
+impl AsduLayout {
+    pub const IEC104: AsduLayout = AsduLayout { cot: 2, casdu: 2, ioa: 3 };
+
+    /// Rentang yang diizinkan IEC 60870-5-101/104: COT 1..2, CASDU 1..2, IOA 1..3.
+    pub fn validate(&self) -> Result<(), ParseError> {
+        if (1..=2).contains(&self.cot) && (1..=2).contains(&self.casdu) && (1..=3).contains(&self.ioa) {
+            Ok(())
+        } else {
+            Err(ParseError::Layout(*self))
+        }
+    }
+
+    /// Type id + VSQ + COT + CASDU.
+    pub fn header_len(&self) -> usize { 2 + self.cot + self.casdu }
+}
+
+/// ASDU yang tidak bisa diurai sama sekali. Masalah di tingkat objek (terpotong, sisa byte,
+/// elemen rusak) bukan error: dilaporkan di `DecodedAsdu::status`/`skipped`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Layout(AsduLayout),
+    /// Lebih pendek dari header ASDU.
+    TooShort { len: usize, header: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Layout(l) => write!(f, "panjang medan tidak valid: COT {} (1..2), CASDU {} (1..2), IOA {} (1..3)", l.cot, l.casdu, l.ioa),
+            ParseError::TooShort { len, header } => write!(f, "ASDU {} byte, lebih pendek dari header {} byte", len, header),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Panjang minimum ASDU (header + satu objek) untuk type id yang dikenal.
+pub fn min_asdu_len(type_id: u8) -> Option<usize> {
+    element_size(type_id).map(|size| 6 + 3 + size)
+}
+
+// ================= CP56Time2a =================
+/// Time tag 7 oktet: ms(2) | IV.res.menit | SU.res.jam | DOW.hari | bulan | tahun(0..99)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cp56Time2a {
+    pub year: u16, // 2000 + 0..99
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    pub ms: u16,   // detik*1000 + milidetik
+    pub dow: u8,   // 1..7, 0 = tidak dipakai
+    pub iv: bool,  // waktu ditandai invalid oleh RTU
+    pub su: bool,  // summer time
+}
+
+/// Cara menafsirkan jam RTU (CP56Time2a tidak membawa zona waktu).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RtuClock {
+    /// Offset waktu standar RTU terhadap UTC, menit (mis. +420 untuk UTC+7).
+    pub utc_offset_min: i32,
+    /// Bila true, bit SU=1 berarti offset ditambah 60 menit (waktu musim panas).
+    pub su_adds_hour: bool,
+}
+
+impl Cp56Time2a {
+    pub fn parse(b: &[u8]) -> Option<Cp56Time2a> {
+        if b.len() < 7 { return None; }
+        Some(Cp56Time2a {
+            ms: u16::from_le_bytes([b[0], b[1]]),
+            minute: b[2] & 0x3F,
+            iv: b[2] & 0x80 != 0,
+            hour: b[3] & 0x1F,
+            su: b[3] & 0x80 != 0,
+            day: b[4] & 0x1F,
+            dow: b[4] >> 5,
+            month: b[5] & 0x0F,
+            year: 2000 + (b[6] & 0x7F) as u16,
+        })
+    }
+
+    /// Medan kalender di luar rentang => byte time tag ngawur (salah offset/ASDU rusak).
+    pub fn check_ranges(&self) -> Result<(), String> {
+        if self.ms >= 60_000 || self.minute > 59 || self.hour > 23
+            || !(1..=31).contains(&self.day) || !(1..=12).contains(&self.month)
+        {
+            return Err(format!(
+                "CP56Time2a di luar rentang ({:04}-{:02}-{:02} {:02}:{:02} ms={})",
+                self.year, self.month, self.day, self.hour, self.minute, self.ms
+            ));
+        }
+        Ok(())
+    }
+
+    /// Susun time tag dari epoch ms (medan kalender = UTC + offset_min).
+    pub fn from_epoch_ms(ms: i64, offset_min: i32) -> Cp56Time2a {
+        let ms = ms + offset_min as i64 * 60_000;
+        let days = ms.div_euclid(86_400_000);
+        let rem = ms.rem_euclid(86_400_000);
+        let (y, m, d) = civil_from_days(days);
+        Cp56Time2a {
+            year: y.clamp(2000, 2099) as u16,
+            month: m as u8,
+            day: d as u8,
+            hour: (rem / 3_600_000) as u8,
+            minute: (rem / 60_000 % 60) as u8,
+            ms: (rem % 60_000) as u16,
+            dow: ((days + 3).rem_euclid(7) + 1) as u8, // 1970-01-01 = Kamis; Senin = 1
+            iv: false,
+            su: false,
+        }
+    }
+
+    pub fn to_bytes(self) -> [u8; 7] {
+        let ms = self.ms.to_le_bytes();
+        [
+            ms[0], ms[1],
+            self.minute | if self.iv { 0x80 } else { 0 },
+            self.hour | if self.su { 0x80 } else { 0 },
+            self.day | (self.dow << 5),
+            self.month,
+            (self.year.saturating_sub(2000) as u8) & 0x7F,
+        ]
+    }
+
+    /// Milidetik sejak epoch Unix, menganggap medan kalender sebagai UTC.
+    fn naive_epoch_ms(&self) -> i64 {
+        let days = days_from_civil(self.year as i64, self.month as i64, self.day as i64);
+        ((days * 24 + self.hour as i64) * 60 + self.minute as i64) * 60_000 + self.ms as i64
+    }
+
+    /// Milidetik epoch UTC setelah menerapkan offset zona RTU (dan SU bila dikonfigurasi).
+    pub fn utc_epoch_ms(&self, clk: &RtuClock) -> i64 {
+        let mut offset = clk.utc_offset_min as i64;
+        if clk.su_adds_hour && self.su { offset += 60; }
+        self.naive_epoch_ms() - offset * 60_000
+    }
+
+    /// Alasan bila waktu sumber patut dicurigai: bit IV, atau selisih terhadap jam lokal > max_skew.
+    pub fn suspect_reason(&self, clk: &RtuClock, max_skew: Option<Duration>) -> Option<String> {
+        if self.iv { return Some("bit IV aktif".into()); }
+        let max_skew = max_skew?;
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        let skew_ms = self.utc_epoch_ms(clk) - now_ms;
+        if skew_ms.unsigned_abs() > max_skew.as_millis() as u64 {
+            let dir = if skew_ms < 0 { "lalu" } else { "depan" };
+            return Some(format!("selisih {} s ke masa {} terhadap jam lokal", skew_ms.abs() / 1000, dir));
+        }
+        None
+    }
+}
+
+impl fmt::Display for Cp56Time2a {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}",
+            self.year, self.month, self.day, self.hour, self.minute,
+            self.ms / 1000, self.ms % 1000
+        )?;
+        if self.su { write!(f, " SU")?; }
+        Ok(())
+    }
+}
+
+/// Format epoch ms sebagai ISO-8601 UTC, mis. 2024-03-01T12:00:00.000Z
+pub fn format_epoch_ms(ms: i64) -> String {
+    let days = ms.div_euclid(86_400_000);
+    let rem = ms.rem_euclid(86_400_000);
+    let (y, m, d) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        y, m, d, rem / 3_600_000, rem / 60_000 % 60, rem / 1000 % 60, rem % 1000
+    )
+}
+
+/// Kebalikan days_from_civil.
+fn civil_from_days(z: i64) -> (i64, i64, i64) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    (yoe + era * 400 + if m <= 2 { 1 } else { 0 }, m, d)
+}
+
+/// Jumlah hari sejak 1970-01-01 untuk tanggal Gregorian (algoritma H. Hinnant).
+fn days_from_civil(y: i64, m: i64, d: i64) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Ukuran elemen informasi (tanpa IOA) per type id yang didukung.
+pub fn element_size(type_id: u8) -> Option<usize> {
+    let base = match type_id {
+        1 | 3 | 30 | 31 => 1,
+        5 | 32 => 2,
+        7 | 33 => 5,
+        9 | 11 | 34 | 35 => 3,
+        13 | 15 | 36 | 37 => 5,
+        45..=47 | 58..=60 => 1,
+        48 | 49 | 61 | 62 => 3,
+        50 | 63 => 5,
+        51 | 64 => 4,
+        110 | 111 => 3,
+        112 => 5,
+        70 | 100 | 101 => 1,
+        103 => 0,
+        _ => return None,
+    };
+    Some(if has_time_tag(type_id) { base + 7 } else { base })
+}
+
+/// Tipe yang elemennya diakhiri CP56Time2a.
+pub fn has_time_tag(type_id: u8) -> bool {
+    matches!(type_id, 30..=40 | 58..=64 | 103)
+}
+
+fn decode_element(type_id: u8, e: &[u8]) -> Option<(ObjValue, Option<Quality>)> {
+    let i16_at = |o: usize| i16::from_le_bytes([e[o], e[o + 1]]);
+    let f32_at = |o: usize| f32::from_le_bytes([e[o], e[o + 1], e[o + 2], e[o + 3]]);
+    let q = |b: u8| Some(Quality::from_byte(b));
+    let qds = |b: u8| Some(Quality::from_qds(b));
+    // Perintah: bit 7 = S/E, bit 2..6 = QU
+    let cmd = |b: u8| (b & 0x80 != 0, Qu::from_bits(b >> 2));
+    // Set-point: QOS = bit 7 S/E, bit 0..6 QL
+    let qos = |b: u8| (b & 0x80 != 0, Ql::from_bits(b));
+    let v = match type_id {
+        1 | 30 => (ObjValue::SinglePoint(e[0] & 0x01 != 0), q(e[0])),
+        3 | 31 => (ObjValue::DoublePoint(e[0] & 0x03), q(e[0])),
+        5 | 32 => {
+            // VTI: 7 bit bertanda (two's complement) + bit transient
+            let pos = ((e[0] << 1) as i8) >> 1;
+            (ObjValue::StepPos { pos, transient: e[0] & 0x80 != 0 }, qds(e[1]))
+        }
+        7 | 33 => (ObjValue::Bitstring(u32::from_le_bytes([e[0], e[1], e[2], e[3]])), qds(e[4])),
+        9 | 34 => (ObjValue::Normalized(i16_at(0) as f32 / 32768.0), qds(e[2])),
+        11 | 35 => (ObjValue::Scaled(i16_at(0)), qds(e[2])),
+        13 | 36 => (ObjValue::Float(f32_at(0)), qds(e[4])),
+        15 | 37 => {
+            // Byte ke-5: SQ(5 bit) | CY | CA | IV — hanya IV yang relevan untuk kualitas
+            let value = i32::from_le_bytes([e[0], e[1], e[2], e[3]]);
+            (ObjValue::Counter { value, seq: e[4] & 0x1F }, Some(Quality { iv: e[4] & 0x80 != 0, ..Quality::default() }))
+        }
+        45 | 58 => { let (select, qu) = cmd(e[0]); (ObjValue::SingleCommand { on: e[0] & 0x01 != 0, select, qu }, None) }
+        46 | 59 => { let (select, qu) = cmd(e[0]); (ObjValue::DoubleCommand { dcs: e[0] & 0x03, select, qu }, None) }
+        47 | 60 => { let (select, qu) = cmd(e[0]); (ObjValue::RegulatingStep { direction: StepDirection::from_bits(e[0]), select, qu }, None) }
+        48 | 61 => { let (select, ql) = qos(e[2]); (ObjValue::SetpointNormalized { value: i16_at(0) as f32 / 32768.0, select, ql }, None) }
+        49 | 62 => { let (select, ql) = qos(e[2]); (ObjValue::SetpointScaled { value: i16_at(0), select, ql }, None) }
+        50 | 63 => { let (select, ql) = qos(e[4]); (ObjValue::SetpointFloat { value: f32_at(0), select, ql }, None) }
+        51 | 64 => (ObjValue::BitstringCommand(u32::from_le_bytes([e[0], e[1], e[2], e[3]])), None),
+        70 => (ObjValue::EndOfInit { coi: e[0] }, None),
+        100 => (ObjValue::Interrogation { qoi: e[0] }, None),
+        101 => (ObjValue::CounterInterrogation { qcc: e[0] }, None),
+        103 => (ObjValue::ClockSync, None),
+        110 => (ObjValue::ParameterNormalized { value: i16_at(0) as f32 / 32768.0, qpm: Qpm::from_byte(e[2]) }, None),
+        111 => (ObjValue::ParameterScaled { value: i16_at(0), qpm: Qpm::from_byte(e[2]) }, None),
+        112 => (ObjValue::ParameterFloat { value: f32_at(0), qpm: Qpm::from_byte(e[4]) }, None),
+        _ => return None,
+    };
+    Some(v)
+}
+
+/// Dekode ASDU mentah profil standar 104 (mulai dari byte type id); None bila lebih pendek
+/// dari header. Tipe yang belum didukung menghasilkan daftar objek kosong.
+pub fn decode_asdu(asdu: &[u8]) -> Option<DecodedAsdu> {
+    parse_asdu_full(asdu, AsduLayout::IEC104).ok()
+}
+
+/// Dekode ASDU mentah (mulai dari byte type id) dengan panjang medan `layout` menjadi header +
+/// semua objek informasi. Tipe tanpa decoder menghasilkan daftar objek kosong; struktur yang
+/// tidak cocok dengan VSQ dilaporkan di `status`, objek rusak di `skipped`.
+pub fn parse_asdu_full(asdu: &[u8], layout: AsduLayout) -> Result<DecodedAsdu, ParseError> {
+    layout.validate()?;
+    let hdr = layout.header_len();
+    if asdu.len() < hdr {
+        return Err(ParseError::TooShort { len: asdu.len(), header: hdr });
+    }
+    // Medan alamat little-endian 1..3 oktet
+    let le = |b: &[u8]| b.iter().rev().fold(0u32, |v, &x| (v << 8) | x as u32);
+    let io = layout.ioa;
+    let type_id = asdu[0];
+    let mut d = DecodedAsdu {
+        type_id,
+        sq: asdu[1] & 0x80 != 0,
+        num_objects: asdu[1] & 0x7F,
+        cot: asdu[2] & 0x3F,
+        negative: asdu[2] & 0x40 != 0,
+        test: asdu[2] & 0x80 != 0,
+        oa: if layout.cot == 2 { asdu[3] } else { 0 },
+        casdu: le(&asdu[2 + layout.cot..hdr]) as u16,
+        first_ioa: asdu.get(hdr..hdr + io).map(le),
+        objects: Vec::new(),
+        skipped: Vec::new(),
+        status: AsduStatus::Ok,
+    };
+    let Some(size) = element_size(type_id) else { return Ok(d); };
+    let n = d.num_objects as usize;
+
+    // VSQ x ukuran objek harus pas dengan sisa byte; bila kurang, dekode yang muat saja.
+    // SQ=0: tiap objek membawa IOA sendiri. SQ=1: satu IOA dasar lalu n elemen berurutan.
+    let expected = if d.sq { hdr + io + n * size } else { hdr + n * (io + size) };
+    let actual = asdu.len();
+    d.status = if n == 0 {
+        AsduStatus::Malformed("VSQ menyatakan 0 objek".into())
+    } else if actual < hdr + io + size {
+        AsduStatus::Malformed(format!("type {} butuh minimal {} byte, ada {}", type_id, hdr + io + size, actual))
+    } else if actual < expected {
+        AsduStatus::Truncated { expected, actual }
+    } else if actual > expected {
+        AsduStatus::Oversized { expected, actual }
+    } else {
+        AsduStatus::Ok
+    };
+    if !d.status.is_usable() {
+        return Ok(d);
+    }
+
+    let base_ioa = le(&asdu[hdr..hdr + io]);
+    let ioa_mask = (1u32 << (8 * io)) - 1;
+    for i in 0..n {
+        // Objek ke-i: (IOA, elemen tanpa IOA); berhenti pada objek yang tidak utuh
+        let (ioa, e) = if d.sq {
+            let off = hdr + io + i * size;
+            let Some(e) = asdu.get(off..off + size) else { break; };
+            (base_ioa.wrapping_add(i as u32) & ioa_mask, e)
+        } else {
+            let off = hdr + i * (io + size);
+            let Some(obj) = asdu.get(off..off + io + size) else { break; };
+            (le(&obj[..io]), &obj[io..])
+        };
+        // Objek rusak dilewati dan dicatat; objek sesudahnya tetap didekode
+        let Some((value, quality)) = decode_element(type_id, e) else {
+            d.skipped.push(format!("objek #{} ioa={}: elemen tidak bisa didekode", i, ioa));
+            continue;
+        };
+        let time = if has_time_tag(type_id) { Cp56Time2a::parse(&e[e.len() - 7..]) } else { None };
+        // Time tag ngawur tidak membatalkan nilai: objek dipakai tanpa waktu, alasannya ditandai
+        let (time, bad_time) = match time.map(|t| t.check_ranges().map(|()| t)) {
+            Some(Err(why)) => (None, Some(why)),
+            t => (t.and_then(Result::ok), None),
+        };
+        d.objects.push(InformationObject { ioa, value, quality, time, bad_time });
+    }
+    Ok(d)
+}
+
+/// Kategori asal data berdasarkan COT.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    Periodic,     // COT=1
+    Background,   // COT=2
+    Spontaneous,  // COT=3
+    Requested,    // COT=5
+    Interrogated, // COT=20..=41 (station/grup, termasuk counter)
+    Other,
+}
+
+impl Origin {
+    pub fn from_cot(cot: u8) -> Origin {
+        match cot {
+            1 => Origin::Periodic,
+            2 => Origin::Background,
+            3 => Origin::Spontaneous,
+            5 => Origin::Requested,
+            20..=41 => Origin::Interrogated,
+            _ => Origin::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Origin::Periodic => "periodic",
+            Origin::Background => "background",
+            Origin::Spontaneous => "spontaneous",
+            Origin::Requested => "requested",
+            Origin::Interrogated => "interrogated",
+            Origin::Other => "other",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Origin, String> {
+        [Origin::Periodic, Origin::Background, Origin::Spontaneous, Origin::Requested, Origin::Interrogated, Origin::Other]
+            .into_iter()
+            .find(|o| o.as_str() == s.trim())
+            .ok_or_else(|| format!("kategori asal tidak dikenal: '{}'", s))
+    }
+}
+
+/// Satu titik siap diemit: objek informasi + konteks ASDU-nya.
+#[derive(Debug, Clone)]
+pub struct DecodedPoint {
+    pub casdu: u16,
+    pub type_id: u8,
+    pub cot: u8,
+    pub origin: Origin,
+    pub obj: InformationObject,
+    /// Waktu sumber dinormalisasi ke UTC (epoch ms) sesuai RtuClock.
+    pub time_utc_ms: Option<i64>,
+    pub suspect_time: bool,
+    /// Nama dari [stations]/[points] konfigurasi, bila ada.
+    pub station: Option<String>,
+    pub point: Option<String>,
+    /// Nilai satuan teknik (raw * scale + offset; identitas tanpa entri [scaling]), hanya nilai numerik.
+    pub value_eng: Option<f64>,
+    pub unit: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// M_SP_NA_1, SQ=0, dua objek: IOA 1001 ON, IOA 1002 OFF.
+    const SP_TWO: [u8; 14] = [0x01, 0x02, 0x03, 0x00, 0x01, 0x00, 0xE9, 0x03, 0x00, 0x01, 0xEA, 0x03, 0x00, 0x00];
+
+    #[test]
+    fn shorter_than_header_is_rejected() {
+        for len in 0..6 {
+            assert_eq!(parse_asdu_full(&SP_TWO[..len], AsduLayout::IEC104), Err(ParseError::TooShort { len, header: 6 }));
+            assert_eq!(decode_asdu(&SP_TWO[..len]), None);
+        }
+    }
+
+    #[test]
+    fn header_only_is_malformed() {
+        let d = decode_asdu(&SP_TWO[..6]).unwrap();
+        assert!(matches!(d.status, AsduStatus::Malformed(_)));
+        assert!(d.objects.is_empty());
+        assert_eq!(d.first_ioa, None);
+    }
+
+    #[test]
+    fn first_object_cut_mid_ioa_is_malformed() {
+        let d = decode_asdu(&SP_TWO[..8]).unwrap();
+        assert!(matches!(d.status, AsduStatus::Malformed(_)));
+        assert!(d.objects.is_empty());
+    }
+
+    #[test]
+    fn later_object_cut_mid_ioa_keeps_complete_objects() {
+        for len in 10..14 {
+            let d = decode_asdu(&SP_TWO[..len]).unwrap();
+            assert_eq!(d.status, AsduStatus::Truncated { expected: 14, actual: len });
+            assert_eq!(d.objects.len(), 1, "len {}", len);
+            assert_eq!((d.objects[0].ioa, d.objects[0].value), (1001, ObjValue::SinglePoint(true)));
+        }
+    }
+
+    #[test]
+    fn sequence_cut_mid_element_keeps_complete_elements() {
+        // M_ME_NB_1 SQ=1, tiga elemen 3 byte; elemen ketiga terpotong
+        let asdu = [0x0B, 0x83, 0x14, 0x00, 0x01, 0x00, 0xF4, 0x01, 0x00, 0x0A, 0x00, 0x00, 0x14, 0x00, 0x00, 0x1E];
+        let d = decode_asdu(&asdu).unwrap();
+        assert_eq!(d.status, AsduStatus::Truncated { expected: 18, actual: 16 });
+        assert_eq!(d.objects.iter().map(|o| o.ioa).collect::<Vec<_>>(), [500, 501]);
+    }
+
+    /// M_SP_TB_1, SQ=0: IOA 2003 dengan time tag sah, IOA 2004 dengan bulan 0.
+    const SP_TB_BAD_MONTH: [u8; 28] = [
+        0x1E, 0x02, 0x03, 0x00, 0x01, 0x00,
+        0xD3, 0x07, 0x00, 0x01, 0x24, 0x77, 0x14, 0x0A, 0xAF, 0x03, 0x18,
+        0xD4, 0x07, 0x00, 0x00, 0x24, 0x77, 0x14, 0x0A, 0xAF, 0x00, 0x18,
+    ];
+
+    #[test]
+    fn out_of_range_time_keeps_value_without_time() {
+        let d = decode_asdu(&SP_TB_BAD_MONTH).unwrap();
+        assert_eq!(d.status, AsduStatus::Ok);
+        assert!(d.skipped.is_empty());
+        assert_eq!(d.objects.len(), 2);
+        assert!(d.objects[0].time.is_some() && d.objects[0].bad_time.is_none());
+        let bad = &d.objects[1];
+        assert_eq!((bad.ioa, bad.value, bad.time), (2004, ObjValue::SinglePoint(false), None));
+        assert!(bad.bad_time.as_deref().is_some_and(|why| why.contains("di luar rentang")), "{:?}", bad.bad_time);
+    }
+
+    #[test]
+    fn garbage_object_run_keeps_every_value() {
+        // M_ME_TF_1, tiga objek 0xFF: ms=65535 di luar rentang, float NaN
+        let mut asdu = vec![0x24, 0x03, 0x03, 0x00, 0x01, 0x00];
+        asdu.extend([0xFF; 3 * 15]);
+        let d = decode_asdu(&asdu).unwrap();
+        assert_eq!(d.status, AsduStatus::Ok);
+        assert_eq!(d.objects.len(), 3);
+        assert!(d.objects.iter().all(|o| o.time.is_none() && o.bad_time.is_some()));
+        // Sampah yang juga terpotong: hanya objek utuh, tetap tanpa panic
+        let d = decode_asdu(&asdu[..6 + 15 + 7]).unwrap();
+        assert_eq!(d.status, AsduStatus::Truncated { expected: 51, actual: 28 });
+        assert_eq!(d.objects.len(), 1);
+    }
+
+    #[test]
+    fn every_prefix_of_every_type_decodes_without_panic() {
+        for type_id in 0..=255u8 {
+            let mut asdu = vec![type_id, 0x83, 0x03, 0x00, 0x01, 0x00];
+            asdu.extend((0..40u8).map(|b| b.wrapping_mul(37)));
+            for len in 0..=asdu.len() {
+                let _ = decode_asdu(&asdu[..len]);
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Mode `--dry-run`: gatekeeper TX sebagai alat lint. Frame usulan dibaca dari stdin, diperiksa
+//! strukturnya, lalu dinilai `TxPolicy` dengan konfigurasi yang sama seperti produksi — tanpa
+//! koneksi, tanpa kirim apa pun. Putusan ditampilkan dua kali bila ACK-only aktif: sekarang,
+//! dan setelah jalur kirim dibuka (`--enable-tx`).
+//!
+//! Baris masukan:
+//! ```text
+//! 68 0E 00 00 00 00 64 01 06 00 01 00 00 00 00 14   # APDU lengkap (hex)
+//! asdu 2D 01 06 00 01 00 88 13 00 81                # ASDU saja, dibungkus build_i_frame
+//! gi 1 20                                           # builder GI: casdu, qoi
+//! sc 1 5000 on short select                         # builder C_SC_NA_1: casdu, ioa, on|off, [qu], [select]
+//! rc 1 5002 higher long                             # builder C_RC_NA_1: casdu, ioa, lower|higher, [qu], [select]
+//! bo 1 6000 0x0000FF00                              # builder C_BO_NA_1: casdu, ioa, nilai 32 bit (desimal/0x..)
+//! ```
+
+use std::io::BufRead;
+
+use crate::config::TypePolicy;
+use crate::decode::{decode_asdu, AsduStatus, ObjValue, Qu, StepDirection};
+use crate::repl::parse_hex;
+use crate::{build_bo_asdu, build_gi_asdu, build_i_frame, build_rc_asdu, build_sc_asdu, classify_apdu, describe_frame, hex, is_unknown_type, TxPolicy, MAX_ASDU_LEN};
+
+pub fn run(types: &TypePolicy, passive: bool, ack_only: bool) -> std::io::Result<()> {
+    println!("Dry-run: baca frame dari stdin (APDU hex | asdu <hex> | gi <casdu> <qoi> | sc|rc <casdu> <ioa> <state> [qu] [select] | bo <casdu> <ioa> <u32>); tidak ada yang dikirim.");
+    println!("Kebijakan tipe keluar: {}{}", types, if passive { "; mode pasif (semua TX diblok)" } else { "" });
+    let (mut allowed, mut blocked, mut invalid) = (0, 0, 0);
+    for (n, line) in std::io::stdin().lock().lines().enumerate() {
+        let line = line?;
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+        let apdu = match build(line) {
+            Ok(a) => a,
+            Err(e) => {
+                invalid += 1;
+                println!("\n[{}] {}\n  TIDAK VALID: {}", n + 1, line, e);
+                continue;
+            }
+        };
+        println!("\n[{}] > TX (dry-run) {}", n + 1, hex(&apdu));
+        println!("  = {}", describe_frame(&classify_apdu(&apdu)));
+        let problems = validate(&apdu);
+        for p in &problems {
+            println!("  struktur: {}", p);
+        }
+        if !problems.is_empty() {
+            invalid += 1;
+            println!("  TIDAK VALID: {} masalah struktur.", problems.len());
+            continue;
+        }
+        let verdict = if passive { Err("mode pasif: semua TX diblok.".to_string()) } else { TxPolicy::enforce_rules(&apdu, types, ack_only) };
+        match &verdict {
+            Ok(()) => { allowed += 1; println!("  DIIZINKAN: lolos gatekeeper."); }
+            Err(e) => { blocked += 1; println!("  DIBLOK: {}", e); }
+        }
+        // Yang sebenarnya ingin diketahui sebelum --enable-tx dipasang
+        if ack_only && !passive && verdict.is_err() {
+            match TxPolicy::enforce_rules(&apdu, types, false) {
+                Ok(()) => println!("  tanpa ACK-only: DIIZINKAN."),
+                Err(e) => println!("  tanpa ACK-only: tetap DIBLOK: {}", e),
+            }
+        }
+    }
+    println!("\nRingkasan dry-run: {} diizinkan, {} diblok, {} tidak valid.", allowed, blocked, invalid);
+    Ok(())
+}
+
+/// Satu baris masukan -> APDU. N(S)/N(R) builder = 0 (nilai sebenarnya diisi saat kirim).
+fn build(line: &str) -> Result<Vec<u8>, String> {
+    let mut words = line.split_whitespace();
+    match words.next() {
+        Some("asdu") => {
+            let asdu = parse_hex(&words.collect::<Vec<_>>().join(""))?;
+            if asdu.len() > MAX_ASDU_LEN {
+                return Err(format!("ASDU {} byte melebihi {} byte", asdu.len(), MAX_ASDU_LEN));
+            }
+            Ok(build_i_frame(0, 0, &asdu))
+        }
+        Some("gi") => {
+            let casdu = words.next().unwrap_or("1");
+            let qoi = words.next().unwrap_or("20");
+            let casdu = casdu.parse().map_err(|_| format!("casdu tidak valid: '{}'", casdu))?;
+            let qoi = qoi.parse().map_err(|_| format!("qoi tidak valid: '{}'", qoi))?;
+            Ok(build_i_frame(0, 0, &build_gi_asdu(casdu, qoi)))
+        }
+        Some("sc") => {
+            let (casdu, ioa, state, qu, select) = command_args(words, "sc", "on|off")?;
+            let on = match state {
+                "on" => true,
+                "off" => false,
+                s => return Err(format!("state tidak valid: '{}' (on|off)", s)),
+            };
+            Ok(build_i_frame(0, 0, &build_sc_asdu(casdu, ioa, on, select, qu)))
+        }
+        Some("bo") => {
+            let (casdu, ioa, value, qu, select) = command_args(words, "bo", "<u32>")?;
+            if select || qu != Qu::Unspecified {
+                return Err("bo tidak punya qualifier/select (C_BO_NA_1 hanya BSI 32 bit)".into());
+            }
+            let parsed = match value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")) {
+                Some(h) => u32::from_str_radix(h, 16),
+                None => value.parse(),
+            };
+            let value = parsed.map_err(|_| format!("nilai 32 bit tidak valid: '{}'", value))?;
+            Ok(build_i_frame(0, 0, &build_bo_asdu(casdu, ioa, value)))
+        }
+        Some("rc") => {
+            let (casdu, ioa, state, qu, select) = command_args(words, "rc", "lower|higher")?;
+            Ok(build_i_frame(0, 0, &build_rc_asdu(casdu, ioa, StepDirection::parse(state)?, select, qu)))
+        }
+        _ => parse_hex(line),
+    }
+}
+
+/// `<casdu> <ioa> <state> [qu] [select]` builder perintah -> (casdu, ioa, state, qu, select).
+fn command_args<'a>(mut words: impl Iterator<Item = &'a str>, cmd: &str, states: &str) -> Result<(u16, u32, &'a str, Qu, bool), String> {
+    let (Some(casdu), Some(ioa), Some(state)) = (words.next(), words.next(), words.next()) else {
+        return Err(format!("{} butuh <casdu> <ioa> {}", cmd, states));
+    };
+    let casdu = casdu.parse().map_err(|_| format!("casdu tidak valid: '{}'", casdu))?;
+    let ioa = match ioa.parse::<u32>() {
+        Ok(v) if v <= 0xFF_FFFF => v,
+        _ => return Err(format!("ioa tidak valid: '{}'", ioa)),
+    };
+    let (mut qu, mut select) = (Qu::Unspecified, false);
+    for w in words {
+        if w == "select" { select = true; } else { qu = Qu::parse(w)?; }
+    }
+    Ok((casdu, ioa, state, qu, select))
+}
+
+/// Validasi struktur di luar aturan gatekeeper: framing APCI dan isi ASDU.
+fn validate(apdu: &[u8]) -> Vec<String> {
+    let mut out = Vec::new();
+    if apdu.len() < 6 {
+        out.push(format!("APDU {} byte, minimal 6", apdu.len()));
+        return out;
+    }
+    if apdu[0] != 0x68 {
+        out.push(format!("byte awal 0x{:02X}, harus 0x68", apdu[0]));
+    }
+    if apdu[1] as usize + 2 != apdu.len() {
+        out.push(format!("LEN={} tidak cocok dengan panjang {} byte", apdu[1], apdu.len()));
+    }
+    let c0 = apdu[2];
+    if c0 & 0b01 == 0 {
+        // I-frame: harus membawa ASDU yang bisa didekode
+        let asdu = &apdu[6..];
+        match decode_asdu(asdu) {
+            None => out.push(format!("ASDU {} byte, header saja butuh 6", asdu.len())),
+            Some(d) => {
+                if d.cot == 0 { out.push("COT 0 tidak terdefinisi".into()); }
+                if d.casdu == 0 { out.push("CASDU 0 tidak dipakai".into()); }
+                if is_unknown_type(d.type_id) {
+                    out.push(format!("type {} tidak dikenal / tanpa decoder", d.type_id));
+                } else if d.status != AsduStatus::Ok {
+                    out.push(format!("ASDU {:?}", d.status));
+                }
+                for o in &d.objects {
+                    if let ObjValue::RegulatingStep { direction: StepDirection::Invalid(rcs), .. } = o.value {
+                        out.push(format!("IOA {}: RCS={} tidak diizinkan standar (1 = lower, 2 = higher)", o.ioa, rcs));
+                    }
+                }
+            }
+        }
+    } else if apdu.len() != 6 {
+        out.push("S-/U-frame tidak boleh membawa ASDU".into());
+    } else if c0 & 0b11 == 0b11 && (c0 >> 2).count_ones() != 1 {
+        out.push(format!("U-frame 0x{:02X}: tepat satu fungsi harus aktif", c0));
+    }
+    out
+}
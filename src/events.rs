@@ -0,0 +1,83 @@
+//! Pencatat SOE lokal: hanya transisi titik status (tipe 1/3 dan versi bertime tag),
+//! perubahan IV, dan alarm muncul/pulih, terpisah dari aliran nilai ukur.
+//! Status sebelumnya diambil dari `PointCache`.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::cache::{AlarmDef, AlarmKind};
+use crate::decode::{format_epoch_ms, DecodedPoint, ObjValue, Quality};
+
+pub struct EventLog {
+    out: BufWriter<File>,
+    pub count: u64,
+}
+
+impl EventLog {
+    pub fn open(path: &str) -> std::io::Result<EventLog> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(EventLog { out: BufWriter::new(file), count: 0 })
+    }
+
+    /// `prev` = isi cache sebelum update. Titik baru (None) bukan transisi: nilai awal GI
+    /// tidak dicatat sebagai kejadian.
+    pub fn record(&mut self, p: &DecodedPoint, prev: Option<(ObjValue, Option<Quality>)>) -> std::io::Result<()> {
+        if !matches!(p.obj.value, ObjValue::SinglePoint(_) | ObjValue::DoublePoint(_)) { return Ok(()); }
+        let Some((last_value, last_quality)) = prev else { return Ok(()) };
+        let iv = |q: Option<Quality>| q.is_some_and(|q| q.iv);
+        let value_changed = last_value != p.obj.value;
+        let iv_changed = iv(last_quality) != iv(p.obj.quality);
+        if !value_changed && !iv_changed { return Ok(()); }
+
+        let mut line = header(p);
+        if value_changed {
+            line += &format!(" {} -> {}", last_value, p.obj.value);
+        } else {
+            line += &format!(" {}", p.obj.value);
+        }
+        if iv_changed {
+            let q = |q: Option<Quality>| q.map(|q| q.to_string()).unwrap_or_else(|| "-".into());
+            line += &format!(" kualitas {} -> {}", q(last_quality), q(p.obj.quality));
+        }
+        self.write_line(&line)
+    }
+
+    /// Alarm muncul (`raised`) atau pulih untuk titik `p`.
+    pub fn alarm(&mut self, def: &AlarmDef, raised: bool, p: &DecodedPoint) -> std::io::Result<()> {
+        let line = format!("{} ALARM {} {}", header(p), def.name, alarm_detail(def, raised, p));
+        self.write_line(&line)
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        writeln!(self.out, "{}", line)?;
+        // Kejadian jarang dan berharga: jangan tertahan di buffer saat proses mati
+        self.out.flush()?;
+        self.count += 1;
+        Ok(())
+    }
+}
+
+/// "MUNCUL nilai=92.5 degC (>= 90)" / "PULIH nilai=84 degC (< 85)"
+pub fn alarm_detail(def: &AlarmDef, raised: bool, p: &DecodedPoint) -> String {
+    let (op, th) = match (def.kind, raised) {
+        (AlarmKind::High, true) => (">=", def.set),
+        (AlarmKind::High, false) => ("<", def.reset),
+        (AlarmKind::Low, true) => ("<=", def.set),
+        (AlarmKind::Low, false) => (">", def.reset),
+    };
+    let value = p.value_eng.map(|v| v.to_string()).unwrap_or_else(|| p.obj.value.to_string());
+    let unit = p.unit.as_deref().map(|u| format!(" {}", u)).unwrap_or_default();
+    format!("{} nilai={}{} ({} {})", if raised { "MUNCUL" } else { "PULIH" }, value, unit, op, th)
+}
+
+/// Waktu sumber bila ada (SOE sebenarnya), selain itu waktu terima; lalu alamat & nama titik.
+fn header(p: &DecodedPoint) -> String {
+    let (ms, src) = match p.time_utc_ms {
+        Some(ms) => (ms, "src"),
+        None => (SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0), "rx"),
+    };
+    let mut line = format!("{} {} casdu={} ioa={}", format_epoch_ms(ms), src, p.casdu, p.obj.ioa);
+    if let Some(name) = &p.point { line += &format!(" \"{}\"", name); }
+    line
+}
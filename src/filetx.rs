@@ -0,0 +1,334 @@
+//! Transfer berkas arah monitor (tipe 120..126), mis. rekaman gangguan (disturbance) dari RTU:
+//!
+//! ```text
+//! master                          RTU
+//! F_SC_NA_1 SCQ=1 pilih berkas ->
+//!                              <- F_FR_NA_1 berkas siap (LOF)
+//! F_SC_NA_1 SCQ=2 minta berkas ->
+//!                              <- F_SR_NA_1 seksi siap (NOS, LOS)
+//! F_SC_NA_1 SCQ=6 minta seksi  ->
+//!                              <- F_SG_NA_1 segmen ... F_LS_NA_1 LSQ=3 (CHS seksi)
+//! F_AF_NA_1 AFQ=3 ACK seksi    ->
+//!                              <- (seksi berikutnya ...) F_LS_NA_1 LSQ=1 (CHS berkas)
+//! F_AF_NA_1 AFQ=1 ACK berkas   ->
+//! ```
+//!
+//! Checksum = jumlah oktet mod 256. Satu transfer aktif sekaligus; permintaan lain diantre.
+//! ASDU keluar tetap lewat gatekeeper TX (diblok selama ACK-only tanpa `--enable-tx`).
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::decode::format_epoch_ms;
+
+pub const TYPE_F_FR_NA_1: u8 = 120; // file ready
+pub const TYPE_F_SR_NA_1: u8 = 121; // section ready
+pub const TYPE_F_SC_NA_1: u8 = 122; // call directory, select file, call file, call section
+pub const TYPE_F_LS_NA_1: u8 = 123; // last section, last segment
+pub const TYPE_F_AF_NA_1: u8 = 124; // ack file, ack section
+pub const TYPE_F_SG_NA_1: u8 = 125; // segment
+pub const TYPE_F_DR_TA_1: u8 = 126; // directory
+
+const COT_SPONT: u8 = 3;
+const COT_FILE: u8 = 13;
+
+// SCQ (select and call qualifier)
+const SCQ_SELECT_FILE: u8 = 1;
+const SCQ_REQUEST_FILE: u8 = 2;
+const SCQ_REQUEST_SECTION: u8 = 6;
+// LSQ (last section or segment qualifier)
+const LSQ_FILE_NO_DEACT: u8 = 1;
+const LSQ_FILE_DEACT: u8 = 2;
+const LSQ_SECTION_NO_DEACT: u8 = 3;
+const LSQ_SECTION_DEACT: u8 = 4;
+// AFQ (acknowledge file or section qualifier)
+const AFQ_FILE_POS: u8 = 1;
+const AFQ_FILE_NEG: u8 = 2;
+const AFQ_SECTION_POS: u8 = 3;
+const AFQ_SECTION_NEG: u8 = 4;
+
+/// Batas tunggu jawaban RTU per langkah sebelum transfer dibatalkan.
+const STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Berkas yang akan ditarik: "casdu:ioa:nof", mis. "1:5000:2" (NOF 2 = disturbance data).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileRequest {
+    pub casdu: u16,
+    pub ioa: u32,
+    pub nof: u16,
+}
+
+impl FileRequest {
+    pub fn parse(s: &str) -> Result<FileRequest, String> {
+        let bad = || format!("berkas tidak valid: '{}' (casdu:ioa:nof)", s.trim());
+        let mut parts = s.trim().split(':');
+        let mut next = || parts.next().map(str::trim).ok_or_else(bad);
+        let casdu = next()?.parse().map_err(|_| bad())?;
+        let ioa: u32 = next()?.parse().map_err(|_| bad())?;
+        let nof = next()?.parse().map_err(|_| bad())?;
+        if ioa > 0xFF_FFFF || parts.next().is_some() { return Err(bad()); }
+        Ok(FileRequest { casdu, ioa, nof })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Selected,           // F_SC pilih berkas terkirim, tunggu F_FR
+    FileCalled,         // F_SC minta berkas terkirim, tunggu F_SR
+    SectionCalled(u8),  // F_SC minta seksi terkirim, terima F_SG sampai F_LS seksi
+}
+
+struct Active {
+    req: FileRequest,
+    phase: Phase,
+    lof: u32,
+    data: Vec<u8>,
+    section: Vec<u8>,
+    last_step: Instant,
+}
+
+pub struct FileTransfer {
+    dir: PathBuf,
+    /// Ikut tarik berkas yang diumumkan RTU lewat direktori spontan (F_DR_TA_1).
+    auto_fetch: bool,
+    queue: VecDeque<FileRequest>,
+    active: Option<Active>,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+/// ASDU keluar: (label log, bytes ASDU).
+pub type Outgoing = (String, Vec<u8>);
+
+impl FileTransfer {
+    pub fn new(dir: &str, auto_fetch: bool, initial: &[FileRequest]) -> Self {
+        Self {
+            dir: PathBuf::from(dir), auto_fetch, queue: initial.iter().copied().collect(),
+            active: None, completed: 0, failed: 0,
+        }
+    }
+
+    /// Koneksi baru: transfer setengah jalan tidak bisa dilanjutkan, antre ulang dari awal.
+    pub fn reset(&mut self) {
+        if let Some(a) = self.active.take() {
+            logln!("(Berkas) transfer {} terputus koneksi; diantre ulang.", describe(&a.req));
+            self.queue.push_front(a.req);
+        }
+    }
+
+    /// Mulai transfer berikutnya bila tidak ada yang aktif.
+    pub fn poll(&mut self) -> Option<Outgoing> {
+        if self.active.is_some() { return None; }
+        let req = self.queue.pop_front()?;
+        logln!("(Berkas) memulai transfer {}.", describe(&req));
+        self.active = Some(Active {
+            req, phase: Phase::Selected, lof: 0, data: Vec::new(), section: Vec::new(), last_step: Instant::now(),
+        });
+        Some(select_call(&req, 0, SCQ_SELECT_FILE, "pilih berkas"))
+    }
+
+    pub fn check_timeout(&mut self) {
+        if let Some(a) = &self.active {
+            if a.last_step.elapsed() > STEP_TIMEOUT {
+                logln!("(Peringatan) transfer {} tidak dijawab RTU dalam {:?} (fase {:?}); dibatalkan.", describe(&a.req), STEP_TIMEOUT, a.phase);
+                self.active = None;
+                self.failed += 1;
+            }
+        }
+    }
+
+    /// Proses satu ASDU (header 6 byte + objek). Mengembalikan ASDU balasan untuk dikirim.
+    pub fn on_asdu(&mut self, asdu: &[u8]) -> Vec<Outgoing> {
+        let Some(&type_id) = asdu.first() else { return Vec::new() };
+        if !(TYPE_F_FR_NA_1..=TYPE_F_DR_TA_1).contains(&type_id) || asdu.len() < 9 { return Vec::new(); }
+        let cot = asdu[2] & 0x3F;
+        let casdu = u16::from_le_bytes([asdu[4], asdu[5]]);
+        let ioa = u32::from_le_bytes([asdu[6], asdu[7], asdu[8], 0]);
+        let e = &asdu[9..];
+
+        if type_id == TYPE_F_DR_TA_1 {
+            self.on_directory(asdu, cot == COT_SPONT);
+            return Vec::new();
+        }
+        let Some(a) = self.active.as_mut() else {
+            logln!("(Berkas) ASDU tipe {} casdu={} ioa={} tanpa transfer aktif, diabaikan.", type_id, casdu, ioa);
+            return Vec::new();
+        };
+        if casdu != a.req.casdu || ioa != a.req.ioa || e.len() < 2 || u16::from_le_bytes([e[0], e[1]]) != a.req.nof {
+            logln!("(Berkas) ASDU tipe {} casdu={} ioa={} bukan untuk transfer {}, diabaikan.", type_id, casdu, ioa, describe(&a.req));
+            return Vec::new();
+        }
+        a.last_step = Instant::now();
+        let req = a.req;
+
+        match (type_id, a.phase) {
+            // NOF(2) LOF(3) FRQ(1)
+            (TYPE_F_FR_NA_1, Phase::Selected) if e.len() >= 6 => {
+                if e[5] & 0x80 != 0 {
+                    return self.fail(&format!("RTU menolak pilih berkas (FRQ=0x{:02X})", e[5]));
+                }
+                a.lof = u32::from_le_bytes([e[2], e[3], e[4], 0]);
+                a.phase = Phase::FileCalled;
+                logln!("(Berkas) {} siap, {} byte.", describe(&req), a.lof);
+                vec![select_call(&req, 0, SCQ_REQUEST_FILE, "minta berkas")]
+            }
+            // NOF(2) NOS(1) LOS(3) SRQ(1)
+            (TYPE_F_SR_NA_1, Phase::FileCalled) if e.len() >= 7 => {
+                let (nos, los) = (e[2], u32::from_le_bytes([e[3], e[4], e[5], 0]));
+                if e[6] & 0x80 != 0 {
+                    return self.fail(&format!("seksi {} tidak siap (SRQ=0x{:02X})", nos, e[6]));
+                }
+                a.phase = Phase::SectionCalled(nos);
+                a.section.clear();
+                logln!("(Berkas) seksi {} siap, {} byte.", nos, los);
+                vec![select_call(&req, nos, SCQ_REQUEST_SECTION, "minta seksi")]
+            }
+            // NOF(2) NOS(1) LOS(1) segmen(LOS)
+            (TYPE_F_SG_NA_1, Phase::SectionCalled(nos)) if e.len() >= 4 => {
+                let len = e[3] as usize;
+                match e.get(4..4 + len) {
+                    Some(seg) if e[2] == nos => a.section.extend_from_slice(seg),
+                    Some(_) => logln!("(Peringatan) segmen untuk seksi {} saat menunggu seksi {}, diabaikan.", e[2], nos),
+                    None => logln!("(Peringatan) segmen terpotong: LOS={} tetapi {} byte.", len, e.len() - 4),
+                }
+                Vec::new()
+            }
+            // NOF(2) NOS(1) LSQ(1) CHS(1)
+            (TYPE_F_LS_NA_1, phase) if e.len() >= 5 => {
+                let (nos, lsq, chs) = (e[2], e[3], e[4]);
+                match (lsq, phase) {
+                    (LSQ_SECTION_NO_DEACT | LSQ_SECTION_DEACT, Phase::SectionCalled(want)) if nos == want => {
+                        a.phase = Phase::FileCalled;
+                        let sum = checksum(&a.section);
+                        if sum != chs {
+                            logln!("(Peringatan) checksum seksi {} salah: dihitung 0x{:02X}, RTU 0x{:02X}; ACK negatif.", nos, sum, chs);
+                            a.section.clear();
+                            return vec![ack(&req, nos, AFQ_SECTION_NEG, "NACK seksi")];
+                        }
+                        logln!("(Berkas) seksi {} lengkap, {} byte.", nos, a.section.len());
+                        let section = std::mem::take(&mut a.section);
+                        a.data.extend_from_slice(&section);
+                        vec![ack(&req, nos, AFQ_SECTION_POS, "ACK seksi")]
+                    }
+                    (LSQ_FILE_NO_DEACT | LSQ_FILE_DEACT, Phase::FileCalled) => {
+                        let a = self.active.take().expect("transfer aktif");
+                        let sum = checksum(&a.data);
+                        if sum != chs {
+                            self.failed += 1;
+                            logln!("(Peringatan) checksum berkas {} salah: dihitung 0x{:02X}, RTU 0x{:02X}; tidak disimpan.", describe(&req), sum, chs);
+                            return vec![ack(&req, 0, AFQ_FILE_NEG, "NACK berkas")];
+                        }
+                        if a.data.len() as u32 != a.lof {
+                            logln!("(Peringatan) panjang berkas {} byte, RTU mengumumkan {}.", a.data.len(), a.lof);
+                        }
+                        match self.save(&req, &a.data) {
+                            Ok(path) => {
+                                self.completed += 1;
+                                logln!("(Berkas) {} tersimpan: {} ({} byte).", describe(&req), path.display(), a.data.len());
+                            }
+                            Err(e) => {
+                                self.failed += 1;
+                                logln!("(Peringatan) gagal menyimpan berkas {}: {}", describe(&req), e);
+                            }
+                        }
+                        vec![ack(&req, 0, AFQ_FILE_POS, "ACK berkas")]
+                    }
+                    _ => {
+                        logln!("(Peringatan) F_LS_NA_1 NOS={} LSQ={} tidak cocok dengan fase {:?}, diabaikan.", nos, lsq, phase);
+                        Vec::new()
+                    }
+                }
+            }
+            (_, phase) => {
+                logln!("(Peringatan) ASDU tipe {} tidak diharapkan pada fase {:?} (atau terlalu pendek), diabaikan.", type_id, phase);
+                Vec::new()
+            }
+        }
+    }
+
+    /// NOF(2) LOF(3) SOF(1) CP56Time2a(7) per entri; SOF bit 6 = subdirektori, bit 7 = berkas aktif.
+    fn on_directory(&mut self, asdu: &[u8], spontaneous: bool) {
+        const SIZE: usize = 13;
+        let casdu = u16::from_le_bytes([asdu[4], asdu[5]]);
+        let sq = asdu[1] & 0x80 != 0;
+        let n = (asdu[1] & 0x7F) as usize;
+        let ioa_at = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], 0]);
+        for i in 0..n {
+            let (ioa, e) = if sq {
+                let off = 9 + i * SIZE;
+                let Some(e) = asdu.get(off..off + SIZE) else { break };
+                (ioa_at(&asdu[6..9]).wrapping_add(i as u32) & 0xFF_FFFF, e)
+            } else {
+                let off = 6 + i * (3 + SIZE);
+                let Some(o) = asdu.get(off..off + 3 + SIZE) else { break };
+                (ioa_at(o), &o[3..])
+            };
+            let req = FileRequest { casdu, ioa, nof: u16::from_le_bytes([e[0], e[1]]) };
+            let lof = u32::from_le_bytes([e[2], e[3], e[4], 0]);
+            let sof = e[5];
+            logln!("(Berkas) direktori: {} {} byte SOF=0x{:02X}", describe(&req), lof, sof);
+            // Subdirektori dan berkas yang masih ditulis RTU tidak ditarik
+            if spontaneous && self.auto_fetch && sof & 0xC0 == 0 && lof > 0
+                && !self.queue.contains(&req) && self.active.as_ref().map(|a| a.req) != Some(req)
+            {
+                self.queue.push_back(req);
+            }
+        }
+    }
+
+    /// Batalkan transfer aktif, mis. karena ASDU keluarnya diblok gatekeeper TX.
+    pub fn abort(&mut self, why: &str) {
+        if let Some(a) = self.active.take() {
+            logln!("(Peringatan) transfer {} gagal: {}.", describe(&a.req), why);
+            self.failed += 1;
+        }
+    }
+
+    fn fail(&mut self, why: &str) -> Vec<Outgoing> {
+        self.abort(why);
+        Vec::new()
+    }
+
+    /// Tulis ke `.part` lalu rename, agar pembaca tidak pernah melihat berkas setengah jadi.
+    fn save(&self, req: &FileRequest, data: &[u8]) -> std::io::Result<PathBuf> {
+        std::fs::create_dir_all(&self.dir)?;
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        let stamp: String = format_epoch_ms(now_ms).chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        let path = self.dir.join(format!("casdu{}_ioa{}_nof{}_{}.bin", req.casdu, req.ioa, req.nof, stamp));
+        let part = path.with_extension("bin.part");
+        std::fs::write(&part, data)?;
+        std::fs::rename(&part, &path)?;
+        Ok(path)
+    }
+}
+
+fn describe(req: &FileRequest) -> String {
+    format!("casdu={} ioa={} nof={}", req.casdu, req.ioa, req.nof)
+}
+
+fn checksum(data: &[u8]) -> u8 {
+    data.iter().fold(0u8, |s, b| s.wrapping_add(*b))
+}
+
+fn header(type_id: u8, req: &FileRequest) -> Vec<u8> {
+    let ca = req.casdu.to_le_bytes();
+    let ioa = req.ioa.to_le_bytes();
+    vec![type_id, 0x01, COT_FILE, 0x00, ca[0], ca[1], ioa[0], ioa[1], ioa[2]]
+}
+
+/// F_SC_NA_1: NOF(2) NOS(1) SCQ(1)
+fn select_call(req: &FileRequest, nos: u8, scq: u8, what: &str) -> Outgoing {
+    let mut a = header(TYPE_F_SC_NA_1, req);
+    a.extend_from_slice(&req.nof.to_le_bytes());
+    a.extend_from_slice(&[nos, scq]);
+    (format!("F_SC_NA_1 {} {} nos={}", what, describe(req), nos), a)
+}
+
+/// F_AF_NA_1: NOF(2) NOS(1) AFQ(1)
+fn ack(req: &FileRequest, nos: u8, afq: u8, what: &str) -> Outgoing {
+    let mut a = header(TYPE_F_AF_NA_1, req);
+    a.extend_from_slice(&req.nof.to_le_bytes());
+    a.extend_from_slice(&[nos, afq]);
+    (format!("F_AF_NA_1 {} {} nos={}", what, describe(req), nos), a)
+}
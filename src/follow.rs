@@ -0,0 +1,137 @@
+//! Mode `--follow`: tabel titik terpilih digambar ulang di tempat (layar dibersihkan, kursor ke
+//! kiri atas — seperti `top`) dari cache titik tiap `[follow] interval`. Selama mode ini baris
+//! `logln!` ditahan; beberapa yang terakhir (tanpa jejak RX/TX per frame) tampil di bawah tabel.
+//! Hanya escape ANSI, tanpa crate terminal: lebar kolom tetap, nilai panjang dipotong.
+
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::cache::PointCache;
+use crate::config::Config;
+use crate::decode::format_epoch_ms;
+use crate::handler::ConnState;
+use crate::logging;
+
+/// Baris log terakhir di bawah tabel.
+const LOG_LINES: usize = 6;
+const CLEAR: &str = "\x1b[H\x1b[2J";
+
+pub struct FollowView {
+    last_draw: Option<Instant>,
+}
+
+impl FollowView {
+    /// Mulai menahan log; tabel pertama digambar pada `tick` berikutnya.
+    pub fn start() -> FollowView {
+        logging::capture(LOG_LINES);
+        FollowView { last_draw: None }
+    }
+
+    /// Dipanggil tiap putaran read loop; gambar ulang bila interval sudah lewat.
+    pub fn tick(&mut self, cfg: &Config, cache: &PointCache, state: ConnState) {
+        if self.last_draw.is_some_and(|t| t.elapsed() < cfg.follow.interval) { return; }
+        self.draw(cfg, cache, state);
+    }
+
+    pub fn draw(&mut self, cfg: &Config, cache: &PointCache, state: ConnState) {
+        let mut out = String::from(CLEAR);
+        out += &render(cfg, cache, state, Instant::now());
+        let mut stdout = std::io::stdout().lock();
+        let _ = stdout.write_all(out.as_bytes()).and_then(|_| stdout.flush());
+        self.last_draw = Some(Instant::now());
+    }
+
+    /// Tabel terakhir dibiarkan di layar; log kembali ke stdout (ringkasan keluar di bawahnya).
+    pub fn finish(mut self, cfg: &Config, cache: &PointCache) {
+        self.draw(cfg, cache, ConnState::Disconnected);
+        logging::release();
+    }
+}
+
+struct Row {
+    casdu: Option<u16>,
+    ioa: u32,
+    name: String,
+    value: String,
+    eng: String,
+    quality: String,
+    age: String,
+}
+
+fn render(cfg: &Config, cache: &PointCache, state: ConnState, now: Instant) -> String {
+    let sel = &cfg.follow;
+    let mut rows: Vec<Row> = cache.points().iter()
+        .filter(|((casdu, ioa), _)| sel.selects(*casdu, *ioa))
+        .map(|(&(casdu, ioa), e)| {
+            let scaling = cfg.scaling.get(casdu, ioa);
+            let eng = match (e.value.as_f64(), scaling) {
+                (Some(raw), Some(s)) => format!("{}{}", s.apply(raw), s.unit.as_deref().map(|u| format!(" {}", u)).unwrap_or_default()),
+                _ => "-".into(),
+            };
+            Row {
+                casdu: Some(casdu), ioa,
+                name: cfg.names.point(casdu, ioa).unwrap_or("").to_string(),
+                value: e.value.to_string(),
+                eng,
+                quality: e.quality.map_or_else(|| "-".into(), |q| q.to_string()),
+                age: age(now.saturating_duration_since(e.seen)),
+            }
+        })
+        .collect();
+    let received = rows.len();
+    // Titik yang diminta tetapi belum pernah diterima tetap tampil: yang belum datang saat
+    // commissioning justru yang dicari
+    for k in &sel.points {
+        if rows.iter().any(|r| r.casdu.is_some_and(|c| k.matches(c, r.ioa))) { continue; }
+        rows.push(Row {
+            casdu: k.casdu, ioa: k.ioa,
+            name: cfg.names.point_for(*k).unwrap_or("").to_string(),
+            value: "(belum diterima)".into(),
+            eng: String::new(), quality: String::new(), age: String::new(),
+        });
+    }
+    rows.sort_by_key(|r| (r.casdu.is_none(), r.casdu, r.ioa));
+
+    let wall = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+    let mut out = String::new();
+    let _ = writeln!(out, "IEC 104 follow  RTU {}  status {}  {}", cfg.rtu_addr, state, format_epoch_ms(wall));
+    let _ = writeln!(
+        out, "{} titik ({} belum diterima), refresh {:?}; nilai = terakhir lolos deadband, umur = update terakhir",
+        rows.len(), rows.len() - received, sel.interval
+    );
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{:>5} {:>8}  {:<20} {:<24} {:<16} {:<12} {:>7}", "CASDU", "IOA", "NAMA", "NILAI", "TEKNIK", "KUALITAS", "UMUR");
+    for r in &rows {
+        let _ = writeln!(
+            out, "{:>5} {:>8}  {:<20} {:<24} {:<16} {:<12} {:>7}",
+            r.casdu.map_or_else(|| "*".into(), |c| c.to_string()), r.ioa,
+            fit(&r.name, 20), fit(&r.value, 24), fit(&r.eng, 16), fit(&r.quality, 12), r.age
+        );
+    }
+    let tail = logging::tail();
+    if !tail.is_empty() {
+        let _ = writeln!(out, "\nLog terakhir:");
+        for line in tail { let _ = writeln!(out, "  {}", line); }
+    }
+    out
+}
+
+/// Potong ke `w` karakter (bukan byte: nama titik boleh non-ASCII).
+fn fit(s: &str, w: usize) -> String {
+    if s.chars().count() <= w { return s.to_string(); }
+    let mut cut: String = s.chars().take(w - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// "0.4s", "12s", "3m05s", "2h07m"
+fn age(d: Duration) -> String {
+    let s = d.as_secs();
+    match s {
+        0..=9 => format!("{:.1}s", d.as_secs_f64()),
+        10..=59 => format!("{}s", s),
+        60..=3599 => format!("{}m{:02}s", s / 60, s % 60),
+        _ => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+    }
+}
@@ -0,0 +1,249 @@
+//! Kompresi gzip (RFC 1952) tanpa dependensi untuk segmen log yang sudah dirotasi.
+//!
+//! Deflate satu blok Huffman tetap (RFC 1951 §3.2.6) dengan LZ77 hash-chain: rasio lebih
+//! rendah dari zlib, tetapi log JSON/CSV yang berulang tetap menyusut beberapa kali lipat.
+
+use std::path::{Path, PathBuf};
+
+const WINDOW: usize = 32 * 1024;
+const MIN_MATCH: usize = 3;
+const MAX_MATCH: usize = 258;
+const MAX_CHAIN: usize = 64;
+const HASH_BITS: u32 = 15;
+
+const LEN_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LEN_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+
+/// Kompres `path` menjadi `path.gz`, lalu hapus aslinya. Mengembalikan path hasil.
+pub fn compress_file(path: &Path) -> std::io::Result<PathBuf> {
+    let data = std::fs::read(path)?;
+    let mut gz_name = path.as_os_str().to_owned();
+    gz_name.push(".gz");
+    let gz = PathBuf::from(gz_name);
+    let part = gz.with_extension("gz.part");
+    std::fs::write(&part, gzip(&data))?;
+    std::fs::rename(&part, &gz)?;
+    std::fs::remove_file(path)?;
+    Ok(gz)
+}
+
+pub fn gzip(data: &[u8]) -> Vec<u8> {
+    // ID1 ID2 CM=deflate FLG=0 MTIME=0 XFL=0 OS=255 (tidak diketahui)
+    let mut out = vec![0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF];
+    out.extend_from_slice(&deflate(data));
+    out.extend_from_slice(&crc32(data).to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out
+}
+
+struct BitWriter {
+    out: Vec<u8>,
+    acc: u32,
+    n: u32,
+}
+
+impl BitWriter {
+    /// Bit ditulis mulai LSB (urutan medan deflate).
+    fn bits(&mut self, value: u32, count: u32) {
+        self.acc |= value << self.n;
+        self.n += count;
+        while self.n >= 8 {
+            self.out.push(self.acc as u8);
+            self.acc >>= 8;
+            self.n -= 8;
+        }
+    }
+
+    /// Kode Huffman ditulis mulai MSB.
+    fn code(&mut self, code: u32, len: u32) {
+        let rev = code.reverse_bits() >> (32 - len);
+        self.bits(rev, len);
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.n > 0 { self.out.push(self.acc as u8); }
+        self.out
+    }
+}
+
+/// Kode Huffman tetap untuk simbol literal/panjang 0..287.
+fn write_litlen(w: &mut BitWriter, sym: u32) {
+    match sym {
+        0..=143 => w.code(0x30 + sym, 8),
+        144..=255 => w.code(0x190 + sym - 144, 9),
+        256..=279 => w.code(sym - 256, 7),
+        _ => w.code(0xC0 + sym - 280, 8),
+    }
+}
+
+fn write_match(w: &mut BitWriter, len: usize, dist: usize) {
+    let li = LEN_BASE.iter().rposition(|&b| b as usize <= len).expect("len >= 3");
+    write_litlen(w, 257 + li as u32);
+    w.bits((len - LEN_BASE[li] as usize) as u32, LEN_EXTRA[li] as u32);
+    let di = DIST_BASE.iter().rposition(|&b| b as usize <= dist).expect("dist >= 1");
+    w.code(di as u32, 5);
+    w.bits((dist - DIST_BASE[di] as usize) as u32, DIST_EXTRA[di] as u32);
+}
+
+fn deflate(data: &[u8]) -> Vec<u8> {
+    let mut w = BitWriter { out: Vec::with_capacity(data.len() / 2), acc: 0, n: 0 };
+    w.bits(1, 1); // BFINAL
+    w.bits(1, 2); // BTYPE=01 Huffman tetap
+    // head[hash] = posisi terakhir dengan 3 byte awal ber-hash sama; prev[i] = posisi sebelumnya
+    let mut head = vec![usize::MAX; 1 << HASH_BITS];
+    let mut prev = vec![usize::MAX; data.len()];
+    let hash = |i: usize| {
+        let v = (data[i] as u32) << 16 | (data[i + 1] as u32) << 8 | data[i + 2] as u32;
+        (v.wrapping_mul(2_654_435_761) >> (32 - HASH_BITS)) as usize
+    };
+    let insert = |i: usize, head: &mut [usize], prev: &mut [usize]| {
+        if i + MIN_MATCH <= data.len() {
+            let h = hash(i);
+            prev[i] = head[h];
+            head[h] = i;
+        }
+    };
+
+    let mut i = 0;
+    while i < data.len() {
+        let (mut best_len, mut best_dist) = (0, 0);
+        if i + MIN_MATCH <= data.len() {
+            let mut cand = head[hash(i)];
+            let max = (data.len() - i).min(MAX_MATCH);
+            for _ in 0..MAX_CHAIN {
+                if cand == usize::MAX || i - cand > WINDOW { break; }
+                let len = data[cand..].iter().zip(&data[i..i + max]).take_while(|(a, b)| a == b).count();
+                if len > best_len {
+                    (best_len, best_dist) = (len, i - cand);
+                    if len == max { break; }
+                }
+                cand = prev[cand];
+            }
+        }
+        if best_len >= MIN_MATCH {
+            write_match(&mut w, best_len, best_dist);
+            for j in i..i + best_len { insert(j, &mut head, &mut prev); }
+            i += best_len;
+        } else {
+            write_litlen(&mut w, data[i] as u32);
+            insert(i, &mut head, &mut prev);
+            i += 1;
+        }
+    }
+    write_litlen(&mut w, 256); // akhir blok
+    w.finish()
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut table = [0u32; 256];
+    for (n, t) in table.iter_mut().enumerate() {
+        let mut c = n as u32;
+        for _ in 0..8 {
+            c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+        }
+        *t = c;
+    }
+    !data.iter().fold(!0u32, |c, &b| table[((c ^ b as u32) & 0xFF) as usize] ^ (c >> 8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pembaca bit deflate (mulai LSB).
+    struct BitReader<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl BitReader<'_> {
+        fn bits(&mut self, count: u32) -> u32 {
+            (0..count).fold(0, |v, k| {
+                let bit = (self.data[self.pos / 8] >> (self.pos % 8)) & 1;
+                self.pos += 1;
+                v | (bit as u32) << k
+            })
+        }
+
+        /// Kode Huffman dibaca mulai MSB, satu bit per langkah.
+        fn code(&mut self, len: u32) -> u32 {
+            (0..len).fold(0, |v, _| v << 1 | self.bits(1))
+        }
+
+        fn litlen(&mut self) -> u32 {
+            let v = self.code(7);
+            if v <= 0x17 { return 256 + v; }
+            let v = v << 1 | self.bits(1);
+            match v {
+                0x30..=0xBF => v - 0x30,
+                0xC0..=0xC7 => 280 + v - 0xC0,
+                _ => 144 + (v << 1 | self.bits(1)) - 0x190,
+            }
+        }
+    }
+
+    /// Inflate minimal untuk satu blok Huffman tetap, cukup untuk memeriksa keluaran `deflate`.
+    fn inflate_fixed(data: &[u8]) -> Vec<u8> {
+        let mut r = BitReader { data, pos: 0 };
+        assert_eq!((r.bits(1), r.bits(2)), (1, 1), "BFINAL + BTYPE=01");
+        let mut out = Vec::new();
+        loop {
+            match r.litlen() {
+                lit @ 0..=255 => out.push(lit as u8),
+                256 => return out,
+                sym => {
+                    let li = (sym - 257) as usize;
+                    let len = LEN_BASE[li] as usize + r.bits(LEN_EXTRA[li] as u32) as usize;
+                    let di = r.code(5) as usize;
+                    let dist = DIST_BASE[di] as usize + r.bits(DIST_EXTRA[di] as u32) as usize;
+                    for _ in 0..len { out.push(out[out.len() - dist]); }
+                }
+            }
+        }
+    }
+
+    fn gunzip(gz: &[u8]) -> Vec<u8> {
+        assert_eq!(gz[..4], [0x1F, 0x8B, 8, 0]);
+        let (body, trailer) = gz[10..].split_at(gz.len() - 18);
+        let data = inflate_fixed(body);
+        assert_eq!(trailer[..4], crc32(&data).to_le_bytes(), "CRC32");
+        assert_eq!(trailer[4..], (data.len() as u32).to_le_bytes(), "ISIZE");
+        data
+    }
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn empty_input_is_minimal_gzip() {
+        assert_eq!(gzip(b""), [0x1F, 0x8B, 8, 0, 0, 0, 0, 0, 0, 0xFF, 0x03, 0x00, 0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn max_match_boundary_round_trips() {
+        // 1 literal + tepat 258 (kode panjang 285), lalu 258 + 1 yang harus dipecah
+        for n in [259, 260, 517, 518, 1000] {
+            let data = vec![b'a'; n];
+            assert_eq!(gunzip(&gzip(&data)), data, "n={}", n);
+        }
+    }
+
+    #[test]
+    fn log_lines_round_trip_and_shrink() {
+        let mut data = Vec::new();
+        for i in 0..2000u32 {
+            data.extend_from_slice(format!("{{\"casdu\":1,\"ioa\":{},\"value\":{}.{}}}\n", 1000 + i % 37, i * 7 % 251, i % 10).as_bytes());
+        }
+        // Byte semu-acak: literal 144..255 (kode 9 bit) dan jarak jauh ikut teruji
+        let mut x = 0x1234_5678u32;
+        data.extend((0..5000).map(|_| { x ^= x << 13; x ^= x >> 17; x ^= x << 5; x as u8 }));
+        let gz = gzip(&data);
+        assert!(gz.len() < data.len() / 2, "{} -> {}", data.len(), gz.len());
+        assert_eq!(gunzip(&gz), data);
+    }
+}
@@ -0,0 +1,140 @@
+//! Titik ekstensi: aplikasi bereaksi pada titik terdekode & perubahan status koneksi.
+
+use std::fmt;
+
+use crate::decode::DecodedPoint;
+use crate::linkevents::LinkEvent;
+use crate::logging;
+use crate::output::{self, OutputFormat, OutputOptions};
+use crate::stats::Stats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnState {
+    Connecting,
+    Connected,   // TCP tersambung, STARTDT belum dikonfirmasi
+    StartDtSent,
+    Active,      // STARTDT con diterima, data mengalir
+    #[default]
+    Disconnected,
+}
+
+impl fmt::Display for ConnState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ConnState::Connecting => "connecting",
+            ConnState::Connected => "connected",
+            ConnState::StartDtSent => "startdt-sent",
+            ConnState::Active => "active",
+            ConnState::Disconnected => "disconnected",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// Dipanggil oleh read loop untuk setiap titik yang lolos deadband/filter.
+pub trait FrameHandler {
+    fn on_point(&mut self, point: &DecodedPoint);
+    /// Tiap APDU utuh yang diterima (start 0x68 s.d. akhir ASDU), sebelum didekode — juga
+    /// frame yang nanti ditolak mode strict. Slice dipinjam hanya selama panggilan: salin
+    /// (`to_vec`) bila perlu disimpan atau dikirim ke thread lain.
+    fn on_raw_rx(&mut self, _apdu: &[u8]) {}
+    /// Tiap APDU yang sudah ditulis ke socket (STARTDT, S-ACK, TESTFR, I-frame keluar); frame
+    /// yang diblok gatekeeper TX tidak pernah sampai sini. Pinjaman sama seperti `on_raw_rx`.
+    fn on_raw_tx(&mut self, _apdu: &[u8]) {}
+    fn on_state_change(&mut self, _state: ConnState) {}
+    /// Dipanggil tiap putaran read loop (paling lambat tiap read_timeout).
+    fn on_tick(&mut self) {}
+    /// Snapshot statistik link, paling sering sekali per detik selama tersambung.
+    fn on_stats(&mut self, _stats: &Stats) {}
+    /// Kejadian siklus hidup koneksi (lebih rinci dari `on_state_change`: alasan putus, TESTFR, reconnect).
+    fn on_link_event(&mut self, _event: &LinkEvent) {}
+    /// Sekali, sebelum proses keluar: kuras buffer, tutup berkas/klien. Harus berbatas waktu.
+    fn on_shutdown(&mut self) {}
+}
+
+/// Perilaku bawaan: cetak titik ke stdout dalam format terpilih.
+pub struct LoggingHandler {
+    format: OutputFormat,
+    opts: OutputOptions,
+}
+
+impl LoggingHandler {
+    pub fn new(format: OutputFormat, opts: OutputOptions) -> Self {
+        if format == OutputFormat::Csv {
+            println!("{}", opts.csv.header());
+        }
+        Self { format, opts }
+    }
+}
+
+impl FrameHandler for LoggingHandler {
+    fn on_point(&mut self, point: &DecodedPoint) {
+        let line = output::format_point(self.format, &self.opts, point);
+        // Baris teks = log; JSON/CSV/Influx = rekaman mesin tanpa prefiks. Log di stderr:
+        // stdout tetap milik data, baris teks pun dicetak apa adanya
+        if self.format == OutputFormat::Text && !logging::to_stderr() {
+            let p = point;
+            logln!(
+                { casdu = p.casdu, ioa = p.obj.ioa, type_id = p.type_id, cot = p.cot, value = p.value_eng.or(p.obj.value.as_f64()) },
+                "{}", line
+            )
+        } else {
+            println!("{}", line)
+        }
+    }
+
+    fn on_state_change(&mut self, state: ConnState) {
+        if self.format == OutputFormat::Text {
+            logln!("  ▸ Status koneksi: {}", state);
+        }
+    }
+}
+
+/// Menyebarkan event ke beberapa handler berurutan.
+#[derive(Default)]
+pub struct HandlerChain {
+    handlers: Vec<Box<dyn FrameHandler>>,
+    state: ConnState,
+}
+
+impl HandlerChain {
+    pub fn push(&mut self, h: Box<dyn FrameHandler>) { self.handlers.push(h); }
+
+    /// Status koneksi terakhir yang disebarkan.
+    pub fn state(&self) -> ConnState { self.state }
+}
+
+impl FrameHandler for HandlerChain {
+    fn on_point(&mut self, point: &DecodedPoint) {
+        for h in &mut self.handlers { h.on_point(point); }
+    }
+
+    fn on_raw_rx(&mut self, apdu: &[u8]) {
+        for h in &mut self.handlers { h.on_raw_rx(apdu); }
+    }
+
+    fn on_raw_tx(&mut self, apdu: &[u8]) {
+        for h in &mut self.handlers { h.on_raw_tx(apdu); }
+    }
+
+    fn on_state_change(&mut self, state: ConnState) {
+        self.state = state;
+        for h in &mut self.handlers { h.on_state_change(state); }
+    }
+
+    fn on_tick(&mut self) {
+        for h in &mut self.handlers { h.on_tick(); }
+    }
+
+    fn on_stats(&mut self, stats: &Stats) {
+        for h in &mut self.handlers { h.on_stats(stats); }
+    }
+
+    fn on_link_event(&mut self, event: &LinkEvent) {
+        for h in &mut self.handlers { h.on_link_event(event); }
+    }
+
+    fn on_shutdown(&mut self) {
+        for h in &mut self.handlers { h.on_shutdown(); }
+    }
+}
@@ -0,0 +1,196 @@
+//! Endpoint HTTP minimal untuk probe orkestrasi (Kubernetes):
+//! `/healthz` = proses hidup, `/readyz` = TCP tersambung, STARTDT con diterima,
+//! dan ada I-frame dalam jendela watchdog. `/metrics` = statistik koneksi & link (Prometheus),
+//! `/stations` = ringkasan per casdu (JSON) untuk melihat stasiun mana di balik gateway yang diam,
+//! `/config` = parameter efektif (k, w, t1/t2/t3, mode, tipe keluar) setelah INI & CLI.
+//! Probe dan `/metrics` bisa dipisah ke listener berbeda (NIC/port lain) bila beberapa
+//! kolektor berbagi host.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::connstats::ConnStats;
+use crate::decode::DecodedPoint;
+use crate::handler::{ConnState, FrameHandler};
+use crate::params::Params;
+use crate::stats::Stats;
+
+struct Inner {
+    state: ConnState,
+    last_data: Option<Instant>,
+    conn: Option<ConnStats>,
+    stats: Option<Stats>,
+    params: Option<Params>,
+}
+
+/// Status bersama antara read loop (penulis) dan thread HTTP (pembaca).
+pub struct Health {
+    inner: Mutex<Inner>,
+    /// Batas umur data terakhir untuk ready; None = cukup STARTDT con.
+    max_data_age: Option<Duration>,
+}
+
+impl Health {
+    pub fn new(max_data_age: Option<Duration>) -> Arc<Health> {
+        Arc::new(Health { inner: Mutex::new(Inner { state: ConnState::Disconnected, last_data: None, conn: None, stats: None, params: None }), max_data_age })
+    }
+
+    /// Dipanggil untuk setiap I-frame, termasuk yang nilainya ditahan deadband/filter.
+    pub fn data_seen(&self) {
+        if let Ok(mut g) = self.inner.lock() { g.last_data = Some(Instant::now()); }
+    }
+
+    /// Parameter efektif untuk `/config` & `/metrics`; saat start dan setelah konfigurasi dimuat ulang.
+    pub fn set_params(&self, params: Params) {
+        if let Ok(mut g) = self.inner.lock() { g.params = Some(params); }
+    }
+
+    /// Salinan statistik koneksi untuk `/metrics`; dipanggil saat statistiknya berubah.
+    pub fn set_conn(&self, stats: &ConnStats) {
+        if let Ok(mut g) = self.inner.lock() { g.conn = Some(stats.clone()); }
+    }
+
+    fn metrics(&self) -> String {
+        let Ok(g) = self.inner.lock() else { return String::new() };
+        g.params.as_ref().map(Params::metrics).unwrap_or_default()
+            + &g.conn.as_ref().map(ConnStats::metrics).unwrap_or_default()
+            + &g.stats.as_ref().map(Stats::metrics).unwrap_or_default()
+    }
+
+    fn config(&self) -> String {
+        let Ok(g) = self.inner.lock() else { return String::new() };
+        g.params.as_ref().map(Params::json).unwrap_or_else(|| "{}".into())
+    }
+
+    fn stations(&self) -> String {
+        let Ok(g) = self.inner.lock() else { return String::new() };
+        g.stats.as_ref().map(Stats::casdus_json).unwrap_or_else(|| format!("{{\"state\":\"{}\",\"casdus\":[]}}", g.state))
+    }
+
+    fn readiness(&self) -> Result<(), String> {
+        let g = self.inner.lock().map_err(|_| "status tidak tersedia".to_string())?;
+        if g.state != ConnState::Active {
+            return Err(format!("koneksi {}", g.state));
+        }
+        if let Some(max) = self.max_data_age {
+            match g.last_data {
+                Some(t) if t.elapsed() <= max => {}
+                Some(t) => return Err(format!("tidak ada data selama {:.0}s", t.elapsed().as_secs_f64())),
+                None => return Err("belum ada data".into()),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Meneruskan perubahan status koneksi ke `Health`.
+pub struct HealthHandler(pub Arc<Health>);
+
+impl FrameHandler for HealthHandler {
+    fn on_point(&mut self, _point: &DecodedPoint) {}
+
+    fn on_state_change(&mut self, state: ConnState) {
+        if let Ok(mut g) = self.0.inner.lock() {
+            // Sesi baru: data lama tidak boleh membuat siap
+            if state == ConnState::Connecting { g.last_data = None; }
+            g.state = state;
+        }
+    }
+
+    fn on_stats(&mut self, stats: &Stats) {
+        if let Ok(mut g) = self.0.inner.lock() { g.stats = Some(stats.clone()); }
+    }
+}
+
+/// Path yang dilayani satu listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routes {
+    /// /healthz & /readyz; /metrics punya listener sendiri.
+    Probes,
+    /// Hanya /metrics.
+    Metrics,
+    /// Ketiganya di satu listener.
+    All,
+}
+
+impl Routes {
+    fn name(self) -> &'static str {
+        match self {
+            Routes::Probes | Routes::All => "health",
+            Routes::Metrics => "metrics",
+        }
+    }
+
+    fn serves(self, path: &str) -> bool {
+        match self {
+            Routes::Probes => path == "/healthz" || path == "/readyz",
+            Routes::Metrics => matches!(path, "/metrics" | "/stations" | "/config"),
+            Routes::All => true,
+        }
+    }
+
+    fn paths(self) -> &'static str {
+        match self {
+            Routes::Probes => "/healthz, /readyz",
+            Routes::Metrics => "/metrics, /stations, /config",
+            Routes::All => "/healthz, /readyz, /metrics, /stations, /config",
+        }
+    }
+}
+
+/// Bind lalu jalankan server di thread latar. Gagal bind dikembalikan langsung agar proses
+/// berhenti saat start, bukan jalan tanpa endpoint. Alamat yang dicatat = hasil bind
+/// sebenarnya (port 0 terisi).
+pub fn serve(addr: &str, routes: Routes, health: Arc<Health>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)
+        .map_err(|e| std::io::Error::new(e.kind(), format!("gagal bind endpoint {} di {}: {}", routes.name(), addr, e)))?;
+    let local = listener.local_addr()?;
+    logln!("Endpoint {} di http://{} ({})", routes.name(), local, routes.paths());
+    std::thread::spawn(move || {
+        for conn in listener.incoming().flatten() {
+            if let Err(e) = respond(conn, routes, &health) {
+                elogln!("(health) gagal menjawab probe: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+fn respond(mut conn: TcpStream, routes: Routes, health: &Health) -> std::io::Result<()> {
+    conn.set_read_timeout(Some(Duration::from_secs(2)))?;
+    let mut line = String::new();
+    BufReader::new(&conn).read_line(&mut line)?;
+    let path = line.split_whitespace().nth(1).unwrap_or("");
+    let mut content_type = "text/plain";
+    let (status, body) = match path {
+        p if !routes.serves(p) => ("404 Not Found", "not found".to_string()),
+        "/healthz" => ("200 OK", "ok".to_string()),
+        "/readyz" => match health.readiness() {
+            Ok(()) => ("200 OK", "ready".to_string()),
+            Err(why) => ("503 Service Unavailable", format!("not ready: {}", why)),
+        },
+        "/metrics" => {
+            content_type = "text/plain; version=0.0.4";
+            let mut m = health.metrics();
+            // Baris baru penutup sudah ada; buang agar tidak dobel di bawah
+            m.pop();
+            ("200 OK", m)
+        }
+        "/stations" => {
+            content_type = "application/json";
+            ("200 OK", health.stations())
+        }
+        "/config" => {
+            content_type = "application/json";
+            ("200 OK", health.config())
+        }
+        _ => ("404 Not Found", "not found".to_string()),
+    };
+    write!(
+        conn,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}\n",
+        status, content_type, body.len() + 1, body
+    )
+}
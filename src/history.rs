@@ -0,0 +1,74 @@
+//! Riwayat N APDU terakhir yang diterima (bytes mentah + waktu terima) untuk post-mortem.
+//!
+//! Dicetak saat frame tidak dikenali / ASDU malformed, saat sesi berakhir, dan atas permintaan:
+//! `kill -USR1 <pid>` (unix), atau berkas pemicu (`--dump-trigger`) muncul, juga di luar unix.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::decode::format_epoch_ms;
+use crate::{hex, hexdump};
+
+/// Diset penangan SIGUSR1 (`watch_sigusr1`), dikosongkan `check_trigger`.
+static SIGUSR1: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+/// Pasang penangan SIGUSR1 untuk dump riwayat; false bila bukan unix.
+pub fn watch_sigusr1() -> std::io::Result<bool> {
+    #[cfg(unix)]
+    {
+        let flag = SIGUSR1.get_or_init(|| Arc::new(AtomicBool::new(false)));
+        signal_hook::flag::register(signal_hook::consts::SIGUSR1, Arc::clone(flag))?;
+        Ok(true)
+    }
+    #[cfg(not(unix))]
+    Ok(false)
+}
+
+pub struct FrameHistory {
+    cap: usize,
+    hexdump: bool,
+    frames: VecDeque<(i64, Vec<u8>)>, // (epoch ms saat diterima, APDU)
+}
+
+impl FrameHistory {
+    /// cap 0 = riwayat mati.
+    pub fn new(cap: usize, hexdump: bool) -> Self {
+        Self { cap, hexdump, frames: VecDeque::with_capacity(cap) }
+    }
+
+    pub fn push(&mut self, apdu: &[u8]) {
+        if self.cap == 0 { return; }
+        if self.frames.len() == self.cap { self.frames.pop_front(); }
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        self.frames.push_back((now_ms, apdu.to_vec()));
+    }
+
+    pub fn dump(&self, reason: &str) {
+        if self.frames.is_empty() { return; }
+        logln!("=== {} frame terakhir ({}) ===", self.frames.len(), reason);
+        // Waktu terima ikut sebagai medan: di mode JSON tiap frame jadi satu kejadian
+        for (ms, apdu) in &self.frames {
+            if self.hexdump {
+                logln!({ rx_ms = *ms }, "  {}\n{}", format_epoch_ms(*ms), hexdump(apdu));
+            } else {
+                logln!({ rx_ms = *ms }, "  {} {}", format_epoch_ms(*ms), hex(apdu));
+            }
+        }
+        logln!("=== akhir riwayat ===");
+    }
+
+    /// Dump bila berkas pemicu ada, lalu hapus berkasnya agar tidak terulang.
+    /// Dump bila SIGUSR1 diterima atau berkas pemicu muncul (berkas lalu dihapus).
+    pub fn check_trigger(&self, path: Option<&str>) {
+        if SIGUSR1.get().is_some_and(|f| f.swap(false, Ordering::Relaxed)) {
+            self.dump("SIGUSR1");
+        }
+        if let Some(path) = path.filter(|p| Path::new(p).exists()) {
+            let _ = std::fs::remove_file(path);
+            self.dump(&format!("pemicu {}", path));
+        }
+    }
+}
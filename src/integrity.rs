@@ -0,0 +1,140 @@
+//! Pemeriksaan konsistensi internal per I-frame. IEC 104 di atas TCP tidak punya CRC APDU,
+//! tetapi panjang yang dinyatakan, jumlah objek VSQ dan rentang medan header tetap bisa
+//! dicocokkan satu sama lain — bug encoder firmware RTU sering lolos diam-diam di sini.
+//! `IntegrityReport` per frame dipakai untuk log, keputusan mode strict, dan metrik.
+
+use std::fmt;
+
+use crate::decode::{AsduLayout, AsduStatus, DecodedAsdu};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// Janggal tetapi isinya masih bisa dipakai; dilog dan dihitung saja.
+    Warning,
+    /// Isi frame tidak bisa dipercaya; ditolak mode strict.
+    Error,
+}
+
+impl Severity {
+    /// Label metrik & medan log.
+    pub fn key(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub what: String,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.what)
+    }
+}
+
+/// Temuan satu frame: struktur (`check_i_frame`) ditambah pemeriksaan link dari read loop
+/// (urutan N(S)/N(R), type id tanpa decoder). Kosong = ok; hanya peringatan = isi tetap
+/// dipakai; ada error = isi ditolak mode strict.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub issues: Vec<Issue>,
+}
+
+impl IntegrityReport {
+    pub fn warn(&mut self, what: impl Into<String>) {
+        self.issues.push(Issue { severity: Severity::Warning, what: what.into() });
+    }
+
+    pub fn error(&mut self, what: impl Into<String>) {
+        self.issues.push(Issue { severity: Severity::Error, what: what.into() });
+    }
+
+    /// Alasan penolakan mode strict; kosong = frame boleh dipakai.
+    pub fn errors(&self) -> Vec<String> {
+        self.issues.iter().filter(|i| i.severity == Severity::Error).map(|i| i.what.clone()).collect()
+    }
+
+    pub fn count(&self, severity: Severity) -> u64 {
+        self.issues.iter().filter(|i| i.severity == severity).count() as u64
+    }
+
+    /// Panjang APDU, VSQ vs panjang ASDU, objek yang dilewati, dan rentang medan header.
+    /// Temuan baru dikembalikan agar pemanggil bisa melognya. `layout` = tata letak yang
+    /// dipakai mendekode `asdu` (batas IOA).
+    pub fn check_i_frame(&mut self, apdu: &[u8], asdu: Option<&DecodedAsdu>, layout: AsduLayout) -> &[Issue] {
+        let start = self.issues.len();
+        if let Some(&len) = apdu.get(1) {
+            if len as usize + 2 != apdu.len() {
+                self.error(format!("panjang APDU dinyatakan {} byte, terpakai {}", len as usize + 2, apdu.len()));
+            }
+        }
+        let Some(d) = asdu else { return &self.issues[start..] };
+        match &d.status {
+            AsduStatus::Ok => {}
+            AsduStatus::Truncated { expected, actual } => self.error(format!(
+                "ASDU pendek: VSQ={} objek butuh {} byte, ada {}; hanya {} objek didekode",
+                d.num_objects, expected, actual, d.objects.len()
+            )),
+            AsduStatus::Oversized { expected, actual } => {
+                self.error(format!("ASDU lebih panjang dari VSQ: butuh {} byte, ada {}", expected, actual))
+            }
+            AsduStatus::Malformed(why) => self.error(format!("ASDU malformed, dilewati: {}", why)),
+        }
+        for why in &d.skipped {
+            self.error(format!("objek dilewati: {}", why));
+        }
+        for o in &d.objects {
+            if let Some(why) = &o.bad_time {
+                self.warn(format!("objek ioa={}: {}; nilai dipakai tanpa time tag", o.ioa, why));
+            }
+        }
+        match d.cot {
+            0 => self.error("COT 0 (tidak terdefinisi)"),
+            48..=63 => self.warn(format!("COT {} di rentang privat 48..63", d.cot)),
+            _ => {}
+        }
+        if d.casdu == 0 {
+            self.warn("CASDU 0 (tidak dipakai standar)");
+        }
+        // SQ=1: IOA objek terakhir = dasar + N - 1 harus tetap muat di medan IOA
+        let ioa_max = (1u64 << (8 * layout.ioa)) - 1;
+        if let (true, Some(base)) = (d.sq, d.first_ioa) {
+            let last = base as u64 + d.num_objects.saturating_sub(1) as u64;
+            if last > ioa_max {
+                self.warn(format!("IOA berurutan (SQ=1) dari {} melewati {} untuk {} objek", base, ioa_max, d.num_objects));
+            }
+        }
+        // Informasi proses (type 1..40) beralamat IOA 0 = "tidak relevan": encoder lupa mengisi?
+        if (1..=40).contains(&d.type_id) {
+            if let Some(o) = d.objects.iter().find(|o| o.ioa == 0) {
+                self.warn(format!("IOA {} pada objek proses type {}", o.ioa, d.type_id));
+            }
+        }
+        &self.issues[start..]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::decode_asdu;
+
+    #[test]
+    fn bad_time_is_a_warning_not_a_rejection() {
+        // M_SP_TB_1 dengan bulan 0 di time tag
+        let asdu = [0x1E, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD4, 0x07, 0x00, 0x01, 0x24, 0x77, 0x14, 0x0A, 0xAF, 0x00, 0x18];
+        let mut apdu = vec![0x68, asdu.len() as u8 + 4, 0x00, 0x00, 0x00, 0x00];
+        apdu.extend_from_slice(&asdu);
+        let d = decode_asdu(&asdu).unwrap();
+        let mut report = IntegrityReport::default();
+        report.check_i_frame(&apdu, Some(&d), AsduLayout::IEC104);
+        assert_eq!(report.count(Severity::Warning), 1);
+        assert!(report.errors().is_empty());
+        assert_eq!(d.objects.len(), 1);
+    }
+}
@@ -0,0 +1,7 @@
+//! Dekoder ASDU IEC 60870-5-101/104 sebagai library, lepas dari read loop klien: alat offline,
+//! replay dan uji memanggil `decode::parse_asdu_full` dengan panjang medan `decode::AsduLayout`.
+//! Biner `iec104_client` memakai modul yang sama lewat crate ini.
+
+#![forbid(unsafe_code)]
+
+pub mod decode;
@@ -0,0 +1,96 @@
+//! Aliran kejadian siklus hidup koneksi (connected, STARTDT, TESTFR, STOPDT, putus, sambung
+//! ulang) sebagai JSON lines, untuk alarm NOC mis. "terlalu sering reconnect". Terpisah dari
+//! rekaman titik (data) dan dari log operasional: satu objek per baris, skema tetap.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::decode::{format_epoch_ms, DecodedPoint};
+use crate::handler::FrameHandler;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkEvent {
+    /// TCP tersambung; `peer` = alamat yang berhasil (bisa salah satu dari beberapa kandidat).
+    Connected { peer: String },
+    StartDtSent,
+    StartDtCon,
+    /// I-frame pertama di sesi ini.
+    DataStarted,
+    /// TESTFR: `initiator` = "master" (act kita, dijawab con) atau "rtu" (act dari RTU).
+    TestFr { initiator: &'static str, rtt_ms: Option<f64> },
+    /// STOPDT act terkirim; `confirmed` = con diterima sebelum t1.
+    StopDt { confirmed: bool },
+    /// `reason` = kunci `Disconnect` (mis. "t1", "peer_closed") atau "io_error".
+    Disconnected { reason: String, detail: String },
+    /// Percobaan sambung ulang ke-`attempt` berturut-turut sejak sesi terakhir yang tersambung.
+    Reconnect { attempt: u64, delay_ms: u64 },
+}
+
+impl LinkEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            LinkEvent::Connected { .. } => "connected",
+            LinkEvent::StartDtSent => "startdt-sent",
+            LinkEvent::StartDtCon => "startdt-con",
+            LinkEvent::DataStarted => "data-started",
+            LinkEvent::TestFr { .. } => "testfr-exchange",
+            LinkEvent::StopDt { .. } => "stopdt",
+            LinkEvent::Disconnected { .. } => "disconnected",
+            LinkEvent::Reconnect { .. } => "reconnect",
+        }
+    }
+
+    /// Medan khusus kejadian, sudah dalam bentuk `,"k":v` JSON.
+    fn fields(&self) -> String {
+        match self {
+            LinkEvent::Connected { peer } => format!(",\"peer\":{}", json_str(peer)),
+            LinkEvent::TestFr { initiator, rtt_ms } => format!(
+                ",\"initiator\":\"{}\",\"rtt_ms\":{}",
+                initiator, rtt_ms.map(|r| format!("{:.1}", r)).unwrap_or_else(|| "null".into())
+            ),
+            LinkEvent::StopDt { confirmed } => format!(",\"confirmed\":{}", confirmed),
+            LinkEvent::Disconnected { reason, detail } => format!(",\"reason\":{},\"detail\":{}", json_str(reason), json_str(detail)),
+            LinkEvent::Reconnect { attempt, delay_ms } => format!(",\"attempt\":{},\"delay_ms\":{}", attempt, delay_ms),
+            LinkEvent::StartDtSent | LinkEvent::StartDtCon | LinkEvent::DataStarted => String::new(),
+        }
+    }
+}
+
+/// Menulis tiap `LinkEvent` sebagai satu baris JSON. Di-flush per kejadian: jarang, dan
+/// penerima (tail/forwarder) harus melihatnya segera.
+pub struct LinkEventLog {
+    out: BufWriter<File>,
+    rtu: String,
+}
+
+impl LinkEventLog {
+    pub fn open(path: &str, rtu: &str) -> std::io::Result<LinkEventLog> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(LinkEventLog { out: BufWriter::new(file), rtu: rtu.to_string() })
+    }
+
+    fn write(&mut self, ev: &LinkEvent) -> std::io::Result<()> {
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        writeln!(
+            self.out,
+            "{{\"ts\":\"{}\",\"ts_ms\":{},\"rtu\":{},\"event\":\"{}\"{}}}",
+            format_epoch_ms(now_ms), now_ms, json_str(&self.rtu), ev.name(), ev.fields()
+        )?;
+        self.out.flush()
+    }
+}
+
+impl FrameHandler for LinkEventLog {
+    fn on_point(&mut self, _point: &DecodedPoint) {}
+
+    fn on_link_event(&mut self, ev: &LinkEvent) {
+        if let Err(e) = self.write(ev) {
+            elogln!("(Peringatan) gagal menulis kejadian koneksi {}: {}", ev.name(), e);
+        }
+    }
+}
+
+fn json_str(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
@@ -0,0 +1,305 @@
+//! Log operasional (connect, ACK, peringatan, error) lewat `logln!`/`elogln!`: teks untuk
+//! manusia dengan stempel waktu host di awal baris, atau satu objek JSON per kejadian
+//! (`--log-format json`, untuk ELK/Docker) dengan `ts`, `level`, `msg`, `rtu` dan medan bertipe.
+//! Rekaman titik berformat mesin (JSON/CSV/Influx) tidak lewat sini: konsumen hilir mem-parse
+//! baris itu apa adanya. `--log-stream stderr` memindah log ke stderr sehingga stdout hanya
+//! berisi rekaman itu (`| jq`).
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::decode::format_epoch_ms;
+
+/// `println!` lewat lapisan log. Medan bertipe opsional di depan:
+/// `logln!({ ns = ns, nr = nr }, "> TX ...", ...)`; di mode teks medan hanya ada di pesan.
+macro_rules! logln {
+    ({ $($k:ident = $v:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::emit(false, &[$((stringify!($k), &$v as &dyn $crate::logging::LogValue)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::emit(false, &[], format_args!($($arg)*)) };
+}
+
+/// `eprintln!` lewat lapisan log; level default error.
+macro_rules! elogln {
+    ({ $($k:ident = $v:expr),* $(,)? }, $($arg:tt)*) => {
+        $crate::logging::emit(true, &[$((stringify!($k), &$v as &dyn $crate::logging::LogValue)),*], format_args!($($arg)*))
+    };
+    ($($arg:tt)*) => { $crate::logging::emit(true, &[], format_args!($($arg)*)) };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogTime {
+    /// Tanpa prefiks (keluaran deterministik untuk uji/diff).
+    Off,
+    /// ISO-8601 UTC dengan milidetik: 2024-03-01T12:00:00.000Z
+    Iso,
+    /// Milidetik epoch Unix.
+    EpochMs,
+}
+
+impl LogTime {
+    pub fn parse(s: &str) -> Result<LogTime, String> {
+        match s.trim() {
+            "off" => Ok(LogTime::Off),
+            "iso" => Ok(LogTime::Iso),
+            "epoch_ms" => Ok(LogTime::EpochMs),
+            other => Err(format!("format waktu log tidak dikenal: '{}' (iso|epoch_ms|off)", other)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat { Text, Json }
+
+impl LogFormat {
+    pub fn parse(s: &str) -> Result<LogFormat, String> {
+        match s.trim() {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("format log tidak dikenal: '{}' (text|json)", other)),
+        }
+    }
+}
+
+/// Tujuan baris `logln!`; `elogln!` selalu ke stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogStream {
+    /// Log dan data bercampur di stdout (default, pemakaian interaktif).
+    Stdout,
+    /// stdout hanya untuk rekaman titik.
+    Stderr,
+    /// stderr bila stdout dipipe/dialihkan, stdout bila terminal.
+    Auto,
+}
+
+impl LogStream {
+    pub fn parse(s: &str) -> Result<LogStream, String> {
+        match s.trim() {
+            "stdout" => Ok(LogStream::Stdout),
+            "stderr" => Ok(LogStream::Stderr),
+            "auto" => Ok(LogStream::Auto),
+            other => Err(format!("tujuan log tidak dikenal: '{}' (stdout|stderr|auto)", other)),
+        }
+    }
+}
+
+/// Nilai medan log sebagai literal JSON.
+pub trait LogValue {
+    fn json(&self) -> String;
+}
+
+macro_rules! log_value_int {
+    ($($t:ty),*) => {$(impl LogValue for $t { fn json(&self) -> String { self.to_string() } })*};
+}
+log_value_int!(u8, u16, u32, u64, usize, i32, i64, bool);
+
+impl LogValue for f64 {
+    fn json(&self) -> String {
+        if self.is_finite() { self.to_string() } else { "null".into() }
+    }
+}
+
+impl LogValue for str {
+    fn json(&self) -> String { json_str(self) }
+}
+
+impl LogValue for String {
+    fn json(&self) -> String { json_str(self) }
+}
+
+impl<T: LogValue + ?Sized> LogValue for &T {
+    fn json(&self) -> String { (**self).json() }
+}
+
+impl<T: LogValue> LogValue for Option<T> {
+    fn json(&self) -> String { self.as_ref().map_or_else(|| "null".into(), T::json) }
+}
+
+static TIME: AtomicU8 = AtomicU8::new(LogTime::Iso as u8);
+static FORMAT: AtomicU8 = AtomicU8::new(LogFormat::Text as u8);
+static RTU: OnceLock<String> = OnceLock::new();
+/// Mode follow: baris stdout ditahan di sini (N terakhir) alih-alih menggulung tabel.
+static TAIL: Mutex<Option<(usize, VecDeque<String>)>> = Mutex::new(None);
+/// Semua baris log ke stderr (`LogStream` sudah di-resolve).
+static TO_STDERR: AtomicBool = AtomicBool::new(false);
+/// --log-changes-only: jejak per frame hanya dicetak bila read loop menyatakan frame itu penting.
+static CHANGES_ONLY: AtomicBool = AtomicBool::new(false);
+/// Jejak frame yang sedang diproses, menunggu `end_frame`; None = di luar frame.
+static HELD: Mutex<Option<Held>> = Mutex::new(None);
+
+struct Held {
+    /// (stderr, baris jadi)
+    lines: Vec<(bool, String)>,
+    /// Sudah dicetak karena peringatan di tengah frame: sisa jejaknya langsung keluar.
+    shown: bool,
+}
+
+impl Held {
+    fn show(&mut self) {
+        for (stderr, line) in self.lines.drain(..) { print(stderr, &line); }
+        self.shown = true;
+    }
+}
+
+/// Dipanggil sekali setelah konfigurasi dibaca; berlaku untuk semua thread.
+pub fn set_time(mode: LogTime) {
+    TIME.store(mode as u8, Ordering::Relaxed);
+}
+
+pub fn set_format(format: LogFormat, rtu: &str) {
+    FORMAT.store(format as u8, Ordering::Relaxed);
+    let _ = RTU.set(rtu.to_string());
+}
+
+pub fn set_stream(stream: LogStream) {
+    let on = match stream {
+        LogStream::Stdout => false,
+        LogStream::Stderr => true,
+        LogStream::Auto => !std::io::stdout().is_terminal(),
+    };
+    TO_STDERR.store(on, Ordering::Relaxed);
+}
+
+/// stdout khusus data: pencetak rekaman titik tidak boleh lewat `logln!`.
+pub fn to_stderr() -> bool {
+    TO_STDERR.load(Ordering::Relaxed)
+}
+
+pub fn set_changes_only(on: bool) {
+    CHANGES_ONLY.store(on, Ordering::Relaxed);
+}
+
+/// Mulai menahan jejak satu frame (mode changes-only saja).
+pub fn hold_frame() {
+    if CHANGES_ONLY.load(Ordering::Relaxed) {
+        *HELD.lock().unwrap_or_else(|e| e.into_inner()) = Some(Held { lines: Vec::new(), shown: false });
+    }
+}
+
+/// Keputusan read loop untuk frame yang ditahan: cetak jejaknya (`keep`) atau buang.
+pub fn end_frame(keep: bool) {
+    let held = HELD.lock().unwrap_or_else(|e| e.into_inner()).take();
+    if let Some(mut h) = held.filter(|_| keep) { h.show(); }
+}
+
+/// Jejak per frame: RX/TX dan rincian berindentasi di bawahnya.
+fn is_trace(msg: &str) -> bool {
+    msg.starts_with("< RX") || msg.starts_with("> TX") || msg.starts_with(' ')
+}
+
+/// Tahan baris `logln!` (bukan `elogln!`): simpan `keep` terakhir untuk `tail`.
+pub fn capture(keep: usize) {
+    *TAIL.lock().unwrap_or_else(|e| e.into_inner()) = Some((keep, VecDeque::with_capacity(keep)));
+}
+
+/// Kembali mencetak ke stdout (ringkasan saat keluar tampil di bawah tabel terakhir).
+pub fn release() {
+    *TAIL.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Baris yang ditahan `capture`, terlama dulu.
+pub fn tail() -> Vec<String> {
+    TAIL.lock().unwrap_or_else(|e| e.into_inner()).as_ref().map_or_else(Vec::new, |(_, t)| t.iter().cloned().collect())
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
+
+/// Level dari penanda pesan yang sudah dipakai di seluruh log; tanpa penanda: stderr = error.
+fn level(msg: &str, stderr: bool) -> &'static str {
+    let m = msg.trim_start();
+    if m.starts_with("(Error)") {
+        "error"
+    } else if ["(Peringatan)", "(ALARM)", "(Blok)", "(Paksa)"].iter().any(|p| m.starts_with(p)) {
+        "warn"
+    } else if stderr && !m.starts_with("(Info)") {
+        "error"
+    } else {
+        "info"
+    }
+}
+
+#[doc(hidden)]
+pub fn emit(stderr: bool, fields: &[(&str, &dyn LogValue)], msg: fmt::Arguments<'_>) {
+    let time = TIME.load(Ordering::Relaxed);
+    if !stderr {
+        if let Some((keep, tail)) = TAIL.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            let msg = msg.to_string();
+            // Jejak per frame (RX/TX, rincian berindentasi) hanya akan mendorong keluar peringatan;
+            // tail dibaca manusia di bawah tabel: selalu teks, satu baris
+            if is_trace(&msg) { return; }
+            let msg = msg.lines().next().unwrap_or_default();
+            if tail.len() >= *keep { tail.pop_front(); }
+            tail.push_back(match time {
+                t if t == LogTime::Off as u8 => msg.to_string(),
+                _ => format!("{} {}", format_epoch_ms(now_ms()), msg),
+            });
+            return;
+        }
+    }
+    let msg = msg.to_string();
+    let trace = is_trace(&msg);
+    let msg_tx = msg.starts_with("> TX");
+    let line = if FORMAT.load(Ordering::Relaxed) == LogFormat::Json as u8 {
+        let mut out = String::from("{");
+        match time {
+            t if t == LogTime::Off as u8 => {}
+            t if t == LogTime::EpochMs as u8 => out += &format!("\"ts\":{},", now_ms()),
+            _ => out += &format!("\"ts\":\"{}\",", format_epoch_ms(now_ms())),
+        }
+        out += &format!("\"level\":\"{}\",\"msg\":{}", level(&msg, stderr), json_str(msg.trim()));
+        if let Some(rtu) = RTU.get() {
+            out += &format!(",\"rtu\":{}", json_str(rtu));
+        }
+        for (k, v) in fields {
+            out += &format!(",\"{}\":{}", k, v.json());
+        }
+        out + "}"
+    } else {
+        match time {
+            t if t == LogTime::Off as u8 => msg,
+            t if t == LogTime::EpochMs as u8 => format!("{} {}", now_ms(), msg),
+            _ => format!("{} {}", format_epoch_ms(now_ms()), msg),
+        }
+    };
+    if CHANGES_ONLY.load(Ordering::Relaxed) {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        match held.as_mut() {
+            // TX di luar frame (STARTDT, TESTFR idle) sama rutinnya dengan frame yang dibuang;
+            // baris berindentasi lain (dump riwayat) tetap keluar
+            None if msg_tx => return,
+            Some(h) if !h.shown && trace => return h.lines.push((stderr, line)),
+            // Peringatan/alarm di tengah frame: jejak frame-nya ikut dicetak sebagai konteks
+            Some(h) if !h.shown => h.show(),
+            _ => {}
+        }
+    }
+    print(stderr, &line);
+}
+
+fn print(stderr: bool, line: &str) {
+    if stderr || to_stderr() { eprintln!("{}", line) } else { println!("{}", line) }
+}
+
+fn json_str(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -1,23 +1,80 @@
 #![forbid(unsafe_code)]
 
+use std::collections::{BTreeMap, VecDeque};
 use std::io::{ Read, Write};
-use std::net::TcpStream;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+// Makro logln!/elogln! harus terdefinisi sebelum modul lain
+#[macro_use]
+mod logging;
+mod backlog;
+#[cfg(feature = "bench")]
+mod bench;
+mod cache;
+mod config;
+mod connstats;
+mod dryrun;
+mod events;
+mod filetx;
+mod follow;
+mod gzip;
+mod handler;
+mod health;
+mod history;
+mod integrity;
+mod linkevents;
+mod mock;
+mod repl;
+mod output;
+mod params;
+mod pcap;
+mod simulate;
+mod sink;
+mod stats;
+mod vectors;
+
+use cache::PointCache;
+use config::{AckMode, AckPolicy, Config, KeepalivePolicy, NoDataAction, ParseMode, TypePolicy};
+use connstats::ConnStats;
+// Dekoder ada di crate library (src/lib.rs); `crate::decode` tetap berlaku di semua modul
+use iec104_client::decode;
+use socket2::{Domain, Protocol, SockRef, Socket, TcpKeepalive, Type};
+use decode::{AsduLayout, AsduStatus, DecodedPoint, ObjValue, Origin, Qu, StepDirection};
+use backlog::{Backlog, BacklogChange};
+use events::EventLog;
+use linkevents::{LinkEvent, LinkEventLog};
+use filetx::FileTransfer;
+use follow::FollowView;
+use handler::{ConnState, FrameHandler, HandlerChain, LoggingHandler};
+use health::{Health, HealthHandler, Routes};
+use history::FrameHistory;
+use integrity::{IntegrityReport, Severity};
+use output::OutputFormat;
+use params::Params;
+use sink::{DeadLetter, SinkSet, SinkWorker};
+use stats::{AckStats, Stats};
+
 const RTU_ADDR: &str = "192.168.11.93:2404";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const STATS_PUSH_INTERVAL: Duration = Duration::from_secs(1); // FrameHandler::on_stats
+// Banjir U-frame tidak standar = kemungkinan framing hilang: putus & sambung ulang
+const U_OTHER_FLOOD: usize = 5;
+const U_OTHER_WINDOW: Duration = Duration::from_secs(10);
 
 // ================= Kebijakan Operasi =================
-// Kirim STARTDT act satu kali di awal? (umumnya perlu agar RTU mulai kirim data)
-const SEND_STARTDT_ONCE: bool = true;
-// Mode ACK-only: TIDAK ADA I-frame keluar.
+// Mode ACK-only: TIDAK ADA I-frame keluar kecuali dibuka saat jalan dengan --enable-tx / [tx] enable_tx.
 const ACK_ONLY: bool = true;
-// Nonaktifkan TESTFR saat idle (ACK-only murni)
-const SEND_TESTFR_WHEN_IDLE: bool = false;
+// Supervisi TESTFR saat idle diatur lewat --testfr / [testfr]; butuh --enable-tx.
+// General Interrogation (C_IC_NA_1) otomatis diatur lewat --auto-gi / [tx] auto_gi; butuh --enable-tx.
+const GI_CASDU: u16 = 1;                               // Common Address tujuan GI (boleh CASDU_BROADCAST)
+const GI_QOI: u8 = 20;                                 // 20 = global/station, 21..36 = grup 1..16
+const GI_TIMEOUT: Duration = Duration::from_secs(60);  // batas tunggu act-term (COT=10)
 
-// ================= Parameter Siemens (umum) =================
-const SIEMENS_K: u16 = 12;                     // jendela kirim sisi RTU (perkiraan)
-const SIEMENS_W: usize = 8;                    // wajib ACK setelah 8 I-frame diterima
-const T2: Duration = Duration::from_secs(10);  // timeout t2 untuk ACK koalescing
+// ================= Timer link =================
+// k/w/t2 sisi terima ada di config::AckPolicy ([ack], --ack-*)
+const T1: Duration = Duration::from_secs(15);  // timeout t1: I-frame kita harus sudah di-ACK
 
 // ================= Konstanta U-frame =================
 const U_STARTDT_ACT: u8 = 0x07;
@@ -28,53 +85,739 @@ const U_TESTFR_ACT:  u8 = 0x43;
 const U_TESTFR_CON:  u8 = 0x83;
 
 // ================= Larangan tipe ASDU keluar =================
-const FORBIDDEN_TYPE_IDS: &[u8] = &[45, 46]; // C_SC_NA_1, C_DC_NA_1
+// Default kebijakan tipe keluar; bisa diganti [tx] forbid_types / allow_types
+const FORBIDDEN_TYPE_IDS: &[u8] = &[45, 46, 47]; // C_SC_NA_1, C_DC_NA_1, C_RC_NA_1
+
+// ================= Konstanta ASDU =================
+const TYPE_C_SC_NA_1: u8 = 45;
+const TYPE_C_RC_NA_1: u8 = 47;
+const TYPE_C_BO_NA_1: u8 = 51;
+const TYPE_C_IC_NA_1: u8 = 100;
+const TYPE_C_CI_NA_1: u8 = 101;
+const COT_ACT: u8 = 6;
+const COT_ACTCON: u8 = 7;
+const COT_ACTTERM: u8 = 10;
+const COT_INROGEN: u8 = 20;  // interrogated by station interrogation
+const COT_INRO16: u8 = 36;   // interrogated by group 16
+
+// Common Address global (CASDU 2 oktet). Hanya sah untuk perintah sistem C_IC (100),
+// C_CI (101), C_CS (103) dan C_RP (105); RTU menjawab dengan CASDU miliknya sendiri.
+const CASDU_BROADCAST: u16 = 0xFFFF;
+const BROADCAST_TYPE_IDS: &[u8] = &[100, 101, 103, 105];
 
-struct AckStats { w: u64, t2: u64, emergency: u64 }
-impl AckStats {
-    fn inc(&mut self, reason: &str) {
-        match reason { "w" => self.w+=1, "t2" => self.t2+=1, "emergency" => self.emergency+=1, _=>{} }
+/// Hitung temuan integritas satu frame; Ok(true) = isi ditolak mode strict, Err = putus.
+fn judge_frame(cfg: &Config, link: &mut LinkStats, history: &FrameHistory, report: &IntegrityReport) -> Result<bool, Disconnect> {
+    link.integrity_warnings += report.count(Severity::Warning);
+    link.integrity_errors += report.count(Severity::Error);
+    let errors = report.errors();
+    if errors.is_empty() || !cfg.parse_mode.strict() { return Ok(false); }
+    strict_reject(cfg, link, history, &errors).map_or(Ok(true), Err)
+}
+
+/// Mode strict: frame menyimpang dicatat sebagai error dan isinya tidak dipakai (pemanggil);
+/// Some = --strict-disconnect, sesi ditutup.
+fn strict_reject(cfg: &Config, link: &mut LinkStats, history: &FrameHistory, violations: &[String]) -> Option<Disconnect> {
+    link.strict_rejects += 1;
+    let why = violations.join("; ");
+    elogln!("(Error) strict: frame ditolak (ke-{}): {}", link.strict_rejects, why);
+    if cfg.parse_mode == ParseMode::StrictDisconnect {
+        // Riwayat dicetak saat sesi berakhir
+        return Some(Disconnect::StrictViolation(why));
+    }
+    history.dump("frame ditolak mode strict");
+    None
+}
+
+/// Alasan sesi berakhir.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Disconnect {
+    PeerClosed,
+    ReadError(String),
+    T1Timeout,
+    SequenceError(String),
+    /// Banjir U-frame tidak standar: aliran kemungkinan tidak sinkron.
+    FramingError(String),
+    NoData,
+    /// Snapshot selesai: GI act-term diterima dan STOPDT sudah dikirim.
+    SnapshotDone,
+    SnapshotFailed(String),
+    /// --strict-disconnect: frame menyimpang dari standar.
+    StrictViolation(String),
+    /// Supervisi TESTFR: tidak ada con setelah semua kirim ulang.
+    TestFrTimeout,
+    /// Berkas pemicu berhenti muncul (--stop-trigger).
+    Stopped,
+    /// Umur sesi mencapai --max-session: siklus terjadwal, selalu sambung ulang.
+    MaxSession(Duration),
+}
+impl std::fmt::Display for Disconnect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Disconnect::PeerClosed => write!(f, "ditutup peer"),
+            Disconnect::ReadError(e) => write!(f, "kesalahan baca: {}", e),
+            Disconnect::T1Timeout => write!(f, "t1 habis"),
+            Disconnect::SequenceError(e) => write!(f, "kesalahan urutan: {}", e),
+            Disconnect::FramingError(e) => write!(f, "kesalahan framing: {}", e),
+            Disconnect::NoData => write!(f, "watchdog: tidak ada data"),
+            Disconnect::SnapshotDone => write!(f, "snapshot selesai"),
+            Disconnect::SnapshotFailed(e) => write!(f, "snapshot gagal: {}", e),
+            Disconnect::StrictViolation(e) => write!(f, "pelanggaran strict: {}", e),
+            Disconnect::TestFrTimeout => write!(f, "TESTFR tidak dijawab"),
+            Disconnect::Stopped => write!(f, "diminta berhenti"),
+            Disconnect::MaxSession(d) => write!(f, "siklus terjadwal setelah {:?}", d),
+        }
+    }
+}
+impl Disconnect {
+    /// Kunci stabil untuk statistik alasan putus.
+    fn key(&self) -> &'static str {
+        match self {
+            Disconnect::PeerClosed => "peer_closed",
+            Disconnect::ReadError(_) => "read_error",
+            Disconnect::T1Timeout => "t1",
+            Disconnect::SequenceError(_) => "sequence_error",
+            Disconnect::FramingError(_) => "framing_error",
+            Disconnect::NoData => "no_data",
+            Disconnect::SnapshotDone => "snapshot_done",
+            Disconnect::SnapshotFailed(_) => "snapshot_failed",
+            Disconnect::StrictViolation(_) => "strict_violation",
+            Disconnect::TestFrTimeout => "testfr_timeout",
+            Disconnect::Stopped => "stopped",
+            Disconnect::MaxSession(_) => "max_session",
+        }
     }
 }
 
+/// Penghitung lalu lintas untuk baris statistik berkala.
+struct LinkStats {
+    frames: u64,
+    i_frames: u64,
+    s_frames: u64,
+    u_frames: u64,
+    /// U-frame dengan byte kontrol di luar STARTDT/STOPDT/TESTFR act/con.
+    u_other: u64,
+    u_other_recent: VecDeque<Instant>,
+    /// I-frame tanpa ASDU (LEN=4, APCI saja): tetap dihitung di `i_frames` dan di-ACK.
+    i_empty: u64,
+    unknown_frames: u64,
+    /// Buffer RX melewati max_buffer_bytes dan dipangkas.
+    rx_overflows: u64,
+    /// I-frame per type id ASDU.
+    asdu_types: BTreeMap<u8, u64>,
+    /// I-frame per casdu.
+    casdu_frames: BTreeMap<u16, u64>,
+    window_used: u16,
+    /// k sisi RTU dari kebijakan ACK yang berlaku.
+    window_k: u16,
+    last_data: Option<Instant>,
+    last_report: Instant,
+    frames_at_report: u64,
+    /// RTT lapisan aplikasi terakhir (TESTFR act -> con).
+    rtt: Option<Duration>,
+    /// Objek yang dibuang filter casdu/IOA (frame-nya tetap di-ACK).
+    filtered: u64,
+    /// ASDU bertipe tanpa nama atau tanpa decoder (privat vendor / firmware baru), per type id.
+    unknown_types: BTreeMap<u8, u64>,
+    /// Balasan perintah ber-OA master lain (hanya bila originator dikonfigurasi).
+    foreign_replies: u64,
+    /// N(R) terakhir dari RTU (I- atau S-frame) di sesi ini; None = belum diketahui (pasif).
+    peer_nr: Option<u16>,
+    /// N(R) RTU yang mundur atau melompat melewati frame yang mungkin sudah dikirim master.
+    peer_nr_anomalies: u64,
+    /// STARTDT act diterima dari peer: peer menganggap kita stasiun terkendali (peran terbalik).
+    startdt_act_rx: u64,
+    /// Frame yang isinya ditolak mode strict.
+    strict_rejects: u64,
+    /// TESTFR act tanpa con dalam t1: total, dan beruntun sejak con terakhir (lintas reconnect).
+    testfr_failures: u64,
+    testfr_fail_streak: u64,
+    /// M_EI_NA_1 diterima (RTU restart), dan yang melaporkan parameter lokal berubah.
+    end_of_init: u64,
+    end_of_init_changed: u64,
+    /// Temuan `IntegrityReport` per I-frame, per tingkat.
+    integrity_warnings: u64,
+    integrity_errors: u64,
+    /// Frame yang jejaknya tidak dilog (--log-changes-only).
+    quiet_frames: u64,
+    /// Laju I-frame RTU & S-ACK emergency: RTU menguras backlog?
+    backlog: Backlog,
+}
+impl LinkStats {
+    fn new() -> Self {
+        Self {
+            frames: 0, i_frames: 0, s_frames: 0, u_frames: 0, u_other: 0, u_other_recent: VecDeque::new(), i_empty: 0, unknown_frames: 0, rx_overflows: 0, asdu_types: BTreeMap::new(), casdu_frames: BTreeMap::new(), window_used: 0, window_k: 0,
+            last_data: None, last_report: Instant::now(), frames_at_report: 0, rtt: None, filtered: 0, unknown_types: BTreeMap::new(), foreign_replies: 0,
+            peer_nr: None, peer_nr_anomalies: 0, startdt_act_rx: 0, strict_rejects: 0,
+            testfr_failures: 0, testfr_fail_streak: 0, end_of_init: 0, end_of_init_changed: 0,
+            integrity_warnings: 0, integrity_errors: 0, quiet_frames: 0, backlog: Backlog::new(),
+        }
+    }
+
+    /// Catat satu U-frame tidak standar; true bila sudah U_OTHER_FLOOD dalam U_OTHER_WINDOW.
+    fn u_other_flood(&mut self) -> bool {
+        let now = Instant::now();
+        self.u_other += 1;
+        self.u_other_recent.push_back(now);
+        while self.u_other_recent.front().is_some_and(|t| now.duration_since(*t) > U_OTHER_WINDOW) {
+            self.u_other_recent.pop_front();
+        }
+        let flood = self.u_other_recent.len() >= U_OTHER_FLOOD;
+        if flood { self.u_other_recent.clear(); }
+        flood
+    }
+
+    /// Catat N(R) dari RTU. Anomali bila mundur, atau maju lebih dari `max_step` (frame master
+    /// yang belum di-ACK); deskripsinya dikembalikan untuk dilog.
+    fn track_peer_nr(&mut self, nr: u16, max_step: u16) -> Option<String> {
+        let prev = self.peer_nr.replace(nr)?;
+        let why = if !seq_in_window(nr, prev, SEQ_MOD / 2) {
+            format!("mundur dari {} ke {}", prev, nr)
+        } else if !seq_in_window(nr, prev, max_step + 1) {
+            format!("melompat {} dari {} ke {} (maks {} frame master belum di-ACK)", seq_distance(nr, prev), prev, nr, max_step)
+        } else {
+            return None;
+        };
+        self.peer_nr_anomalies += 1;
+        Some(why)
+    }
+
+    /// Salinan hanya-baca untuk handler & /metrics; aktivitas per casdu digabung dari cache.
+    fn snapshot(&self, acks: &AckStats, state: ConnState, cache: &PointCache) -> Stats {
+        let mut casdus = cache.casdus().clone();
+        for (casdu, n) in &self.casdu_frames {
+            casdus.entry(*casdu).or_default().frames = *n;
+        }
+        Stats {
+            state, acks: *acks,
+            frames_i: self.i_frames, frames_s: self.s_frames, frames_u: self.u_frames, frames_u_other: self.u_other, frames_i_empty: self.i_empty, frames_unknown: self.unknown_frames,
+            rx_overflows: self.rx_overflows,
+            peer_nr: self.peer_nr, peer_nr_anomalies: self.peer_nr_anomalies,
+            startdt_act_rx: self.startdt_act_rx,
+            testfr_failures: self.testfr_failures, testfr_fail_streak: self.testfr_fail_streak,
+            end_of_init: self.end_of_init, end_of_init_changed: self.end_of_init_changed,
+            asdu_types: self.asdu_types.clone(),
+            window_used: self.window_used, window_k: self.window_k, rtt: self.rtt, casdus,
+            integrity_warnings: self.integrity_warnings, integrity_errors: self.integrity_errors,
+            i_rate: self.backlog.rate(Instant::now()), backlog_suspected: self.backlog.suspected(), backlog_episodes: self.backlog.episodes,
+        }
+    }
+
+    /// Cetak satu baris ringkas bila interval sudah lewat (interval 0 = mati).
+    fn maybe_report(&mut self, interval: Duration, acks: &AckStats) {
+        if interval.is_zero() || self.last_report.elapsed() < interval { return; }
+        let secs = self.last_report.elapsed().as_secs_f64();
+        let fps = (self.frames - self.frames_at_report) as f64 / secs;
+        logln!(
+            "[stats] {:.1} frame/s | I-frame={} | ack w={} t2={} emergency={} immediate={} | window={}/{} | rtt={} | data terakhir: {}{}{}",
+            fps, self.i_frames, acks.w, acks.t2, acks.emergency, acks.immediate, self.window_used, self.window_k,
+            self.rtt.map(|d| format!("{:.1}ms", d.as_secs_f64() * 1000.0)).unwrap_or_else(|| "-".into()),
+            self.last_data.map(|t| format!("{:.1}s lalu", t.elapsed().as_secs_f64())).unwrap_or_else(|| "belum ada".into()),
+            if self.filtered > 0 { format!(" | disaring={}", self.filtered) } else { String::new() },
+            if self.backlog.suspected() { " | BACKLOG RTU" } else { "" }
+        );
+        self.last_report = Instant::now();
+        self.frames_at_report = self.frames;
+    }
+}
+
+/// State yang bertahan lintas koneksi ulang.
+struct Collector {
+    cfg: Config,
+    handler: HandlerChain,
+    cache: PointCache,
+    ack_stats: AckStats,
+    link: LinkStats,
+    history: FrameHistory,
+    health: Option<Arc<Health>>,
+    events: Option<EventLog>,
+    files: Option<FileTransfer>,
+    seq_error_reconnects: u64,
+    conn: ConnStats,
+    /// Tabel titik subperintah snapshot: nilai terakhir per (casdu, ioa).
+    snapshot: BTreeMap<(u16, u32), DecodedPoint>,
+    /// Tabel live --follow; None = log biasa.
+    follow: Option<FollowView>,
+}
+
 fn main() -> std::io::Result<()> {
-    println!("IEC 60870-5-104 Client/Master (ACK-only; Siemens w/t2; anti-45/46)");
-    println!("Menghubungkan ke RTU {} ...", RTU_ADDR);
-    let mut stream = TcpStream::connect(RTU_ADDR)?;
-    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
-    stream.set_nodelay(true)?;
-    let mut ack_stats = AckStats { w:0, t2:0, emergency:0 };
+    // Kesalahan konfigurasi bisa banyak baris (berkas situs): cetak apa adanya, bukan Debug io::Error
+    let mut cfg = Config::from_args().unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    });
+    logging::set_time(cfg.log_time);
+    logging::set_format(cfg.log_format, &cfg.rtu_addr);
+    logging::set_stream(cfg.log_stream);
+    logging::set_changes_only(cfg.log_changes_only);
+    logln!("IEC 60870-5-104 Client/Master (ACK-only; w/t2; anti-45/46/47)");
+    if let Some(addr) = &cfg.serve {
+        return mock::serve(addr);
+    }
+    if cfg.simulate.enabled {
+        return simulate::serve(&cfg.simulate);
+    }
+    if cfg.self_test {
+        return vectors::run();
+    }
+    #[cfg(feature = "bench")]
+    if cfg.bench {
+        return bench::run();
+    }
+    if cfg.dry_run {
+        return dryrun::run(&cfg.out_types, cfg.passive, cfg.ack_only());
+    }
+    if cfg.interactive {
+        return repl::run(&cfg.rtu_addr, cfg.annotate_hex, &cfg.out_types, cfg.ack_only());
+    }
+    // Label RTU di aliran kejadian: alamat yang dikonfigurasi, bukan loopback replay pcap
+    let rtu_label = cfg.rtu_addr.clone();
+    if let Some(path) = cfg.pcap.clone() {
+        let port = cfg.rtu_addr.rsplit_once(':').and_then(|(_, p)| p.parse().ok()).unwrap_or(2404);
+        let ex = pcap::extract(&path, port).map_err(ioerr)?;
+        match ex.flow {
+            Some((rtu, master)) => logln!(
+                "(Info) pcap {}: aliran {} -> {}, {} segmen, {} byte.", path, rtu, master, ex.segments, ex.data.len()
+            ),
+            None => return Err(ioerr(format!("pcap {}: tidak ada payload TCP dari port {}", path, port))),
+        }
+        if ex.gaps > 0 {
+            logln!("(Peringatan) pcap: {} celah urutan TCP (paket hilang dari capture); APDU di sekitarnya bisa rusak.", ex.gaps);
+        }
+        if ex.other_flows > 0 {
+            logln!("(Info) pcap: {} koneksi lain dari port {} diabaikan.", ex.other_flows, port);
+        }
+        cfg.rtu_addr = pcap::replay(ex.data)?.to_string();
+        cfg.bind = None;
+        cfg.reconnect = false;
+    }
+    if cfg.decode_only && !cfg.passive {
+        logln!("(Info) decode-only: tanpa S-ACK; RTU aktif akan berhenti setelah k I-frame dan menutup koneksi saat t1-nya habis.");
+    } else if !cfg.decode_only {
+        logln!("(Info) kebijakan S-ACK: {}", cfg.ack);
+    }
+    if cfg.out_types != TypePolicy::default() {
+        logln!("(Info) kebijakan tipe ASDU keluar: {}.", cfg.out_types);
+    }
+    if cfg.testfr.enabled {
+        logln!("(Info) supervisi TESTFR: {}", cfg.testfr);
+    }
+    if cfg.keepalive.idle.is_some() {
+        logln!("(Info) TCP keepalive: {}", cfg.keepalive);
+    }
+    if cfg.watch_sighup()? {
+        logln!("(Info) muat ulang konfigurasi: kirim SIGHUP (kill -HUP {}) atau ubah berkasnya.", std::process::id());
+    }
+    let params = Params::new(&cfg, cfg.ack_only(), T1);
+    for line in params.banner() { logln!("{}", line); }
+    let mut handler = HandlerChain::default();
+    if let Some(path) = &cfg.link_events {
+        let log = LinkEventLog::open(path, &rtu_label).map_err(|e| ioerr(format!("gagal buka log kejadian koneksi {}: {}", path, e)))?;
+        handler.push(Box::new(log));
+    }
+    // --pretty sudah mencetak tiap objek di pohon; baris teks per titik jadi duplikat.
+    // Snapshot mencetak titik sekali, sebagai tabel di akhir; follow menggambar tabel live.
+    if !(cfg.snapshot || cfg.follow.enabled || (cfg.pretty && cfg.format == OutputFormat::Text)) {
+        handler.push(Box::new(LoggingHandler::new(cfg.format, cfg.output_options())));
+    }
+    let mut sinks = SinkSet::from_specs(&cfg.sinks, cfg.rotation, cfg.output_options()).map_err(ioerr)?;
+    if !sinks.is_empty() {
+        let dead_letter = match &cfg.dead_letter {
+            Some(path) => Some(DeadLetter::open(path).map_err(|e| ioerr(format!("gagal buka dead-letter {}: {}", path, e)))?),
+            None => None,
+        };
+        sinks.set_delivery(cfg.sink_retry, dead_letter);
+        handler.push(Box::new(SinkWorker::spawn(sinks, cfg.sink_queue, cfg.sink_shutdown_timeout)));
+    }
+    let health = if cfg.health_addr.is_some() || cfg.metrics_addr.is_some() {
+        let h = Health::new(cfg.no_data_timeout);
+        match (&cfg.health_addr, &cfg.metrics_addr) {
+            (Some(addr), None) => health::serve(addr, Routes::All, h.clone())?,
+            (health_addr, Some(metrics_addr)) => {
+                if let Some(addr) = health_addr { health::serve(addr, Routes::Probes, h.clone())?; }
+                health::serve(metrics_addr, Routes::Metrics, h.clone())?;
+            }
+            (None, None) => unreachable!(),
+        }
+        handler.push(Box::new(HealthHandler(h.clone())));
+        h.set_params(params);
+        Some(h)
+    } else {
+        None
+    };
+    // Snapshot memotret nilai persis: tanpa deadband, nilai GI selalu masuk tabel
+    let cache = if cfg.snapshot {
+        PointCache::new(cache::Deadband::None, Default::default())
+    } else {
+        PointCache::new(cfg.deadband, cfg.point_deadbands.clone())
+    };
+    let history = FrameHistory::new(cfg.frame_history, cfg.hexdump);
+    if cfg.frame_history > 0 && history::watch_sigusr1()? {
+        logln!("(Info) dump riwayat APDU: kill -USR1 {}.", std::process::id());
+    }
+    let events = match &cfg.event_log {
+        Some(path) => Some(EventLog::open(path).map_err(|e| ioerr(format!("gagal buka log kejadian {}: {}", path, e)))?),
+        None => None,
+    };
+    if cfg.decode_only && cfg.file_dir.is_some() {
+        logln!("(Info) decode-only: transfer berkas dimatikan (butuh ASDU keluar).");
+        cfg.file_dir = None;
+    }
+    let files = cfg.file_dir.as_deref().map(|dir| FileTransfer::new(dir, cfg.file_auto_fetch, &cfg.fetch_files));
+    let mut col = Collector { cfg, handler, cache, ack_stats: AckStats::default(),
+        link: LinkStats::new(), history, health, events, files, seq_error_reconnects: 0,
+        conn: ConnStats::new(),
+        snapshot: BTreeMap::new(),
+        follow: None,
+    };
+    // Mulai menahan log setelah banner: parameter efektif masih terlihat sebelum tabel pertama
+    if col.cfg.follow.enabled { col.follow = Some(FollowView::start()); }
+
+    let mut snapshot_result: Result<(), String> = Err("sesi tidak berjalan".into());
+    // Percobaan sambung ulang berturut-turut sejak sesi terakhir yang berhasil tersambung
+    let mut reconnect_attempt = 0u64;
+    loop {
+        let end = run_session(&mut col);
+        let connected = col.handler.state() != ConnState::Connecting;
+        if let Some(c) = col.link.backlog.reset(Instant::now()) { log_backlog(&c, col.cfg.ack.k); }
+        if connected { reconnect_attempt = 0; }
+        if col.cfg.snapshot {
+            snapshot_result = match &end {
+                Ok(Disconnect::SnapshotDone) => Ok(()),
+                Ok(Disconnect::SnapshotFailed(e)) => Err(e.clone()),
+                Ok(d) => Err(format!("sesi berakhir sebelum GI selesai: {}", d)),
+                Err(e) => Err(e.to_string()),
+            };
+        }
+        col.handler.on_state_change(ConnState::Disconnected);
+        col.handler.on_link_event(&LinkEvent::Disconnected {
+            reason: end.as_ref().map_or(if connected { "io_error" } else { "connect_failed" }, Disconnect::key).to_string(),
+            detail: end.as_ref().map_or_else(|e| e.to_string(), |d| d.to_string()),
+        });
+        col.handler.on_stats(&col.link.snapshot(&col.ack_stats, ConnState::Disconnected, &col.cache));
+        if let Some(f) = col.follow.as_mut() { f.draw(&col.cfg, &col.cache, ConnState::Disconnected); }
+        // Err = gagal connect, atau kesalahan I/O (tulis/socket) saat tersambung
+        col.conn.disconnected(end.as_ref().map_or("io_error", Disconnect::key));
+        if let Some(h) = &col.health { h.set_conn(&col.conn); }
+        match &end {
+            Ok(d) => col.history.dump(&format!("sesi berakhir: {}", d)),
+            Err(e) => col.history.dump(&format!("sesi gagal: {}", e)),
+        }
+        let reconnect = match &end {
+            Ok(d @ Disconnect::Stopped) => {
+                logln!("Sesi berakhir: {}.", d);
+                false
+            }
+            Ok(d) if col.cfg.snapshot => {
+                logln!("Sesi berakhir: {}.", d);
+                false
+            }
+            // Siklus terjadwal ada justru untuk menyambung ulang, juga tanpa --reconnect
+            Ok(d @ Disconnect::MaxSession(_)) => {
+                logln!("(Info) sesi berakhir: {}; sambung ulang.", d);
+                true
+            }
+            // Kesalahan urutan & t1: spesifikasi mewajibkan putus; selalu sambung ulang.
+            // Framing hilang: sambungan baru satu-satunya cara sinkron kembali.
+            Ok(d @ (Disconnect::SequenceError(_) | Disconnect::FramingError(_) | Disconnect::T1Timeout | Disconnect::NoData | Disconnect::TestFrTimeout)) => {
+                if matches!(d, Disconnect::SequenceError(_)) {
+                    col.seq_error_reconnects += 1;
+                    logln!("Sequence error, reconnecting: {} (total {}).", d, col.seq_error_reconnects);
+                }
+                true
+            }
+            Ok(d) => {
+                logln!("Sesi berakhir: {}.", d);
+                col.cfg.reconnect
+            }
+            Err(e) => {
+                elogln!("Sesi gagal: {}", e);
+                if !col.cfg.reconnect { break; }
+                true
+            }
+        };
+        if !reconnect || stop_requested(&col.cfg) { break; }
+        reconnect_attempt += 1;
+        col.handler.on_link_event(&LinkEvent::Reconnect { attempt: reconnect_attempt, delay_ms: RECONNECT_DELAY.as_millis() as u64 });
+        logln!("Menyambung ulang dalam {:?} ...", RECONNECT_DELAY);
+        std::thread::sleep(RECONNECT_DELAY);
+    }
+
+    if let Some(f) = col.follow.take() { f.finish(&col.cfg, &col.cache); }
+    // Sink dikuras & ditutup sebelum ringkasan; berbatas waktu per sink (sink_shutdown_timeout)
+    col.handler.on_shutdown();
+
+    if col.cache.suppressed > 0 {
+        logln!("Ringkasan: {} nilai ditahan deadband.", col.cache.suppressed);
+    }
+    if col.link.filtered > 0 {
+        logln!("Ringkasan: {} objek disaring filter casdu/IOA.", col.link.filtered);
+    }
+    if col.link.casdu_frames.len() > 1 {
+        let snap = col.link.snapshot(&col.ack_stats, ConnState::Disconnected, &col.cache);
+        for (casdu, s) in &snap.casdus {
+            logln!(
+                "Ringkasan: casdu {}{}: {} I-frame, {} titik, {} objek, terakhir {}.",
+                casdu, col.cfg.names.station(*casdu).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                s.frames, s.points, s.updates,
+                s.last_update_ms.map(decode::format_epoch_ms).unwrap_or_else(|| "belum ada".into())
+            );
+        }
+    }
+    if !col.link.unknown_types.is_empty() {
+        let counts: Vec<String> = col.link.unknown_types.iter().map(|(t, n)| format!("{}×{}", t, n)).collect();
+        logln!("Ringkasan: ASDU tipe tak dikenal/tanpa decoder: {}.", counts.join(", "));
+    }
+    if col.link.foreign_replies > 0 {
+        logln!("Ringkasan: {} balasan perintah untuk master lain (OA berbeda) diabaikan pelacak perintah.", col.link.foreign_replies);
+    }
+    if col.cache.clock_jumps > 0 {
+        logln!("Ringkasan: {} lompatan jam sumber (mundur / maju melebihi jam lokal).", col.cache.clock_jumps);
+    }
+    if col.cache.counter_seq_gaps > 0 {
+        logln!("Ringkasan: {} SQ counter tidak naik satu (freeze terlewat / dibaca ulang).", col.cache.counter_seq_gaps);
+    }
+    if col.cache.active_alarms() > 0 {
+        logln!("Ringkasan: {} alarm masih aktif saat keluar.", col.cache.active_alarms());
+    }
+    if let Some(ev) = col.events.as_ref().filter(|ev| ev.count > 0) {
+        logln!("Ringkasan: {} kejadian dicatat ke log kejadian.", ev.count);
+    }
+    if let Some(ft) = col.files.as_ref().filter(|ft| ft.completed + ft.failed > 0) {
+        logln!("Ringkasan: {} berkas ditarik, {} gagal.", ft.completed, ft.failed);
+    }
+    if col.link.peer_nr_anomalies > 0 {
+        logln!("Ringkasan: {} anomali N(R) dari RTU (mundur / melompat).", col.link.peer_nr_anomalies);
+    }
+    if col.link.integrity_warnings + col.link.integrity_errors > 0 {
+        logln!("Ringkasan: integritas frame: {} error, {} peringatan.", col.link.integrity_errors, col.link.integrity_warnings);
+    }
+    if col.link.strict_rejects > 0 {
+        logln!("Ringkasan: {} frame ditolak mode strict.", col.link.strict_rejects);
+    }
+    if col.link.startdt_act_rx > 0 {
+        logln!("Ringkasan: {} STARTDT act diterima dari peer (peran master/RTU tertukar?).", col.link.startdt_act_rx);
+    }
+    if col.link.end_of_init > 0 {
+        logln!(
+            "Ringkasan: RTU restart {} kali (M_EI_NA_1), {} dengan parameter lokal berubah.",
+            col.link.end_of_init, col.link.end_of_init_changed
+        );
+    }
+    if col.link.testfr_failures > 0 {
+        logln!("Ringkasan: {} TESTFR act tanpa con dalam t1 ({} beruntun di akhir).", col.link.testfr_failures, col.link.testfr_fail_streak);
+    }
+    if col.link.rx_overflows > 0 {
+        logln!("Ringkasan: buffer RX meluap {} kali (sampah dibuang sampai 0x68 berikutnya).", col.link.rx_overflows);
+    }
+    if col.link.u_other > 0 {
+        logln!("Ringkasan: {} U-frame tidak standar diterima.", col.link.u_other);
+    }
+    if col.link.i_empty > 0 {
+        logln!("Ringkasan: {} I-frame tanpa ASDU diterima (APCI saja).", col.link.i_empty);
+    }
+    if col.seq_error_reconnects > 0 {
+        logln!("Ringkasan: {} koneksi ulang akibat kesalahan urutan.", col.seq_error_reconnects);
+    }
+    if col.cfg.log_changes_only && col.link.quiet_frames > 0 {
+        logln!("Ringkasan: jejak {} dari {} frame tidak dilog (tanpa perubahan, --log-changes-only).", col.link.quiet_frames, col.link.frames);
+    }
+    if col.link.backlog.episodes > 0 {
+        logln!("Ringkasan: backlog RTU dicurigai {} kali, total {:.1}s.", col.link.backlog.episodes, col.link.backlog.total.as_secs_f64());
+    }
+    for line in col.conn.summary() {
+        logln!("Ringkasan: {}.", line);
+    }
+    if col.cfg.snapshot {
+        print_snapshot(&col);
+        return snapshot_result.map_err(|e| ioerr(format!("snapshot gagal: {}", e)));
+    }
+    Ok(())
+}
+
+/// Berkas --stop-trigger muncul: hapus lalu berhenti rapi (handler di-shutdown, sink dikuras).
+fn stop_requested(cfg: &Config) -> bool {
+    let Some(path) = cfg.stop_trigger.as_deref().filter(|p| std::path::Path::new(p).exists()) else { return false };
+    let _ = std::fs::remove_file(path);
+    logln!("(Info) pemicu berhenti {} ditemukan.", path);
+    true
+}
+
+/// Log awal/akhir episode backlog RTU.
+fn log_backlog(change: &BacklogChange, k: u16) {
+    match change {
+        BacklogChange::Started { high, emergency, acks, rate } => logln!(
+            "(Peringatan) backlog RTU dicurigai: {} dari {} S-ACK dalam {:?} dengan jendela hampir penuh (k={}, {} emergency), laju {:.1} I-frame/s; RTU kemungkinan menguras buffer kejadian.",
+            high, acks, backlog::WINDOW, k, emergency, rate
+        ),
+        BacklogChange::Ended { took, frames } => logln!(
+            "(Info) backlog RTU selesai setelah {:.1}s ({} I-frame).", took.as_secs_f64(), frames
+        ),
+    }
+}
+
+/// Tabel titik snapshot dalam format keluaran terpilih, urut casdu lalu IOA.
+fn print_snapshot(col: &Collector) {
+    let cfg = &col.cfg;
+    logln!("Snapshot: {} titik.", col.snapshot.len());
+    // Tabel = data, bukan log: tanpa stempel waktu agar bisa di-parse/di-diff
+    if cfg.format == OutputFormat::Csv {
+        println!("{}", cfg.csv.header());
+    }
+    let mut casdu = None;
+    for p in col.snapshot.values() {
+        // Baris teks tidak memuat casdu: beri judul per casdu
+        if cfg.format == OutputFormat::Text && casdu != Some(p.casdu) {
+            println!("  casdu {}{}:", p.casdu, p.station.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default());
+            casdu = Some(p.casdu);
+        }
+        println!("{}", output::format_point(cfg.format, &cfg.output_options(), p));
+    }
+}
+
+/// Resolve alamat RTU (hostname, IPv4, `[IPv6]:port`) lalu coba tiap alamat berurutan.
+/// Dengan --bind, hanya alamat sekeluarga dengan alamat lokal yang dicoba.
+fn connect_rtu(cfg: &Config) -> std::io::Result<TcpStream> {
+    let mut targets: Vec<_> = cfg.rtu_addr.to_socket_addrs()?.collect();
+    if targets.is_empty() {
+        return Err(ioerr(format!("alamat RTU tidak ter-resolve: {}", cfg.rtu_addr)));
+    }
+    if let Some(local) = cfg.bind {
+        targets.retain(|t| t.is_ipv4() == local.is_ipv4());
+        if targets.is_empty() {
+            return Err(ioerr(format!("--bind {} beda keluarga alamat dengan semua alamat {}", local, cfg.rtu_addr)));
+        }
+    }
+    let mut last_err = None;
+    for t in &targets {
+        match connect_from(*t, cfg.bind) {
+            Ok(s) => {
+                logln!("Tersambung ke {} (dari {}).", t, cfg.rtu_addr);
+                return Ok(s);
+            }
+            Err(e) => {
+                logln!("(Info) gagal ke {}: {}", t, e);
+                last_err = Some(e);
+            }
+        }
+    }
+    Err(last_err.unwrap_or_else(|| ioerr("tidak ada alamat untuk dicoba".into())))
+}
+
+/// `TcpStream::connect`, atau bind ke alamat lokal dulu (std::net tidak punya bind-sebelum-connect).
+fn connect_from(target: SocketAddr, local: Option<SocketAddr>) -> std::io::Result<TcpStream> {
+    let Some(local) = local else { return TcpStream::connect(target) };
+    let socket = Socket::new(Domain::for_address(target), Type::STREAM, Some(Protocol::TCP))?;
+    // Port sumber tetap: sambung ulang cepat tidak boleh gagal karena TIME_WAIT koneksi lama
+    if local.port() != 0 { socket.set_reuse_address(true)?; }
+    socket.bind(&local.into()).map_err(|e| ioerr(format!("bind {}: {}", local, e)))?;
+    socket.connect(&target.into())?;
+    Ok(socket.into())
+}
+
+/// SO_KEEPALIVE dari [tcp] keepalive_*; interval/count yang tidak diset memakai bawaan OS.
+fn set_keepalive(stream: &TcpStream, ka: &KeepalivePolicy) -> std::io::Result<()> {
+    let Some(idle) = ka.idle else { return Ok(()) };
+    #[allow(unused_mut)]
+    let mut params = TcpKeepalive::new().with_time(idle);
+    #[cfg(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "netbsd"))]
+    {
+        if let Some(i) = ka.interval { params = params.with_interval(i); }
+        if let Some(n) = ka.count { params = params.with_retries(n); }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos", target_os = "ios", target_os = "freebsd", target_os = "netbsd")))]
+    if ka.interval.is_some() || ka.count.is_some() {
+        logln!("(Peringatan) keepalive interval/count tidak didukung OS ini; hanya idle yang dipakai.");
+    }
+    SockRef::from(stream).set_tcp_keepalive(&params)
+}
+
+/// Batas bawah read timeout (nol berarti blok selamanya bagi `set_read_timeout`).
+const MIN_READ_TIMEOUT: Duration = Duration::from_millis(1);
+
+/// Read timeout dasar read loop. Link sepi tetap membangunkan loop tiap interval follow agar
+/// umur di tabel berjalan, dan paling lambat tiap t3/t1 TESTFR agar supervisi idle tidak
+/// terlambat sampai read timeout.
+fn base_read_timeout(cfg: &Config) -> Duration {
+    let mut t = if cfg.follow.enabled { cfg.read_timeout.min(cfg.follow.interval) } else { cfg.read_timeout };
+    if cfg.testfr.enabled { t = t.min(cfg.testfr.t3).min(cfg.testfr.t1); }
+    t
+}
+
+/// Satu koneksi TCP: STARTDT, baca/ACK sampai putus. Ok(alasan) bila berakhir "normal".
+fn run_session(col: &mut Collector) -> std::io::Result<Disconnect> {
+    let Collector { cfg, handler, cache, ack_stats, link, history, health, events, files, conn, snapshot, follow, .. } = col;
+    logln!("Menghubungkan ke RTU {} ...", cfg.rtu_addr);
+    handler.on_state_change(ConnState::Connecting);
+    let mut stream = connect_rtu(cfg)?;
+    let session_start = Instant::now();
+    conn.connected();
+    if let Some(h) = health { h.set_conn(conn); }
+    handler.on_state_change(ConnState::Connected);
+    handler.on_link_event(&LinkEvent::Connected { peer: stream.peer_addr().map_or_else(|_| cfg.rtu_addr.clone(), |a| a.to_string()) });
+    let mut base_timeout = base_read_timeout(cfg);
+    let mut read_timeout = base_timeout;
+    stream.set_read_timeout(Some(read_timeout))?;
+    stream.set_write_timeout(cfg.write_timeout)?;
+    stream.set_nodelay(cfg.nodelay)?;
+    set_keepalive(&stream, &cfg.keepalive)?;
+    let mut gi = GiTracker::new();
+    if let Some(ft) = files.as_mut() { ft.reset(); }
+    // Medan alamat ASDU RX per situs; GI & berkas hanya jalan di profil 104 (dijaga Config)
+    let layout = cfg.asdu.layout();
+    // Transfer berkas baru dimulai setelah STARTDT con
+    let mut data_active = false;
+    // I-frame pertama sesi ini sudah dilaporkan sebagai kejadian data-started
+    let mut data_started = false;
     // Gatekeeper untuk semua TX
-    let mut tx = TxPolicy::new();
+    let mut tx = TxPolicy::new(RateLimiter::new(cfg.tx_max_fps, cfg.tx_min_gap), cfg.max_outstanding_tx, cfg.passive, cfg.ack_only(), cfg.out_types.clone(), cfg.originator);
+    // Mode pasif: belum tahu N(S) saat bergabung di tengah aliran
+    let mut passive_synced = false;
 
-    // STARTDT act sekali (opsional)
-    if SEND_STARTDT_ONCE {
-        tx.send_startdt(&mut stream)?;
+    // STARTDT act sekali; tanpa STARTDT = listen-only untuk gateway yang memulai aliran sendiri
+    if cfg.passive {
+        // Data sudah mengalir antara RTU dan master sebenarnya; kita hanya mendengar
+        logln!("(Info) mode pasif: tidak ada TX sama sekali (STARTDT, S-ACK, TESTFR con).");
+        handler.on_state_change(ConnState::Active);
+    } else if cfg.startdt {
+        tx.send_startdt(&mut stream, handler)?;
+        handler.on_state_change(ConnState::StartDtSent);
+        handler.on_link_event(&LinkEvent::StartDtSent);
     } else {
-        println!("(Info) STARTDT act dimatikan; banyak RTU tidak kirim data tanpa ini.");
+        logln!("(Info) listen-only: STARTDT act tidak dikirim, kita tidak memulai transfer data; menunggu peer mengirim I-frame (tetap di-ACK).");
     }
 
     // Buffer penerimaan & state parsing
     let mut rx_buf: Vec<u8> = Vec::with_capacity(8192);
     let mut tmp = [0u8; 4096];
 
-    // State ACK koalescing (Siemens w/t2)
-    let mut since_last_ack = 0usize;
-    let mut t2_started: Option<Instant> = None;
+    // Urutan & koalescing S-ACK (w/t2, cfg.ack); decode-only hanya memakai st.next_nr
+    let mut st = LoopState::new(cfg.ack);
+    // Sesi aktif mulai dari N(S) kita = 0; pasif tidak tahu urutan master sebelum frame pertama
+    link.peer_nr = if cfg.passive { None } else { Some(0) };
+    link.window_k = cfg.ack.k;
 
-    // State sequence / window
-    let mut last_ack_nr: u16 = 0; // N(R) terakhir yang sudah dikirim
-    let mut next_nr: u16 = 0;     // N(R) kandidat untuk ACK berikutnya
-
-    // Untuk (opsional) TESTFR saat idle — default dinonaktifkan
+    // Supervisi TESTFR (cfg.testfr): idle dihitung dari frame masuk apa pun
     let mut last_read = Instant::now();
+    // Waktu kirim TESTFR act yang belum dijawab con (RTT & t1), dan berapa act di siklus ini
+    let mut testfr_sent: Option<Instant> = None;
+    let mut testfr_tries = 0u8;
+    // Watchdog "tidak ada data": hanya I-frame yang dihitung (TESTFR/S-frame tidak)
+    let mut last_data_instant = Instant::now();
+    // Snapshot statistik ke handler paling sering tiap STATS_PUSH_INTERVAL
+    let mut last_stats_push = Instant::now();
 
     // Baca terus sampai koneksi putus.
     loop {
+        // S-ACK t2 yang tertunda tidak boleh menunggu read timeout penuh
+        let want = st.t2_remaining(Instant::now()).map_or(base_timeout, |r| base_timeout.min(r.max(MIN_READ_TIMEOUT)));
+        if want != read_timeout {
+            stream.set_read_timeout(Some(want))?;
+            read_timeout = want;
+        }
         match stream.read(&mut tmp) {
             Ok(0) => {
-                println!("Koneksi ditutup oleh peer.");
-                break;
+                logln!("Koneksi ditutup oleh peer.");
+                return Ok(Disconnect::PeerClosed);
             }
             Ok(n) => {
                 last_read = Instant::now();
@@ -82,140 +825,1011 @@ fn main() -> std::io::Result<()> {
 
                 // Proses semua APDU utuh yang ada di buffer
                 while let Some((apdu, consumed)) = take_one_apdu(&rx_buf) {
+                    // --log-changes-only: jejak frame ini ditahan sampai isinya diketahui
+                    logging::hold_frame();
+                    let mut notable = false;
                     // Tampilkan hex mentahnya
-                    println!("< RX {} bytes: {}", apdu.len(), hex(apdu));
+                    if cfg.pretty {
+                        logln!("{}", pretty_frame(apdu, cfg));
+                    } else {
+                        logln!("< RX {} bytes:{}", apdu.len(), render_apdu(apdu, cfg));
+                    }
+                    link.frames += 1;
+                    history.push(apdu);
+                    handler.on_raw_rx(apdu);
 
                     // Klasifikasikan & tampilkan ringkasan
-                    match classify_apdu(apdu) {
-                        Frame::U(ut) => {
-                            println!("  ▸ Frame: U-Frame ({})", ut);
+                    let frame = classify_apdu_as(apdu, layout);
+                    match frame {
+                        Frame::U { ut, control } => {
+                            link.u_frames += 1;
+                            // STARTDT/STOPDT = perubahan status link; TESTFR = rutin
+                            notable = !matches!(ut, UType::TestFrAct | UType::TestFrCon);
+                            if !cfg.pretty { logln!({ control = hex(&control) }, "  ▸ Frame: U-Frame ({}) | control={}", ut, hex(&control)); }
+                            if let UType::Other(b) = ut {
+                                let flood = link.u_other_flood();
+                                logln!(
+                                    "(Peringatan) U-frame tidak standar: kontrol 0x{:02X} ({:08b}), APDU {} (ke-{}).",
+                                    b, b, hex(apdu), link.u_other
+                                );
+                                if cfg.parse_mode.strict() {
+                                    if let Some(d) = strict_reject(cfg, link, history, &[format!("U-frame kontrol 0x{:02X}", b)]) { return Ok(d); }
+                                }
+                                if flood {
+                                    let why = format!("{} U-frame tidak standar dalam {:?}", U_OTHER_FLOOD, U_OTHER_WINDOW);
+                                    // Pasif: tidak bisa sambung ulang ke tap; cukup diperingatkan
+                                    if !cfg.passive { return Ok(Disconnect::FramingError(why)); }
+                                    logln!("(Peringatan) mode pasif: {}; kemungkinan framing hilang.", why);
+                                }
+                            }
+                            if ut == UType::TestFrCon {
+                                if let Some(sent) = testfr_sent.take() {
+                                    testfr_tries = 0;
+                                    link.testfr_fail_streak = 0;
+                                    let rtt = sent.elapsed();
+                                    logln!("  ▸ RTT TESTFR: {:.1} ms", rtt.as_secs_f64() * 1000.0);
+                                    link.rtt = Some(rtt);
+                                    handler.on_link_event(&LinkEvent::TestFr { initiator: "master", rtt_ms: Some(rtt.as_secs_f64() * 1000.0) });
+                                }
+                            }
+                            // Pasif: STARTDT act milik master asli di tap, wajar
+                            if ut == UType::StartDtAct && !cfg.passive {
+                                link.startdt_act_rx += 1;
+                                logln!(
+                                    { count = link.startdt_act_rx },
+                                    "(Peringatan) STARTDT act diterima dari peer: peer menganggap kita stasiun terkendali. \
+                                     Kemungkinan peran tertukar (master ke master?); tidak dijawab (ke-{}).",
+                                    link.startdt_act_rx
+                                );
+                            }
+                            if ut == UType::TestFrAct {
+                                handler.on_link_event(&LinkEvent::TestFr { initiator: "rtu", rtt_ms: None });
+                            }
                             if ut == UType::StartDtCon {
-                                println!("  ▸ STARTDT dikonfirmasi RTU. Data dapat mulai mengalir.");
+                                logln!("  ▸ STARTDT dikonfirmasi RTU. Data dapat mulai mengalir.");
+                                handler.on_state_change(ConnState::Active);
+                                handler.on_link_event(&LinkEvent::StartDtCon);
+                                data_active = true;
+                                // Tiap sesi baru (termasuk setelah reconnect) cache di-refresh lewat GI
+                                if (cfg.auto_gi || cfg.snapshot) && !gi.is_active() {
+                                    // I-frame keluar ikut membawa N(R) => sekaligus ACK
+                                    match tx.send_gi(&mut stream, handler, st.next_nr, GI_CASDU, GI_QOI)? {
+                                        TxOutcome::Sent => {
+                                            gi.start(GI_CASDU, GI_QOI);
+                                            st.sent(st.next_nr);
+                                        }
+                                        TxOutcome::Queued => gi.start(GI_CASDU, GI_QOI),
+                                        TxOutcome::Blocked if cfg.snapshot => {
+                                            return Ok(Disconnect::SnapshotFailed("GI act diblok gatekeeper TX (periksa kebijakan tipe keluar)".into()));
+                                        }
+                                        TxOutcome::Blocked => {}
+                                    }
+                                }
                             }
                         }
-                        Frame::S { nr } => {
-                            println!("  ▸ Frame: S-Frame (ACK) | N(R)={}", nr);
+                        Frame::S { nr, control } => {
+                            link.s_frames += 1;
+                            if !cfg.pretty { logln!({ nr = nr, control = hex(&control) }, "  ▸ Frame: S-Frame (ACK) | N(R)={} | control={}", nr, hex(&control)); }
+                            // Pasif: batas lompatan = perkiraan k master; aktif: I-frame kita yang belum di-ACK
+                            let max_step = if cfg.passive { cfg.max_outstanding_tx } else { tx.outstanding() };
+                            if let Some(why) = link.track_peer_nr(nr, max_step) {
+                                logln!({ nr = nr }, "(Peringatan) N(R) RTU {}.", why);
+                                if cfg.parse_mode.strict() {
+                                    if let Some(d) = strict_reject(cfg, link, history, &[format!("N(R) {}", why)]) { return Ok(d); }
+                                }
+                            }
+                            // Pasif: N(R) meng-ACK frame master sebenarnya, bukan milik kita
+                            if !cfg.decode_only {
+                                if let Err(e) = tx.accept_peer_nr(nr) {
+                                    return Ok(Disconnect::SequenceError(e));
+                                }
+                                if tx.flush_pending(&mut stream, handler, st.next_nr)? > 0 {
+                                    st.sent(st.next_nr);
+                                }
+                            }
                         }
-                        Frame::I { ns, nr, asdu } => {
-                            println!("  ▸ Frame: I-Frame | N(S)={} N(R)={}", ns, nr);
+                        Frame::I { ns, nr, ref asdu, control } => {
+                            if !cfg.pretty { logln!({ ns = ns, nr = nr, control = hex(&control) }, "  ▸ Frame: I-Frame | N(S)={} N(R)={} | control={}", ns, nr, hex(&control)); }
+                            if !data_started {
+                                data_started = true;
+                                handler.on_link_event(&LinkEvent::DataStarted);
+                                // Listen-only: tidak ada STARTDT con; I-frame pertama = aliran dimulai peer
+                                if !cfg.startdt && !cfg.passive {
+                                    logln!("  ▸ Peer memulai transfer data tanpa STARTDT dari kita.");
+                                    handler.on_state_change(ConnState::Active);
+                                    data_active = true;
+                                }
+                            }
+                            link.i_frames += 1;
+                            link.backlog.i_frame(Instant::now());
+                            link.last_data = Some(Instant::now());
+                            if let Some(h) = health { h.data_seen(); }
+                            if conn.data() {
+                                if let Some(h) = health { h.set_conn(conn); }
+                            }
+                            last_data_instant = Instant::now();
+                            // Temuan integritas frame ini; lenient = hanya peringatan, strict = isi frame ditolak
+                            // bila ada error
+                            let mut report = IntegrityReport::default();
+                            // N(R) piggyback: di ACK-only kita tidak pernah kirim I-frame, jadi harus tetap 0
+                            let max_step = if cfg.passive { cfg.max_outstanding_tx } else { tx.outstanding() };
+                            if let Some(why) = link.track_peer_nr(nr, max_step) {
+                                logln!({ nr = nr }, "(Peringatan) N(R) RTU {}.", why);
+                                report.error(format!("N(R) {}", why));
+                            }
+                            // N(S) harus tepat = N(R) yang kita harapkan; celah berarti data hilang
+                            if cfg.passive {
+                                // Tidak bisa minta ulang: catat celah lalu ikuti urutan RTU
+                                if passive_synced && ns != st.next_nr {
+                                    logln!("(Peringatan) mode pasif: N(S) celah, diharapkan {}, diterima {}.", st.next_nr, ns);
+                                    report.error(format!("N(S) celah: diharapkan {}, diterima {}", st.next_nr, ns));
+                                }
+                                passive_synced = true;
+                            } else {
+                                if ns != st.next_nr {
+                                    return Ok(Disconnect::SequenceError(format!("N(S) celah: diharapkan {}, diterima {}", st.next_nr, ns)));
+                                }
+                                if let Err(e) = tx.accept_peer_nr(nr) {
+                                    return Ok(Disconnect::SequenceError(e));
+                                }
+                            }
+                            for issue in report.check_i_frame(apdu, asdu.as_ref().map(|a| &a.full), layout) {
+                                logln!(
+                                    { ns = ns, severity = issue.severity.key() },
+                                    "(Peringatan) integritas frame N(S)={}: {}.", ns, issue
+                                );
+                            }
+                            // Balasan transfer berkas dikirim setelah N(R) memuat frame ini
+                            let mut file_out = Vec::new();
+                            // M_EI_NA_1 di frame ini: RTU baru restart
+                            let mut restarted = false;
                             if let Some(a) = asdu {
-                                println!(
-                                    "    ASDU: type_id={}{} vsq=0x{:02X} cot={} casdu={} ioa_first={}",
+                                *link.asdu_types.entry(a.type_id).or_default() += 1;
+                                *link.casdu_frames.entry(a.casdu).or_default() += 1;
+                                if !cfg.pretty { logln!(
+                                    { ns = ns, nr = nr, type_id = a.type_id, cot = a.cot, casdu = a.casdu, ioa = a.ioa_first },
+                                    "    ASDU: type_id={}{} vsq=0x{:02X} N={} SQ={} cot={}{} casdu={}{} ioa_first={}{}",
                                     a.type_id,
                                     asdu_type_name(a.type_id).map(|n| format!(" ({})", n)).unwrap_or_default(),
-                                    a.vsq, a.cot, a.casdu, a.ioa_first
+                                    a.vsq, a.num_objects, a.is_sequence as u8, a.cot,
+                                    cot_name(a.cot).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                                    a.casdu,
+                                    cfg.names.station(a.casdu).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                                    a.ioa_first,
+                                    if a.malformed { " (malformed)" } else { "" }
+                                ); }
+                                if is_unknown_type(a.type_id) {
+                                    *link.unknown_types.entry(a.type_id).or_default() += 1;
+                                    logln!(
+                                        "(Peringatan) ASDU type {} tidak dikenal/tanpa decoder (ke-{}): objek {}",
+                                        a.type_id, link.unknown_types[&a.type_id], apdu.get(6 + layout.header_len()..).map(hex).unwrap_or_default()
+                                    );
+                                    report.error(format!("type id {} tanpa decoder", a.type_id));
+                                }
+                                let origin = Origin::from_cot(a.cot);
+                                // Malformed tidak punya objek utuh: cache/sink tidak menerima nilai ngawur
+                                if let AsduStatus::Malformed(_) = a.full.status { history.dump("ASDU malformed"); }
+                                let objects = a.full.objects.clone();
+                                // Strict: frame tetap dihitung untuk N(R)/ACK, tetapi isinya tidak dipakai
+                                let rejected = match judge_frame(cfg, link, history, &report) {
+                                    Ok(rejected) => rejected,
+                                    Err(d) => return Ok(d),
+                                };
+                                let objects = if rejected { Vec::new() } else { objects };
+                                if !rejected {
+                                    // Bus multi-master: balasan perintah master lain tidak boleh dicocokkan ke perintah kita
+                                    let foreign = cfg.originator.is_some_and(|oa| oa != a.oa) && is_reply_cot(a.cot);
+                                    if foreign {
+                                        link.foreign_replies += 1;
+                                        logln!(
+                                            "    (Audit) balasan untuk master lain: OA={} (kita {}), type {} cot={}; tidak dicocokkan ke perintah kita.",
+                                            a.oa, cfg.originator.unwrap_or(0), a.type_id, a.cot
+                                        );
+                                    } else {
+                                        // Qualifier (QOI/QCC) = oktet setelah IOA objek tunggal
+                                        gi.on_asdu(a, apdu.get(6 + layout.header_len() + layout.ioa).copied());
+                                        if let Some(ft) = files.as_mut() { file_out = ft.on_asdu(&apdu[6..]); }
+                                    }
+                                }
+                                for obj in &objects {
+                                    let ObjValue::EndOfInit { coi } = obj.value else { continue };
+                                    restarted = true;
+                                    cache.reset_counter_seqs(a.casdu);
+                                    link.end_of_init += 1;
+                                    if coi & 0x80 != 0 { link.end_of_init_changed += 1; }
+                                    logln!(
+                                        { casdu = a.casdu, coi = coi },
+                                        "(Peringatan) RTU restart casdu={}{}: COI={}{}",
+                                        a.casdu, cfg.names.station(a.casdu).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                                        decode::describe_coi(coi),
+                                        if coi & 0x80 != 0 { "; konfigurasi RTU mungkin sudah lain dari sebelumnya." } else { "." }
+                                    );
+                                }
+                                for obj in objects {
+                                    if !cfg.filter.matches(a.casdu, obj.ioa) {
+                                        link.filtered += 1;
+                                        continue;
+                                    }
+                                    // Integritas SOE: time tag (valid) per casdu harus maju sejalan jam lokal
+                                    if let (Some(t), Some(max_jump)) = (obj.time.filter(|t| !t.iv), cfg.max_clock_jump) {
+                                        // Replay pcap tidak berjalan di waktu nyata: hanya cek mundur
+                                        let max_jump = Some(max_jump).filter(|_| cfg.pcap.is_none());
+                                        if let Some(why) = cache.clock_jump(a.casdu, t.utc_epoch_ms(&cfg.rtu_clock), max_jump, Instant::now()) {
+                                            logln!(
+                                                { casdu = a.casdu, ioa = obj.ioa },
+                                                "(Peringatan) jam RTU casdu={} {} pada ioa={} (ke-{}); jam baru disetel atau tidak stabil?",
+                                                a.casdu, why, obj.ioa, cache.clock_jumps
+                                            );
+                                        }
+                                    }
+                                    // Integritas billing: SQ counter harus naik satu tiap freeze
+                                    if let (true, Some(seq)) = (cfg.check_counter_seq, obj.value.counter_seq()) {
+                                        if let Some(why) = cache.counter_seq(a.casdu, obj.ioa, seq) {
+                                            logln!(
+                                                { casdu = a.casdu, ioa = obj.ioa, seq = seq },
+                                                "(Peringatan) counter casdu={} ioa={}{}: {} (ke-{}).",
+                                                a.casdu, obj.ioa, cfg.names.point(a.casdu, obj.ioa).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                                                why, cache.counter_seq_gaps
+                                            );
+                                        }
+                                    }
+                                    let scaling = cfg.scaling.get(a.casdu, obj.ioa);
+                                    let value_eng = obj.value.as_f64().map(|raw| scaling.map_or(raw, |s| s.apply(raw)));
+                                    // Alarm dievaluasi sebelum deadband agar lintasan ambang kecil tidak hilang;
+                                    // nilai invalid tidak memunculkan/memulihkan alarm
+                                    let mut alarms = Vec::new();
+                                    if let (Some(v), false) = (value_eng, obj.quality.is_some_and(|q| q.iv)) {
+                                        for def in cfg.alarms.iter().filter(|d| d.key.matches(a.casdu, obj.ioa)) {
+                                            if let Some(raised) = cache.alarm_transition(def, a.casdu, obj.ioa, v) {
+                                                alarms.push((def, raised));
+                                            }
+                                        }
+                                    }
+                                    // Status sebelum update: pembanding untuk log kejadian
+                                    let prev = cache.last(a.casdu, obj.ioa);
+                                    // Report-by-exception: hanya emit bila lewat deadband / kualitas berubah
+                                    let emit = cache.update(a.casdu, &obj)
+                                        && (cfg.origins.is_empty() || cfg.origins.contains(&origin));
+                                    notable |= emit || !alarms.is_empty() || obj.quality.is_some_and(|q| !q.is_good());
+                                    if !emit && alarms.is_empty() { continue; }
+                                    let reason = obj.time.and_then(|t| t.suspect_reason(&cfg.rtu_clock, cfg.max_clock_skew));
+                                    if let Some(r) = &reason {
+                                        logln!("(Peringatan) waktu sumber mencurigakan casdu={} ioa={}: {}", a.casdu, obj.ioa, r);
+                                    }
+                                    let p = DecodedPoint {
+                                        casdu: a.casdu, type_id: a.type_id, cot: a.cot, origin,
+                                        time_utc_ms: obj.time.map(|t| t.utc_epoch_ms(&cfg.rtu_clock)),
+                                        station: cfg.names.station(a.casdu).map(String::from),
+                                        point: cfg.names.point(a.casdu, obj.ioa).map(String::from),
+                                        value_eng,
+                                        unit: scaling.and_then(|s| s.unit.clone()),
+                                        obj,
+                                        suspect_time: reason.is_some(),
+                                    };
+                                    for (def, raised) in alarms {
+                                        logln!("(ALARM) {} casdu={} ioa={}: {}", def.name, p.casdu, p.obj.ioa, events::alarm_detail(def, raised, &p));
+                                        if let Some(Err(e)) = events.as_mut().map(|ev| ev.alarm(def, raised, &p)) {
+                                            elogln!("(Peringatan) gagal tulis log kejadian: {}", e);
+                                        }
+                                    }
+                                    if !emit { continue; }
+                                    if let Some(Err(e)) = events.as_mut().map(|ev| ev.record(&p, prev)) {
+                                        elogln!("(Peringatan) gagal tulis log kejadian: {}", e);
+                                    }
+                                    handler.on_point(&p);
+                                    // Tabel snapshot hanya titik arah monitor (M_*, type 1..44), bukan con/term perintah
+                                    if cfg.snapshot && p.type_id < 45 { snapshot.insert((p.casdu, p.obj.ioa), p); }
+                                }
+                            } else if apdu.len() == 6 {
+                                // LEN=4: urutan tetap maju dan di-ACK, tetapi tidak ada data yang bisa dibawa
+                                link.i_empty += 1;
+                                logln!(
+                                    { ns = ns },
+                                    "(Peringatan) I-frame tanpa ASDU (LEN=4, APCI saja) N(S)={} (ke-{}); bug panjang/framing di pengirim atau perangkat tidak patuh?",
+                                    ns, link.i_empty
                                 );
+                                report.error("I-frame tanpa ASDU");
+                                if let Err(d) = judge_frame(cfg, link, history, &report) { return Ok(d); }
                             } else {
-                                println!("    ASDU: (tidak utuh/pendek)");
+                                logln!("    ASDU: (tidak utuh/pendek)");
+                                report.error("ASDU tidak utuh/pendek");
+                                if let Err(d) = judge_frame(cfg, link, history, &report) { return Ok(d); }
+                            }
+
+                            st.next_nr = seq_inc(ns);               // ACK untuk frame ini => ns+1 (mod 32768)
+
+                            // Decode-only (termasuk pasif): jendela & ACK bukan urusan kita
+                            if !cfg.decode_only {
+                                // N(R) dari RTU membebaskan jendela kirim; I-frame antrean ikut membawa ACK
+                                if tx.flush_pending(&mut stream, handler, st.next_nr)? > 0 {
+                                    st.sent(st.next_nr);
+                                }
+                                for (label, out) in file_out {
+                                    match tx.send_i(&mut stream, handler, st.next_nr, label, out)? {
+                                        TxOutcome::Sent => st.sent(st.next_nr),
+                                        TxOutcome::Queued => {}
+                                        TxOutcome::Blocked => if let Some(ft) = files.as_mut() { ft.abort("ASDU keluar diblok") },
+                                    }
+                                }
+
+                                // Hitung jendela terpakai di sisi pengirim (RTU)
+                                let used = st.unacked();
+                                link.window_used = used;
+                                logln!(
+                                    "    window_used ≈ {}/{} ({}%)",
+                                    used,
+                                    st.policy.k,
+                                    ((used as f32 / st.policy.k as f32) * 100.0).round() as u32
+                                );
+
+                                let actions = process_frame(&mut st, &frame, Instant::now());
+                                send_acks(actions, &mut stream, &mut tx, handler, ack_stats, link, used)?;
                             }
 
-                            // Update koalescing dan jendela
-                            next_nr = seq_inc(ns);                  // ACK untuk frame ini => ns+1 (mod 32768)
-                            since_last_ack += 1;
-                            if t2_started.is_none() { t2_started = Some(Instant::now()); }
-
-                            // Hitung jendela terpakai di sisi pengirim (RTU)
-                            let used = seq_distance(next_nr, last_ack_nr);
-                            println!(
-                                "    window_used ≈ {}/{} ({}%)",
-                                used,
-                                SIEMENS_K,
-                                ((used as f32 / SIEMENS_K as f32) * 100.0).round() as u32
-                            );
-
-                            // Keputusan ACK:
-                            let emergency = used >= SIEMENS_K.saturating_sub(2); // hampir mentok k
-                            let need_by_count = since_last_ack >= SIEMENS_W;     // capai w
-                            let need_by_t2 = t2_started.map(|s| s.elapsed() >= T2).unwrap_or(false);
-
-                            if emergency || need_by_count || need_by_t2 {
-                                let reason = if emergency { "emergency" } else if need_by_count { "w" } else { "t2" };
-                                tx.send_s_ack(&mut stream, next_nr, reason)?;
-                                ack_stats.inc(reason);
-                                println!("    ack_stats: w={} t2={} emergency={}", ack_stats.w, ack_stats.t2, ack_stats.emergency);
-   
-                                last_ack_nr = next_nr;
-                                since_last_ack = 0;
-                                t2_started = None;
+                            // Restart RTU: data di sisi RTU dibangun ulang; auto GI menyegarkan cache
+                            // seperti setelah STARTDT con
+                            if restarted && cfg.auto_gi && data_active {
+                                if gi.is_active() {
+                                    logln!("(Info) GI setelah restart RTU dilewati: GI sebelumnya masih berjalan.");
+                                } else {
+                                    match tx.send_gi(&mut stream, handler, st.next_nr, GI_CASDU, GI_QOI)? {
+                                        TxOutcome::Sent => {
+                                            gi.start(GI_CASDU, GI_QOI);
+                                            st.sent(st.next_nr);
+                                        }
+                                        TxOutcome::Queued => gi.start(GI_CASDU, GI_QOI),
+                                        TxOutcome::Blocked => {}
+                                    }
+                                }
                             }
                         }
                         Frame::Unknown => {
-                            println!("  ▸ Frame: (tidak dikenali)");
+                            link.unknown_frames += 1;
+                            logln!("  ▸ Frame: (tidak dikenali)");
+                            history.dump("frame tidak dikenali");
+                            if cfg.parse_mode.strict() {
+                                if let Some(d) = strict_reject(cfg, link, history, &["APCI tidak dikenali".to_string()]) { return Ok(d); }
+                            }
                         }
                     }
 
+                    logging::end_frame(notable);
+                    if !notable { link.quiet_frames += 1; }
                     // Geser buffer yang sudah dikonsumsi
                     rx_buf.drain(0..consumed);
                 }
+                // Sisa setelah semua APDU utuh diambil seharusnya < satu APDU; lebih dari batas
+                // berarti sampah tanpa 0x68 menumpuk (peer membanjiri / framing hilang)
+                if rx_buf.len() > cfg.max_buffer_bytes {
+                    link.rx_overflows += 1;
+                    let cut = resync_point(&rx_buf);
+                    elogln!(
+                        "(Error) buffer RX melebihi {} byte (ke-{}): {} byte dibuang sampai 0x68 valid berikutnya. Awal buffer:\n{}",
+                        cfg.max_buffer_bytes, link.rx_overflows, cut, hexdump(&rx_buf[..rx_buf.len().min(64)])
+                    );
+                    rx_buf.drain(..cut);
+                }
             }
-            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                // Idle — jika ingin TESTFR saat idle, aktifkan flag SEND_TESTFR_WHEN_IDLE
+            // Interrupted: SIGHUP (muat ulang konfigurasi) memotong read bertimeout
+            Err(ref e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::Interrupted) => {
+                // Idle — supervisi TESTFR (t3) diatur lewat --testfr / [testfr], dicek di bawah
             }
             Err(e) => {
-                eprintln!("Kesalahan saat membaca: {}", e);
-                break;
+                elogln!("Kesalahan saat membaca: {}", e);
+                return Ok(Disconnect::ReadError(e.to_string()));
             }
         }
 
-        // (Opsional) kirim TESTFR act jika idle > 25 detik (default: off agar ACK-only murni)
-        if SEND_TESTFR_WHEN_IDLE && last_read.elapsed() > Duration::from_secs(25) {
-            let test_act = [0x68, 0x04, U_TESTFR_ACT, 0x00, 0x00, 0x00];
-            // Lewat gatekeeper juga (akan diblok bila ACK_ONLY true)
-            if let Err(e) = TxPolicy::enforce_static(&test_act) {
-                println!("(Blok) TESTFR act: {}", e);
-            } else {
-                println!("> TX TESTFR act (idle): {}", hex(&test_act));
-                let _ = stream.write_all(&test_act);
+        // t2 jatuh tempo juga saat link sepi: kurang dari w I-frame lalu diam tetap di-ACK
+        // sebelum t1 RTU habis. Antrean TX yang tertahan rate limiter ikut dikuras walau
+        // tidak ada frame masuk.
+        if !cfg.decode_only {
+            if tx.flush_pending(&mut stream, handler, st.next_nr)? > 0 {
+                st.sent(st.next_nr);
+            }
+            let used = st.unacked();
+            send_acks(process_idle(&mut st, Instant::now()), &mut stream, &mut tx, handler, ack_stats, link, used)?;
+        }
+
+        history.check_trigger(cfg.dump_trigger.as_deref());
+        if stop_requested(cfg) { return Ok(Disconnect::Stopped); }
+        match cfg.reload_if_changed() {
+            Some(Ok(changes)) if changes.is_empty() => logln!("Konfigurasi dimuat ulang: tidak ada perubahan runtime."),
+            Some(Ok(changes)) => {
+                for c in &changes { logln!("Konfigurasi dimuat ulang: {}", c); }
+                cache.set_deadbands(cfg.deadband, cfg.point_deadbands.clone());
+                link.window_k = cfg.ack.k;
+                st.policy = cfg.ack;
+                tx.set_out_types(cfg.out_types.clone());
+                // TESTFR baru aktif/berubah: t3/t1 ikut membatasi read timeout mulai putaran berikutnya
+                base_timeout = base_read_timeout(cfg);
+                if let Some(h) = health { h.set_params(Params::new(cfg, cfg.ack_only(), T1)); }
+            }
+            Some(Err(e)) => logln!("(Peringatan) muat ulang konfigurasi gagal, nilai lama tetap dipakai: {}", e),
+            None => {}
+        }
+
+        // GI yang tidak pernah ditutup act-term ditandai
+        gi.check_timeout();
+        if let Some(c) = link.backlog.check(Instant::now()) { log_backlog(&c, cfg.ack.k); }
+        if last_stats_push.elapsed() >= STATS_PUSH_INTERVAL {
+            let state = handler.state();
+            handler.on_stats(&link.snapshot(ack_stats, state, cache));
+            last_stats_push = Instant::now();
+        }
+        if let Some(f) = follow.as_mut() { f.tick(cfg, cache, handler.state()); }
+        if cfg.snapshot {
+            let failed = match gi.take_end() {
+                Some(GiEnd::Complete) => {
+                    finish_stopdt(&mut stream, &mut tx, handler, Some((st.next_nr, st.last_nr)), "snapshot")?;
+                    return Ok(Disconnect::SnapshotDone);
+                }
+                Some(GiEnd::Rejected) => Some("GI ditolak RTU (act-con negatif)".to_string()),
+                Some(GiEnd::TimedOut) => Some(format!("GI tidak selesai dalam {:?}", GI_TIMEOUT)),
+                None if session_start.elapsed() > cfg.snapshot_timeout => {
+                    Some(format!("act-term tidak diterima dalam {:?}", cfg.snapshot_timeout))
+                }
+                None => None,
+            };
+            if let Some(why) = failed {
+                finish_stopdt(&mut stream, &mut tx, handler, Some((st.next_nr, st.last_nr)), "snapshot")?;
+                return Ok(Disconnect::SnapshotFailed(why));
+            }
+        }
+        if let Some(max) = cfg.max_session.filter(|m| session_start.elapsed() >= *m) {
+            logln!("(Info) umur sesi mencapai max-session {:?}: STOPDT, putus, sambung ulang (siklus terjadwal).", max);
+            // Pasif tidak mengirim apa pun; decode-only tidak pernah S-ACK
+            if !cfg.passive {
+                let acks = (!cfg.decode_only).then_some((st.next_nr, st.last_nr));
+                finish_stopdt(&mut stream, &mut tx, handler, acks, "max-session")?;
             }
-            last_read = Instant::now();
+            return Ok(Disconnect::MaxSession(max));
         }
+        handler.on_tick();
+        if let Some(ft) = files.as_mut().filter(|_| data_active) {
+            ft.check_timeout();
+            if let Some((label, out)) = ft.poll() {
+                match tx.send_i(&mut stream, handler, st.next_nr, label, out)? {
+                    TxOutcome::Sent => {
+                        st.sent(st.next_nr);
+                    }
+                    TxOutcome::Queued => {}
+                    TxOutcome::Blocked => ft.abort("ASDU keluar diblok"),
+                }
+            }
+        }
+        link.maybe_report(cfg.stats_interval, ack_stats);
+
+        if let Some(limit) = cfg.no_data_timeout {
+            if last_data_instant.elapsed() >= limit {
+                elogln!("(ALARM) no data from RTU selama {:?} (koneksi TCP masih hidup).", last_data_instant.elapsed());
+                last_data_instant = Instant::now(); // re-arm
+                // Auto GI: celah data juga memicu GI; reconnect sendiri berakhir di GI setelah STARTDT con
+                let action = match cfg.no_data_action {
+                    NoDataAction::Log if cfg.auto_gi => NoDataAction::Gi,
+                    a => a,
+                };
+                match action {
+                    NoDataAction::Log => {}
+                    NoDataAction::Reconnect => return Ok(Disconnect::NoData),
+                    NoDataAction::Gi if !gi.is_active() => {
+                        match tx.send_gi(&mut stream, handler, st.next_nr, GI_CASDU, GI_QOI)? {
+                            TxOutcome::Sent => {
+                                gi.start(GI_CASDU, GI_QOI);
+                                st.sent(st.next_nr);
+                            }
+                            TxOutcome::Queued => gi.start(GI_CASDU, GI_QOI),
+                            TxOutcome::Blocked => {}
+                        }
+                    }
+                    NoDataAction::Gi => logln!("(Info) GI watchdog dilewati: GI sebelumnya masih berjalan."),
+                }
+            }
+        }
+
+        // t1: I-frame kita tidak di-ACK => 104 tidak mengenal retransmisi selektif, tutup koneksi
+        if let Some(lost) = tx.check_t1() {
+            logln!("t1 habis: I-frame N(S)={:?} tidak di-ACK RTU dalam {:?}. Menutup koneksi.", lost, T1);
+            return Ok(Disconnect::T1Timeout);
+        }
+
+        // Supervisi TESTFR: t3 idle -> act; t1 tanpa con -> kirim ulang; habis -> sambung ulang
+        if cfg.testfr.enabled {
+            let due = match testfr_sent {
+                None => last_read.elapsed() >= cfg.testfr.t3,
+                Some(sent) if sent.elapsed() >= cfg.testfr.t1 => {
+                    link.testfr_failures += 1;
+                    link.testfr_fail_streak += 1;
+                    if testfr_tries > cfg.testfr.retries {
+                        elogln!(
+                            { streak = link.testfr_fail_streak },
+                            "(Error) TESTFR act tidak dijawab setelah {} percobaan (t1={:?}); link dianggap mati, menutup koneksi.",
+                            testfr_tries, cfg.testfr.t1
+                        );
+                        return Ok(Disconnect::TestFrTimeout);
+                    }
+                    logln!("(Peringatan) TESTFR con tidak diterima dalam {:?} ({} beruntun); kirim ulang.", cfg.testfr.t1, link.testfr_fail_streak);
+                    true
+                }
+                Some(_) => false,
+            };
+            if due {
+                if tx.send_testfr(&mut stream, handler)? {
+                    testfr_sent = Some(Instant::now());
+                    testfr_tries += 1;
+                } else {
+                    // Diblok gatekeeper: tidak ada con yang bisa ditunggu; coba lagi setelah t3
+                    testfr_sent = None;
+                    testfr_tries = 0;
+                }
+                last_read = Instant::now();
+            }
+        }
+    }
+}
+
+
+/// Akhiri sesi dengan rapi (snapshot, siklus terjadwal): ACK I-frame yang tersisa, STOPDT act,
+/// lalu tunggu STOPDT con (RTU boleh mengirim sisa data dulu; semuanya di-ACK) paling lama t1.
+/// `acks` = (N(R) berikutnya, N(R) terakhir terkirim); None = decode-only, tanpa S-ACK.
+fn finish_stopdt(stream: &mut TcpStream, tx: &mut TxPolicy, handler: &mut HandlerChain, acks: Option<(u16, u16)>, reason: &str) -> std::io::Result<()> {
+    if let Some((next_nr, _)) = acks.filter(|(next, last)| next != last) {
+        tx.send_s_ack(stream, handler, next_nr, reason)?;
+    }
+    if let Err(e) = tx.send_stopdt(stream, handler) {
+        logln!("(Blok) STOPDT act: {}; koneksi langsung ditutup.", e);
+        return Ok(());
+    }
+    let deadline = Instant::now() + T1;
+    let mut rx_buf = Vec::new();
+    let mut tmp = [0u8; 4096];
+    while Instant::now() < deadline {
+        match stream.read(&mut tmp) {
+            Ok(0) => break,
+            Ok(n) => rx_buf.extend_from_slice(&tmp[..n]),
+            Err(ref e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted) => continue,
+            Err(e) => return Err(e),
+        }
+        while let Some((apdu, consumed)) = take_one_apdu(&rx_buf) {
+            handler.on_raw_rx(apdu);
+            match classify_apdu(apdu) {
+                Frame::U { ut: UType::StopDtCon, .. } => {
+                    logln!("  ▸ STOPDT dikonfirmasi RTU.");
+                    handler.on_link_event(&LinkEvent::StopDt { confirmed: true });
+                    return Ok(());
+                }
+                Frame::I { ns, .. } if acks.is_some() => {
+                    tx.send_s_ack(stream, handler, seq_inc(ns), "stopdt")?;
+                }
+                _ => {}
+            }
+            rx_buf.drain(0..consumed);
+        }
+    }
+    logln!("(Peringatan) STOPDT con tidak diterima dalam {:?}; koneksi ditutup.", T1);
+    handler.on_link_event(&LinkEvent::StopDt { confirmed: false });
+    Ok(())
+}
+
+// ================= Keputusan terima & S-ACK (tanpa socket) =================
+/// State urutan & koalescing S-ACK sisi terima, terpisah dari socket: read loop dan unit test
+/// skenario menjalankan `process_frame` yang sama, dengan waktu disuntikkan.
+struct LoopState {
+    policy: AckPolicy,
+    /// N(R) kandidat ACK berikutnya = N(S) I-frame yang diharapkan.
+    next_nr: u16,
+    /// N(R) terakhir yang sudah kita kirim (S-ACK atau piggyback I-frame).
+    last_nr: u16,
+    /// Kapan I-frame tertua yang belum di-ACK diterima (t2).
+    t2_started: Option<Instant>,
+}
+
+/// Frame keluar yang diputuskan `process_frame`.
+#[derive(Debug, PartialEq, Eq)]
+enum OutAction {
+    SAck { nr: u16, reason: &'static str },
+}
+
+impl LoopState {
+    fn new(policy: AckPolicy) -> Self {
+        LoopState { policy, next_nr: 0, last_nr: 0, t2_started: None }
+    }
+
+    /// Frame keluar (S-ACK atau I-frame) membawa N(R) = `nr`.
+    fn sent(&mut self, nr: u16) {
+        self.last_nr = nr;
+        self.t2_started = None;
     }
 
+    /// I-frame diterima yang belum di-ACK (= jendela terpakai di sisi RTU).
+    fn unacked(&self) -> u16 { seq_distance(self.next_nr, self.last_nr) }
+
+    /// Sisa waktu sampai S-ACK t2 jatuh tempo (nol = sudah lewat); None = tidak ada yang menunggu.
+    fn t2_remaining(&self, now: Instant) -> Option<Duration> {
+        self.t2_started.map(|s| self.policy.t2.saturating_sub(now.duration_since(s)))
+    }
+
+    /// Alasan S-ACK sekarang (kunci AckStats), atau None = tahan dulu.
+    fn decide(&self, now: Instant) -> Option<&'static str> {
+        let unacked = self.unacked();
+        if unacked == 0 { return None; }
+        // immediate = S-ACK per I-frame (w=1, tanpa tunggu t2)
+        if self.policy.mode == AckMode::Immediate { return Some("immediate"); }
+        // Hampir mentok k
+        if unacked >= self.policy.emergency_at() { return Some("emergency"); }
+        if unacked >= self.policy.w { return Some("w"); }
+        self.t2_started.filter(|s| now.duration_since(*s) >= self.policy.t2).map(|_| "t2")
+    }
+}
+
+/// Satu frame masuk -> frame yang harus dikirim. Hanya I-frame yang menggeser urutan dan bisa
+/// memicu S-ACK; N(S) sudah diperiksa pemanggil, I-frame keluar yang membawa N(R) dicatat
+/// lewat `LoopState::sent` sebelum ini dipanggil.
+fn process_frame(state: &mut LoopState, frame: &Frame, now: Instant) -> Vec<OutAction> {
+    let Frame::I { ns, .. } = frame else { return Vec::new() };
+    state.next_nr = seq_inc(*ns);
+    if state.unacked() > 0 && state.t2_started.is_none() {
+        state.t2_started = Some(now);
+    }
+    process_idle(state, now)
+}
+
+/// Putaran loop tanpa I-frame baru (read timeout, S/U-frame): hanya t2 yang bisa jatuh tempo.
+fn process_idle(state: &mut LoopState, now: Instant) -> Vec<OutAction> {
+    let Some(reason) = state.decide(now) else { return Vec::new() };
+    state.sent(state.next_nr);
+    vec![OutAction::SAck { nr: state.next_nr, reason }]
+}
+
+/// Kirim S-ACK hasil `process_frame`/`process_idle` dan catat ke statistik & backlog.
+fn send_acks(
+    actions: Vec<OutAction>,
+    stream: &mut TcpStream,
+    tx: &mut TxPolicy,
+    handler: &mut HandlerChain,
+    ack_stats: &mut AckStats,
+    link: &mut LinkStats,
+    used: u16,
+) -> std::io::Result<()> {
+    for action in actions {
+        match action {
+            OutAction::SAck { nr, reason } => {
+                tx.send_s_ack(stream, handler, nr, reason)?;
+                ack_stats.inc(reason);
+                link.backlog.ack(Instant::now(), reason, used, link.window_k);
+                logln!(
+                    "    ack_stats: w={} t2={} emergency={} immediate={}",
+                    ack_stats.w, ack_stats.t2, ack_stats.emergency, ack_stats.immediate
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod ack_tests {
+    use super::*;
+
+    /// Frame terima dalam skenario, dengan waktu tiba (ms sejak awal skenario).
+    #[derive(Clone, Copy)]
+    enum Rx {
+        /// I-frame berikutnya (N(S) berurutan dari `start`).
+        I(u64),
+        /// S-ACK dari RTU.
+        S(u64),
+        /// TESTFR act dari RTU.
+        U(u64),
+        /// Read timeout tanpa frame (link sepi).
+        Idle(u64),
+    }
+
+    const I8: &[Rx] = &[Rx::I(0), Rx::I(1), Rx::I(2), Rx::I(3), Rx::I(4), Rx::I(5), Rx::I(6), Rx::I(7)];
+
+    fn policy(mode: AckMode, w: u16, t2_ms: u64, emergency: f64) -> AckPolicy {
+        AckPolicy { mode, k: 12, w, t2: Duration::from_millis(t2_ms), emergency }
+    }
+
+    /// Frame dibangun dengan encoder sisi kirim lalu diklasifikasi seperti read loop; hasil
+    /// (indeks frame di `rx`, N(R), alasan) tiap S-ACK yang keluar.
+    fn run(policy: AckPolicy, start: u16, rx: &[Rx]) -> Vec<(usize, u16, &'static str)> {
+        let mut st = LoopState::new(policy);
+        st.next_nr = start;
+        st.last_nr = start;
+        let base = Instant::now();
+        let mut ns = start;
+        let mut got = Vec::new();
+        for (i, &rx) in rx.iter().enumerate() {
+            let (apdu, at) = match rx {
+                Rx::I(at) => {
+                    let f = build_i_frame(ns, 0, &[0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01]);
+                    ns = seq_inc(ns);
+                    (f, at)
+                }
+                Rx::S(at) => (build_s_ack(0).to_vec(), at),
+                Rx::U(at) => (vec![0x68, 0x04, U_TESTFR_ACT, 0x00, 0x00, 0x00], at),
+                Rx::Idle(at) => (Vec::new(), at),
+            };
+            let now = base + Duration::from_millis(at);
+            // Seperti read loop: frame (bila ada) lalu pemeriksaan t2 tiap putaran
+            let mut out = if apdu.is_empty() { Vec::new() } else { process_frame(&mut st, &classify_apdu(&apdu), now) };
+            out.extend(process_idle(&mut st, now));
+            for OutAction::SAck { nr, reason } in out {
+                got.push((i, nr, reason));
+            }
+        }
+        got
+    }
+
+    #[test]
+    fn w_coalesces_eight_frames() {
+        assert_eq!(run(policy(AckMode::Coalesced, 8, 10_000, 0.85), 0, I8), [(7, 8, "w")]);
+    }
+
+    #[test]
+    fn t2_fires_exactly_at_t2() {
+        let rx = [Rx::I(0), Rx::I(1_000), Rx::I(9_999), Rx::I(10_000), Rx::I(10_500)];
+        assert_eq!(run(policy(AckMode::Coalesced, 8, 10_000, 0.85), 0, &rx), [(3, 4, "t2")]);
+    }
+
+    #[test]
+    fn t2_restarts_after_w_ack() {
+        let rx = [Rx::I(0), Rx::I(1), Rx::I(2), Rx::I(3), Rx::I(4_000), Rx::I(5_500), Rx::I(9_000)];
+        assert_eq!(run(policy(AckMode::Coalesced, 4, 5_000, 0.85), 0, &rx), [(3, 4, "w"), (6, 7, "t2")]);
+    }
+
+    #[test]
+    fn immediate_acks_every_frame() {
+        let rx = [Rx::I(0), Rx::I(0), Rx::I(0)];
+        assert_eq!(
+            run(policy(AckMode::Immediate, 8, 10_000, 0.85), 0, &rx),
+            [(0, 1, "immediate"), (1, 2, "immediate"), (2, 3, "immediate")]
+        );
+    }
+
+    #[test]
+    fn emergency_precedes_w() {
+        assert_eq!(run(policy(AckMode::Coalesced, 12, 10_000, 0.5), 0, I8), [(5, 6, "emergency")]);
+    }
+
+    #[test]
+    fn w_across_wrap() {
+        assert_eq!(run(policy(AckMode::Coalesced, 8, 10_000, 0.85), 32764, I8), [(7, 4, "w")]);
+    }
+
+    #[test]
+    fn one_frame_then_silence_acks_at_t2() {
+        let rx = [Rx::I(0), Rx::Idle(5_000), Rx::Idle(9_999), Rx::Idle(10_000), Rx::Idle(20_000)];
+        assert_eq!(run(policy(AckMode::Coalesced, 8, 10_000, 0.85), 0, &rx), [(3, 1, "t2")]);
+    }
+
+    #[test]
+    fn t2_remaining_bounds_the_idle_wait() {
+        let mut st = LoopState::new(policy(AckMode::Coalesced, 8, 10_000, 0.85));
+        let base = Instant::now();
+        assert_eq!(st.t2_remaining(base), None);
+        let i = classify_apdu(&build_i_frame(0, 0, &[0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01]));
+        assert!(process_frame(&mut st, &i, base).is_empty());
+        assert_eq!(st.t2_remaining(base + Duration::from_millis(4_000)), Some(Duration::from_millis(6_000)));
+        assert_eq!(st.t2_remaining(base + Duration::from_millis(12_000)), Some(Duration::ZERO));
+        assert_eq!(process_idle(&mut st, base + Duration::from_millis(12_000)), [OutAction::SAck { nr: 1, reason: "t2" }]);
+        assert_eq!(st.t2_remaining(base + Duration::from_millis(12_000)), None);
+    }
+
+    #[test]
+    fn s_and_u_frames_do_not_ack_or_start_t2() {
+        let rx = [Rx::S(0), Rx::U(500), Rx::S(5_000), Rx::I(6_000), Rx::U(6_500), Rx::I(6_900), Rx::S(8_000), Rx::I(8_000)];
+        // t2 dari I-frame 6000 sudah lewat saat S-frame 8000 tiba: ACK di putaran itu, bukan
+        // menunggu I-frame berikutnya
+        assert_eq!(run(policy(AckMode::Coalesced, 8, 1_000, 0.85), 0, &rx), [(6, 2, "t2")]);
+    }
+}
+
+/// Satu-satunya jalan byte keluar ke socket: APDU yang benar-benar terkirim diteruskan ke
+/// `FrameHandler::on_raw_tx`.
+fn write_apdu(stream: &mut TcpStream, handler: &mut HandlerChain, apdu: &[u8]) -> std::io::Result<()> {
+    stream.write_all(apdu)?;
+    handler.on_raw_tx(apdu);
     Ok(())
 }
 
 // ================= Gatekeeper TX (blokir frame terlarang) =================
+const MAX_PENDING_TX: usize = 16; // antrean I-frame saat jendela kirim (k) penuh
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum TxOutcome { Sent, Queued, Blocked }
+
 struct TxPolicy {
     startdt_sent: bool,
+    ns: u16,              // N(S) untuk I-frame keluar berikutnya
+    peer_ack: u16,        // N(R) terakhir dari RTU: semua N(S) < ini sudah di-ACK
+    max_outstanding: u16, // k sisi kirim
+    pending: VecDeque<(String, Vec<u8>)>, // (label, ASDU) menunggu slot jendela
+    unacked: VecDeque<SentFrame>,         // salinan I-frame terkirim yang belum di-ACK
+    limiter: RateLimiter,
+    passive: bool,        // mode pasif: semua frame keluar diblok, termasuk S-ACK
+    ack_only: bool,       // ACK_ONLY efektif (Config::ack_only)
+    types: TypePolicy,    // tipe ASDU keluar yang diizinkan
+    originator: Option<u8>, // OA yang ditulis ke tiap ASDU keluar (bus multi-master)
+}
+
+struct SentFrame {
+    ns: u16,
+    apdu: Vec<u8>,
+    sent_at: Instant,
 }
 impl TxPolicy {
-    fn new() -> Self { Self { startdt_sent: false } }
+    fn new(limiter: RateLimiter, max_outstanding: u16, passive: bool, ack_only: bool, types: TypePolicy, originator: Option<u8>) -> Self {
+        Self {
+            startdt_sent: false, ns: 0, peer_ack: 0, max_outstanding,
+            pending: VecDeque::new(), unacked: VecDeque::new(), limiter, passive, ack_only, types, originator,
+        }
+    }
+
+    /// Muat ulang konfigurasi: kebijakan tipe baru berlaku untuk frame berikutnya, termasuk antrean.
+    fn set_out_types(&mut self, types: TypePolicy) { self.types = types; }
 
-    fn send_startdt(&mut self, stream: &mut TcpStream) -> std::io::Result<()> {
+    /// Jumlah I-frame kita yang belum di-ACK RTU.
+    fn outstanding(&self) -> u16 { seq_distance(self.ns, self.peer_ack) }
+
+    fn send_startdt(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain) -> std::io::Result<()> {
         if self.startdt_sent {
-            println!("(Lewati) STARTDT act sudah pernah dikirim.");
+            logln!("(Lewati) STARTDT act sudah pernah dikirim.");
             return Ok(());
         }
         let apdu = [0x68u8, 0x04, U_STARTDT_ACT, 0x00, 0x00, 0x00];
         self.enforce(&apdu).map_err(ioerr)?;
-        println!("> TX STARTDT act: {}", hex(&apdu));
-        stream.write_all(&apdu)?;
+        logln!("> TX STARTDT act: {}", hex(&apdu));
+        write_apdu(stream, handler, &apdu)?;
         self.startdt_sent = true;
         Ok(())
     }
 
-    fn send_s_ack(&mut self, stream: &mut TcpStream, nr: u16, reason: &str) -> std::io::Result<()> {
+    /// STOPDT act; Err bila diblok gatekeeper (ACK-only hanya mengizinkan STARTDT).
+    fn send_stopdt(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain) -> Result<(), String> {
+        let apdu = [0x68u8, 0x04, U_STOPDT_ACT, 0x00, 0x00, 0x00];
+        self.enforce(&apdu)?;
+        logln!("> TX STOPDT act: {}", hex(&apdu));
+        write_apdu(stream, handler, &apdu).map_err(|e| e.to_string())
+    }
+
+    /// TESTFR act untuk supervisi idle; Ok(false) bila diblok gatekeeper (mis. ACK-only).
+    fn send_testfr(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain) -> std::io::Result<bool> {
+        let apdu = [0x68u8, 0x04, U_TESTFR_ACT, 0x00, 0x00, 0x00];
+        if let Err(e) = self.enforce(&apdu) {
+            logln!("(Blok) TESTFR act: {}", e);
+            return Ok(false);
+        }
+        logln!("> TX TESTFR act (idle): {}", hex(&apdu));
+        write_apdu(stream, handler, &apdu)?;
+        Ok(true)
+    }
+
+    fn send_s_ack(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain, nr: u16, reason: &str) -> std::io::Result<()> {
         let apdu = build_s_ack(nr);
         self.enforce(&apdu).map_err(ioerr)?;
-        println!("> TX S-ACK N(R)={} (reason: {}) {}", nr, reason, hex(&apdu));
-        stream.write_all(&apdu)
+        logln!({ nr = nr, reason = reason }, "> TX S-ACK N(R)={} (reason: {}) {}", nr, reason, hex(&apdu));
+        write_apdu(stream, handler, &apdu)
+    }
+
+    /// Kirim C_IC_NA_1 act (diblok selama ACK-only tanpa --enable-tx).
+    fn send_gi(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain, nr: u16, casdu: u16, qoi: u8) -> std::io::Result<TxOutcome> {
+        let label = format!("GI act casdu={} qoi={}", casdu, qoi);
+        self.send_i(stream, handler, nr, label, build_gi_asdu(casdu, qoi).to_vec())
+    }
+
+    /// Kirim I-frame; bila sudah k frame belum di-ACK, masukkan antrean.
+    fn send_i(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain, nr: u16, label: String, mut asdu: Vec<u8>) -> std::io::Result<TxOutcome> {
+        // Builder menulis OA=0; RTU memantulkan OA ini di con/term sehingga balasan bisa dipilah
+        if let (Some(oa), Some(b)) = (self.originator, asdu.get_mut(3)) { *b = oa; }
+        if self.outstanding() >= self.max_outstanding || !self.pending.is_empty() {
+            if self.pending.len() >= MAX_PENDING_TX {
+                logln!("(Blok) {}: antrean TX penuh ({} frame).", label, MAX_PENDING_TX);
+                return Ok(TxOutcome::Blocked);
+            }
+            logln!("(Antre) {}: jendela kirim penuh ({}/{} belum di-ACK).", label, self.outstanding(), self.max_outstanding);
+            self.pending.push_back((label, asdu));
+            return Ok(TxOutcome::Queued);
+        }
+        self.transmit_i(stream, handler, nr, &label, &asdu)
     }
 
-    fn enforce(&self, apdu: &[u8]) -> Result<(), String> {
-        Self::enforce_static(apdu)
+    fn transmit_i(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain, nr: u16, label: &str, asdu: &[u8]) -> std::io::Result<TxOutcome> {
+        let apdu = build_i_frame(self.ns, nr, asdu);
+        if let Err(e) = self.enforce(&apdu) {
+            logln!("(Blok) {}: {}", label, e);
+            return Ok(TxOutcome::Blocked);
+        }
+        logln!(
+            { ns = self.ns, nr = nr, type_id = asdu.first().copied(), casdu = asdu.get(4..6).map(|c| u16::from_le_bytes([c[0], c[1]])) },
+            "> TX {} N(S)={} N(R)={}: {}", label, self.ns, nr, hex(&apdu)
+        );
+        write_apdu(stream, handler, &apdu)?;
+        self.unacked.push_back(SentFrame { ns: self.ns, apdu, sent_at: Instant::now() });
+        self.ns = seq_inc(self.ns);
+        Ok(TxOutcome::Sent)
+    }
+
+    /// Some(daftar N(S) hilang) bila I-frame tertua belum di-ACK lebih lama dari t1.
+    fn check_t1(&self) -> Option<Vec<u16>> {
+        let oldest = self.unacked.front()?;
+        if oldest.sent_at.elapsed() < T1 { return None; }
+        for f in &self.unacked {
+            logln!("    tidak di-ACK: N(S)={} {}", f.ns, hex(&f.apdu));
+        }
+        Some(self.unacked.iter().map(|f| f.ns).collect())
     }
 
-    /// Versi statis (bisa dipakai di luar instance)
-    fn enforce_static(apdu: &[u8]) -> Result<(), String> {
+    /// Validasi N(R) dari RTU (S- atau I-frame). Err bila N(R) mustahil (meng-ACK frame yang belum dikirim).
+    fn accept_peer_nr(&mut self, peer_nr: u16) -> Result<(), String> {
+        // Sah: peer_ack..=ns (N(R) = ns berarti semua terkirim sudah di-ACK)
+        if !seq_in_window(peer_nr, self.peer_ack, self.outstanding() + 1) {
+            return Err(format!("N(R)={} di luar jendela kirim (ack={} ns={})", peer_nr, self.peer_ack, self.ns));
+        }
+        self.peer_ack = peer_nr;
+        // Buang salinan yang sudah di-ACK: tersisa hanya N(S) di [peer_ack, ns)
+        let (ack, pending) = (self.peer_ack, self.outstanding());
+        self.unacked.retain(|f| seq_in_window(f.ns, ack, pending));
+        Ok(())
+    }
+
+    /// Kirim antrean yang muat di jendela. Mengembalikan jumlah I-frame terkirim
+    /// (masing-masing membawa N(R) = ACK kita).
+    fn flush_pending(&mut self, stream: &mut TcpStream, handler: &mut HandlerChain, nr: u16) -> std::io::Result<usize> {
+        let mut sent = 0;
+        while self.outstanding() < self.max_outstanding {
+            let Some((label, asdu)) = self.pending.pop_front() else { break; };
+            // Diblok aturan (tipe, ACK-only, pasif) tidak akan pernah lolos: buang agar antrean
+            // di belakangnya tidak macet selamanya
+            if let Err(e) = self.check_rules(&build_i_frame(self.ns, nr, &asdu)) {
+                logln!("(Buang) {} dari antrean TX: {}", label, e);
+                continue;
+            }
+            match self.transmit_i(stream, handler, nr, &label, &asdu)? {
+                TxOutcome::Sent => sent += 1,
+                _ => {
+                    // Sisa penyebab = rate limiter: kembalikan ke depan antrean, coba putaran berikutnya
+                    self.pending.push_front((label, asdu));
+                    break;
+                }
+            }
+        }
+        Ok(sent)
+    }
+
+    /// Aturan statis saja (pasif, ACK-only, tipe): gagal di sini berarti gagal selamanya.
+    fn check_rules(&self, apdu: &[u8]) -> Result<(), String> {
+        if self.passive {
+            return Err("mode pasif: semua TX diblok.".into());
+        }
+        Self::enforce_rules(apdu, &self.types, self.ack_only)
+    }
+
+    /// Aturan statis + rate limiter. S-frame dan U con (respon protokol) tidak dibatasi.
+    fn enforce(&mut self, apdu: &[u8]) -> Result<(), String> {
+        self.check_rules(apdu)?;
+        if is_protocol_response(apdu) {
+            return Ok(());
+        }
+        self.limiter.try_acquire(Instant::now())
+            .map_err(|wait| format!("diblok rate limiter TX (coba lagi ~{} ms).", wait.as_millis()))
+    }
+
+    /// Aturan statis dengan ACK-only sebagai parameter: --dry-run menilai juga jalur kirim
+    /// yang sudah dipersenjatai tanpa mengubah konfigurasi.
+    fn enforce_rules(apdu: &[u8], types: &TypePolicy, ack_only: bool) -> Result<(), String> {
         if apdu.len() < 6 || apdu[0] != 0x68 {
             return Err("APDU invalid/pendek".into());
         }
+        if apdu[1] as usize + 2 != apdu.len() {
+            return Err(format!("LEN={} tidak cocok dengan panjang APDU {} byte.", apdu[1], apdu.len()));
+        }
         let c = &apdu[2..6];
 
         // U-frame?
         if (c[0] & 0b11) == 0b11 {
-            // Hanya izinkan STARTDT act bila ACK_ONLY == true
-            if ACK_ONLY && c[0] != U_STARTDT_ACT {
+            // Hanya izinkan STARTDT act selama ACK-only
+            if ack_only && c[0] != U_STARTDT_ACT {
                 return Err(format!("U-frame 0x{:02X} diblok (ACK-only).", c[0]));
             }
             return Ok(());
@@ -228,14 +1842,18 @@ impl TxPolicy {
 
         // I-frame?
         if (c[0] & 0b01) == 0 {
-            if ACK_ONLY {
+            if ack_only {
                 return Err("I-frame OUT diblok (ACK-only mode).".into());
             }
-            // Jika nanti ACK_ONLY dimatikan, tetap lindungi dari 45/46
+            // Jalur kirim terbuka (--enable-tx): tetap lindungi sesuai kebijakan tipe (default anti-45/46/47)
             if apdu.len() >= 7 {
                 let type_id = apdu[6];
-                if FORBIDDEN_TYPE_IDS.contains(&type_id) {
-                    return Err(format!("ASDU type {} diblok (anti-45/46).", type_id));
+                if !types.permits(type_id) {
+                    return Err(format!("ASDU type {} diblok (kebijakan tipe: {}).", type_id, types));
+                }
+                let casdu = apdu.get(10..12).map(|c| u16::from_le_bytes([c[0], c[1]]));
+                if casdu == Some(CASDU_BROADCAST) && !BROADCAST_TYPE_IDS.contains(&type_id) {
+                    return Err(format!("CASDU broadcast 0xFFFF tidak sah untuk type {} (hanya 100/101/103/105).", type_id));
                 }
             } else {
                 return Err("I-frame OUT tanpa ASDU lengkap diblok.".into());
@@ -247,8 +1865,265 @@ impl TxPolicy {
     }
 }
 
+/// S-frame (ACK) dan U-frame con: wajib protokol, tidak boleh tertahan rate limiter.
+fn is_protocol_response(apdu: &[u8]) -> bool {
+    let c0 = apdu[2];
+    let s_frame = (c0 & 0b11) == 0b01;
+    s_frame || matches!(c0, U_STARTDT_CON | U_STOPDT_CON | U_TESTFR_CON)
+}
+
+#[cfg(test)]
+mod tx_tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// Socket loopback tersambung; sisi RTU disimpan agar koneksi tetap hidup.
+    fn link() -> (TcpStream, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let ours = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        (ours, listener.accept().unwrap().0)
+    }
+
+    fn policy(k: u16, limiter: RateLimiter) -> TxPolicy {
+        TxPolicy::new(limiter, k, false, false, TypePolicy::default(), None)
+    }
+
+    fn unlimited() -> RateLimiter { RateLimiter::new(0.0, Duration::ZERO) }
+
+    #[test]
+    fn full_window_queues_and_ack_flushes() {
+        let (mut s, _rtu) = link();
+        let mut h = HandlerChain::default();
+        let mut tx = policy(2, unlimited());
+        for _ in 0..2 { assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Sent); }
+        assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Queued);
+        assert_eq!((tx.outstanding(), tx.pending.len()), (2, 1));
+        // Jendela masih penuh: tidak ada yang keluar
+        assert_eq!(tx.flush_pending(&mut s, &mut h, 0).unwrap(), 0);
+        // RTU meng-ACK N(S)=0: satu slot bebas, antrean keluar sebagai N(S)=2
+        tx.accept_peer_nr(1).unwrap();
+        assert_eq!(tx.flush_pending(&mut s, &mut h, 0).unwrap(), 1);
+        assert_eq!((tx.ns, tx.outstanding(), tx.pending.len()), (3, 2, 0));
+    }
+
+    #[test]
+    fn blocked_type_is_dropped_not_requeued() {
+        let (mut s, _rtu) = link();
+        let mut h = HandlerChain::default();
+        let mut tx = policy(1, unlimited());
+        assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Sent);
+        assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Queued);
+        tx.send_i(&mut s, &mut h, 0, "clock sync".into(), vec![103, 1, 6, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 24]).unwrap();
+        // Muat ulang melarang GI (100) selagi masih di antrean
+        tx.set_out_types(TypePolicy::Forbid(vec![100]));
+        tx.accept_peer_nr(1).unwrap();
+        assert_eq!(tx.flush_pending(&mut s, &mut h, 0).unwrap(), 1);
+        assert!(tx.pending.is_empty());
+        assert_eq!(tx.unacked.back().map(|f| f.apdu[6]), Some(103));
+    }
+
+    #[test]
+    fn rate_limited_frame_stays_at_head() {
+        let (mut s, _rtu) = link();
+        let mut h = HandlerChain::default();
+        let mut tx = policy(1, RateLimiter::new(0.0, Duration::from_secs(3600)));
+        assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Sent);
+        assert_eq!(tx.send_gi(&mut s, &mut h, 0, 1, 20).unwrap(), TxOutcome::Queued);
+        tx.accept_peer_nr(1).unwrap();
+        assert_eq!(tx.flush_pending(&mut s, &mut h, 0).unwrap(), 0);
+        assert_eq!((tx.ns, tx.pending.len()), (1, 1));
+    }
+}
+
+// ================= Rate limiter TX (token bucket) =================
+/// Membatasi frame keluar per detik + jeda minimum antar frame, melindungi RTU dari burst.
+struct RateLimiter {
+    max_fps: f64,      // 0 = tanpa batas
+    min_gap: Duration,
+    tokens: f64,
+    last_refill: Instant,
+    last_sent: Option<Instant>,
+}
+impl RateLimiter {
+    fn new(max_fps: f64, min_gap: Duration) -> Self {
+        // Kapasitas bucket = 1 detik kuota (minimal 1 frame)
+        let cap = max_fps.max(1.0);
+        Self { max_fps, min_gap, tokens: cap, last_refill: Instant::now(), last_sent: None }
+    }
+
+    /// Ok bila frame boleh dikirim sekarang; Err(perkiraan tunggu) bila tidak.
+    fn try_acquire(&mut self, now: Instant) -> Result<(), Duration> {
+        if let Some(prev) = self.last_sent {
+            let gap = now.duration_since(prev);
+            if gap < self.min_gap {
+                return Err(self.min_gap - gap);
+            }
+        }
+        if self.max_fps > 0.0 {
+            let cap = self.max_fps.max(1.0);
+            let dt = now.duration_since(self.last_refill).as_secs_f64();
+            self.tokens = (self.tokens + dt * self.max_fps).min(cap);
+            self.last_refill = now;
+            if self.tokens < 1.0 {
+                return Err(Duration::from_secs_f64((1.0 - self.tokens) / self.max_fps));
+            }
+            self.tokens -= 1.0;
+        }
+        self.last_sent = Some(now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod rate_tests {
+    use super::*;
+
+    fn ms(n: u64) -> Duration { Duration::from_millis(n) }
+
+    /// Limiter dengan jam awal `base`, agar waktu bisa disuntikkan.
+    fn limiter(max_fps: f64, min_gap: Duration, base: Instant) -> RateLimiter {
+        let mut rl = RateLimiter::new(max_fps, min_gap);
+        rl.last_refill = base;
+        rl
+    }
+
+    #[test]
+    fn burst_is_one_second_of_quota() {
+        let base = Instant::now();
+        let mut rl = limiter(5.0, Duration::ZERO, base);
+        for _ in 0..5 { assert_eq!(rl.try_acquire(base), Ok(())); }
+        assert_eq!(rl.try_acquire(base), Err(ms(200)));
+    }
+
+    #[test]
+    fn tokens_refill_at_max_fps_up_to_cap() {
+        let base = Instant::now();
+        let mut rl = limiter(5.0, Duration::ZERO, base);
+        for _ in 0..5 { rl.try_acquire(base).unwrap(); }
+        assert!(rl.try_acquire(base + ms(100)).is_err());
+        assert_eq!(rl.try_acquire(base + ms(200)), Ok(()));
+        assert!(rl.try_acquire(base + ms(200)).is_err());
+        // Sepi lama: bucket penuh lagi tetapi tidak melebihi kapasitas
+        let later = base + Duration::from_secs(60);
+        for _ in 0..5 { assert_eq!(rl.try_acquire(later), Ok(())); }
+        assert!(rl.try_acquire(later).is_err());
+    }
+
+    #[test]
+    fn min_gap_spaces_frames() {
+        let base = Instant::now();
+        let mut rl = limiter(0.0, ms(100), base);
+        assert_eq!(rl.try_acquire(base), Ok(()));
+        assert_eq!(rl.try_acquire(base + ms(40)), Err(ms(60)));
+        assert_eq!(rl.try_acquire(base + ms(100)), Ok(()));
+    }
+
+    #[test]
+    fn below_one_fps_still_allows_one_frame() {
+        let base = Instant::now();
+        let mut rl = limiter(0.5, Duration::ZERO, base);
+        assert_eq!(rl.try_acquire(base), Ok(()));
+        assert_eq!(rl.try_acquire(base + ms(1_000)), Err(ms(1_000)));
+        assert_eq!(rl.try_acquire(base + ms(2_000)), Ok(()));
+    }
+}
+
 fn ioerr(msg: String) -> std::io::Error {
-    std::io::Error::new(std::io::ErrorKind::Other, msg)
+    std::io::Error::other(msg)
+}
+
+// ================= Siklus hidup General Interrogation =================
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum GiPhase { Idle, Activated, Confirmed }
+
+/// Cara GI terakhir berakhir (diambil sekali lewat `take_end`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum GiEnd { Complete, Rejected, TimedOut }
+
+/// Melacak satu GI: act (COT=6) -> act-con (COT=7) -> nilai COT=20..36 -> act-term (COT=10).
+struct GiTracker {
+    phase: GiPhase,
+    casdu: u16,
+    qoi: u8,
+    started: Instant,
+    objects: u64,
+    end: Option<GiEnd>,
+}
+impl GiTracker {
+    fn new() -> Self {
+        Self { phase: GiPhase::Idle, casdu: 0, qoi: 0, started: Instant::now(), objects: 0, end: None }
+    }
+
+    fn is_active(&self) -> bool { self.phase != GiPhase::Idle }
+
+    fn take_end(&mut self) -> Option<GiEnd> { self.end.take() }
+
+    fn start(&mut self, casdu: u16, qoi: u8) {
+        self.phase = GiPhase::Activated;
+        self.casdu = casdu;
+        self.qoi = qoi;
+        self.started = Instant::now();
+        self.objects = 0;
+        self.end = None;
+    }
+
+    /// `qualifier` = QOI/QCC yang dipantulkan RTU di con/term (type 100/101).
+    fn on_asdu(&mut self, a: &AsduSummary, qualifier: Option<u8>) {
+        // CI tidak pernah kita kirim; con/term-nya (mis. dari master lain) cukup dicatat
+        if a.type_id == TYPE_C_CI_NA_1 && matches!(a.cot, COT_ACTCON | COT_ACTTERM) {
+            let qcc = qualifier.map(decode::describe_qcc).unwrap_or_else(|| "-".into());
+            let phase = if a.cot == COT_ACTCON { "act-con" } else { "act-term" };
+            logln!("    CI {} casdu={} QCC={}{}", phase, a.casdu, qcc, if a.negative { " (negatif)" } else { "" });
+            return;
+        }
+        // GI broadcast dijawab dengan CASDU milik RTU, jadi CASDU apa pun diterima
+        let broadcast = self.casdu == CASDU_BROADCAST;
+        if !self.is_active() || (a.casdu != self.casdu && !broadcast) { return; }
+        let via = if broadcast { format!(" (broadcast, dijawab casdu {})", a.casdu) } else { String::new() };
+        if a.type_id == TYPE_C_IC_NA_1 {
+            // QOI gema berbeda = RTU menjalankan interrogasi lain dari yang kita minta
+            let echo = qualifier.map(decode::describe_qoi).unwrap_or_else(|| "-".into());
+            if matches!(a.cot, COT_ACTCON | COT_ACTTERM) && qualifier != Some(self.qoi) {
+                logln!(
+                    "(Peringatan) QOI gema {} tidak cocok dengan yang diminta {} (casdu {}).",
+                    echo, decode::describe_qoi(self.qoi), self.casdu
+                );
+            }
+            match a.cot {
+                COT_ACTCON if a.negative => {
+                    logln!("    GI ditolak RTU (act-con negatif) untuk casdu {}{}, QOI gema {}.", self.casdu, via, echo);
+                    self.phase = GiPhase::Idle;
+                    self.end = Some(GiEnd::Rejected);
+                }
+                COT_ACTCON => {
+                    logln!(
+                        "    GI dikonfirmasi (act-con) casdu={} qoi={} (gema QOI={}){}, RTT act->con {:.1} ms",
+                        self.casdu, self.qoi, echo, via, self.started.elapsed().as_secs_f64() * 1000.0
+                    );
+                    self.phase = GiPhase::Confirmed;
+                }
+                COT_ACTTERM => {
+                    logln!("    GI complete for casdu {}{} (gema QOI={}), {} objects received.", self.casdu, via, echo, self.objects);
+                    self.phase = GiPhase::Idle;
+                    self.end = Some(GiEnd::Complete);
+                }
+                _ => {}
+            }
+        } else if (COT_INROGEN..=COT_INRO16).contains(&a.cot) {
+            self.objects += a.num_objects as u64;
+        }
+    }
+
+    fn check_timeout(&mut self) {
+        if self.is_active() && self.started.elapsed() > GI_TIMEOUT {
+            logln!(
+                "(Peringatan) GI casdu={} tidak selesai dalam {:?} (fase {:?}, {} objek diterima).",
+                self.casdu, GI_TIMEOUT, self.phase, self.objects
+            );
+            self.phase = GiPhase::Idle;
+            self.end = Some(GiEnd::TimedOut);
+        }
+    }
 }
 
 // ================= Parser & util =================
@@ -256,12 +2131,9 @@ fn ioerr(msg: String) -> std::io::Error {
 /// Mengambil satu APDU utuh dari buffer bila tersedia.
 /// Format: 0x68, LEN, lalu LEN byte berikutnya (APCI[4] + ASDU[Len-4])
 fn take_one_apdu(buf: &[u8]) -> Option<(&[u8], usize)> {
-    if buf.len() < 2 { return None; }
-    // Resinkronisasi: cari start 0x68
-    let mut start = 0usize;
-    while start < buf.len() && buf[start] != 0x68 { start += 1; }
-    if start >= buf.len() - 1 { return None; } // tidak cukup untuk baca LEN
-    let len = buf[start + 1] as usize;
+    // Resinkronisasi: cari start 0x68 dengan LEN sah; byte sebelumnya ikut terbuang
+    let start = (0..buf.len()).find(|&i| is_apdu_start(buf, i))?;
+    let len = *buf.get(start + 1)? as usize; // tidak cukup untuk baca LEN
     let total = 2 + len;
     if buf.len() < start + total { return None; } // belum utuh
     let apdu = &buf[start..start + total];
@@ -296,24 +2168,70 @@ impl std::fmt::Display for UType {
 struct AsduSummary {
     type_id: u8,
     vsq: u8,
+    num_objects: u8,   // VSQ bit 0..6
+    is_sequence: bool, // VSQ.SQ (bit 7): satu IOA lalu elemen berurutan
     cot: u8,
+    negative: bool, // bit P/N pada byte COT
+    oa: u8,         // originator address: oktet kedua COT
     casdu: u16,
     ioa_first: u32, // jika VSQ.SQ=1 maka ini IOA pertama
+    malformed: bool, // lebih pendek dari header + satu objek untuk type id ini
+    /// Dekode penuh asal ringkasan ini: read loop & --pretty memakainya, tidak mendekode ulang.
+    full: decode::DecodedAsdu,
 }
 
+/// Hasil klasifikasi APCI. `control` = 4 oktet kontrol mentah yang menghasilkan klasifikasi
+/// ini, agar salah dekode terlihat di log ("control=01 00 0A 00 → S-Frame N(R)=5").
 #[derive(Debug)]
 enum Frame {
-    U(UType),
-    S { nr: u16 },
-    I { ns: u16, nr: u16, asdu: Option<AsduSummary> },
+    U { ut: UType, control: [u8; 4] },
+    S { nr: u16, control: [u8; 4] },
+    I { ns: u16, nr: u16, asdu: Option<AsduSummary>, control: [u8; 4] },
+    /// Bukan APCI yang sah (awal bukan 0x68 / LEN < 4): tidak ada medan kontrol yang bisa dipercaya.
     Unknown,
 }
 
+impl Frame {
+    fn control(&self) -> Option<[u8; 4]> {
+        match self {
+            Frame::U { control, .. } | Frame::S { control, .. } | Frame::I { control, .. } => Some(*control),
+            Frame::Unknown => None,
+        }
+    }
+}
+
+/// Ringkasan satu baris sebuah frame (dipakai REPL & log).
+fn describe_frame(f: &Frame) -> String {
+    let what = match f {
+        Frame::U { ut, .. } => format!("U-Frame ({})", ut),
+        Frame::S { nr, .. } => format!("S-Frame N(R)={}", nr),
+        Frame::I { ns, nr, asdu: Some(a), .. } => format!(
+            "I-Frame N(S)={} N(R)={} | type_id={}{} vsq=0x{:02X} N={} SQ={} cot={}{}{} casdu={} ioa_first={}",
+            ns, nr, a.type_id,
+            asdu_type_name(a.type_id).map(|n| format!(" ({})", n)).unwrap_or_default(),
+            a.vsq, a.num_objects, a.is_sequence as u8, a.cot,
+            cot_name(a.cot).map(|n| format!(" ({})", n)).unwrap_or_default(),
+            if a.negative { " NEG" } else { "" },
+            a.casdu, a.ioa_first
+        ) + if a.malformed { " (malformed)" } else { "" },
+        Frame::I { ns, nr, asdu: None, .. } => format!("I-Frame N(S)={} N(R)={} | ASDU tidak utuh", ns, nr),
+        Frame::Unknown => return "(tidak dikenali)".into(),
+    };
+    format!("control={} → {}", f.control().map(|c| hex(&c)).unwrap_or_default(), what)
+}
+
+/// Klasifikasi dengan medan alamat profil 104 (mock, REPL, dry-run, bench, vektor uji).
 fn classify_apdu(apdu: &[u8]) -> Frame {
+    classify_apdu_as(apdu, AsduLayout::IEC104)
+}
+
+/// Klasifikasi frame RX dengan tata letak ASDU situs (`[asdu]`).
+fn classify_apdu_as(apdu: &[u8], layout: AsduLayout) -> Frame {
     if apdu.len() < 6 || apdu[0] != 0x68 { return Frame::Unknown; }
     let len = apdu[1] as usize;
     if len < 4 { return Frame::Unknown; }
-    let c = &apdu[2..6];
+    let control = [apdu[2], apdu[3], apdu[4], apdu[5]];
+    let c = &control;
 
     // U-frame: bit0=1, bit1=1 pada byte kontrol 1
     if (c[0] & 0b11) == 0b11 {
@@ -326,13 +2244,13 @@ fn classify_apdu(apdu: &[u8]) -> Frame {
             U_TESTFR_CON  => UType::TestFrCon,
             other         => UType::Other(other),
         };
-        return Frame::U(ut);
+        return Frame::U { ut, control };
     }
 
     // S-frame: bit0=1, bit1=0
     if (c[0] & 0b01) == 0b01 && (c[0] & 0b10) == 0 {
         let nr = (((c[3] as u16) << 8) | (c[2] as u16)) >> 1;
-        return Frame::S { nr };
+        return Frame::S { nr, control };
     }
 
     // I-frame: bit0=0
@@ -343,45 +2261,316 @@ fn classify_apdu(apdu: &[u8]) -> Frame {
         // Coba ringkas ASDU (jika ada)
         let asdu_off = 6usize;
         if apdu.len() > asdu_off {
-            let asdu = parse_asdu(&apdu[asdu_off..]);
-            return Frame::I { ns, nr, asdu };
+            let asdu = parse_asdu(&apdu[asdu_off..], layout);
+            return Frame::I { ns, nr, asdu, control };
         } else {
-            return Frame::I { ns, nr, asdu: None };
+            return Frame::I { ns, nr, asdu: None, control };
         }
     }
 
     Frame::Unknown
 }
 
-fn parse_asdu(asdu: &[u8]) -> Option<AsduSummary> {
-    // Struktur minimum: 6 byte header ASDU + IOA (opsional)
-    if asdu.len() < 6 { return None; }
-    let type_id = asdu[0];
-    let vsq = asdu[1];
-    let cot = asdu[2] & 0x3F; // test/neg bit di atasnya
-    let casdu = asdu.get(4).copied().unwrap_or(0) as u16
-        | ((asdu.get(5).copied().unwrap_or(0) as u16) << 8);
-
-    // IOA (3 byte) — hanya ambil IOA pertama bila tersedia
-    let ioa_first = if asdu.len() >= 9 {
-        (asdu[6] as u32) | ((asdu[7] as u32) << 8) | ((asdu[8] as u32) << 16)
-    } else {
-        0
-    };
-
-    Some(AsduSummary { type_id, vsq, cot, casdu, ioa_first })
+/// Ringkasan header untuk baris log per frame: pembungkus tipis `decode::parse_asdu_full`.
+/// None = lebih pendek dari header ASDU.
+fn parse_asdu(asdu: &[u8], layout: AsduLayout) -> Option<AsduSummary> {
+    let d = decode::parse_asdu_full(asdu, layout).ok()?;
+    Some(AsduSummary {
+        type_id: d.type_id,
+        vsq: asdu[1],
+        num_objects: d.num_objects,
+        is_sequence: d.sq,
+        cot: d.cot,
+        negative: d.negative,
+        oa: d.oa,
+        casdu: d.casdu,
+        ioa_first: d.first_ioa.unwrap_or(0),
+        // Type dikenal tapi bahkan satu objek tidak utuh => ringkasan hanya informatif
+        malformed: decode::element_size(d.type_id).is_some_and(|size| asdu.len() < layout.header_len() + layout.ioa + size),
+        full: d,
+    })
 }
 
 fn build_s_ack(nr: u16) -> [u8; 6] {
     // 0x68, 0x04, 0x01, 0x00, (2*NR LSB), (2*NR MSB)
-    let v = (nr << 1) as u16;
+    // N(R) 15 bit: tanpa mask, bit 15 akan hilang dan nilainya terpotong diam-diam
+    debug_assert!(nr < SEQ_MOD, "N(R) di luar 15 bit: {}", nr);
+    let v = (nr & (SEQ_MOD - 1)) << 1;
     [0x68, 0x04, 0x01, 0x00, (v & 0xFF) as u8, (v >> 8) as u8]
 }
 
+#[cfg(test)]
+mod apci_tests {
+    use super::*;
+
+    /// Frame tidak PartialEq (ringkasan ASDU membawa dekode penuh): bandingkan medan S-frame.
+    fn s_frame(apdu: &[u8]) -> Option<(u16, [u8; 4])> {
+        match classify_apdu(apdu) {
+            Frame::S { nr, control } => Some((nr, control)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn s_ack_round_trip_all_15_bit() {
+        for nr in 0..SEQ_MOD {
+            let apdu = build_s_ack(nr);
+            assert_eq!(s_frame(&apdu), Some((nr, [apdu[2], apdu[3], apdu[4], apdu[5]])), "N(R)={}", nr);
+        }
+    }
+
+    #[test]
+    fn s_ack_wraps_32767_to_0() {
+        let last = build_s_ack(SEQ_MOD - 1);
+        assert_eq!(last[4..], [0xFE, 0xFF]);
+        let next = build_s_ack(seq_inc(SEQ_MOD - 1));
+        assert_eq!(next[4..], [0x00, 0x00]);
+        assert_eq!(s_frame(&next).map(|(nr, _)| nr), Some(0));
+    }
+
+    #[test]
+    fn len_beyond_buffer_waits_for_more_bytes() {
+        let apdu = build_i_frame(0, 0, &[0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x01]);
+        for len in 0..apdu.len() {
+            assert_eq!(take_one_apdu(&apdu[..len]), None, "{} dari {} byte", len, apdu.len());
+        }
+        assert_eq!(take_one_apdu(&apdu), Some((&apdu[..], apdu.len())));
+    }
+
+    #[test]
+    fn short_or_invalid_apci_is_unknown() {
+        for apdu in [&[][..], &[0x68], &[0x68, 0x04, 0x01], &[0x68, 0x04, 0x01, 0x00, 0x00], &[0x68, 0x02, 0x01, 0x00, 0x00, 0x00], &[0x67, 0x04, 0x01, 0x00, 0x00, 0x00]] {
+            assert!(matches!(classify_apdu(apdu), Frame::Unknown), "{}", hex(apdu));
+        }
+    }
+
+    #[test]
+    fn i_frame_with_short_asdu_has_no_summary() {
+        let asdu = [0x01, 0x01, 0x03, 0x00, 0x01, 0x00];
+        for len in 0..asdu.len() {
+            let apdu = build_i_frame(3, 7, &asdu[..len]);
+            assert!(matches!(classify_apdu(&apdu), Frame::I { ns: 3, nr: 7, asdu: None, .. }), "ASDU {} byte", len);
+        }
+    }
+
+    #[test]
+    fn i_frame_cut_mid_ioa_is_flagged_malformed() {
+        let apdu = build_i_frame(0, 0, &[0x01, 0x01, 0x03, 0x00, 0x01, 0x00, 0xE9, 0x03]);
+        let Frame::I { asdu: Some(a), .. } = classify_apdu(&apdu) else { panic!("bukan I-frame berringkasan") };
+        assert!(a.malformed);
+        assert!(matches!(a.full.status, AsduStatus::Malformed(_)));
+        assert!(a.full.objects.is_empty());
+    }
+}
+
+/// ASDU terpanjang yang muat di APDU: LEN (1 oktet) maks 253 = 4 oktet kontrol + ASDU.
+const MAX_ASDU_LEN: usize = 249;
+
+/// APCI I-frame: 0x68, LEN, N(S)<<1 (LE), N(R)<<1 (LE), lalu ASDU. Pasangan encode dari
+/// cabang I-frame `classify_apdu`; dasar semua pembentuk perintah (GI, transfer berkas, mock).
+/// ASDU > MAX_ASDU_LEN menghasilkan LEN salah dan ditolak gatekeeper.
+fn build_i_frame(ns: u16, nr: u16, asdu: &[u8]) -> Vec<u8> {
+    debug_assert!(ns < SEQ_MOD && nr < SEQ_MOD, "N(S)/N(R) di luar 15 bit: {}/{}", ns, nr);
+    debug_assert!(asdu.len() <= MAX_ASDU_LEN, "ASDU {} byte > {}", asdu.len(), MAX_ASDU_LEN);
+    let s = (ns & (SEQ_MOD - 1)) << 1;
+    let r = (nr & (SEQ_MOD - 1)) << 1;
+    let mut f = Vec::with_capacity(6 + asdu.len());
+    f.extend_from_slice(&[0x68, (4 + asdu.len()) as u8, (s & 0xFF) as u8, (s >> 8) as u8, (r & 0xFF) as u8, (r >> 8) as u8]);
+    f.extend_from_slice(asdu);
+    f
+}
+
+/// ASDU C_IC_NA_1 act: type 100, VSQ=1, COT=6, OA=0, CASDU, IOA=0, QOI
+fn build_gi_asdu(casdu: u16, qoi: u8) -> [u8; 10] {
+    [
+        TYPE_C_IC_NA_1, 0x01, COT_ACT, 0x00,
+        (casdu & 0xFF) as u8, (casdu >> 8) as u8,
+        0x00, 0x00, 0x00,
+        qoi,
+    ]
+}
+
+/// ASDU C_SC_NA_1 act: type 45, VSQ=1, COT=6, OA=0, CASDU, IOA, SCO (S/E | QU | SCS)
+fn build_sc_asdu(casdu: u16, ioa: u32, on: bool, select: bool, qu: Qu) -> [u8; 10] {
+    [
+        TYPE_C_SC_NA_1, 0x01, COT_ACT, 0x00,
+        (casdu & 0xFF) as u8, (casdu >> 8) as u8,
+        (ioa & 0xFF) as u8, (ioa >> 8) as u8, (ioa >> 16) as u8,
+        (select as u8) << 7 | qu.bits() << 2 | on as u8,
+    ]
+}
+
+/// ASDU C_RC_NA_1 act: type 47, VSQ=1, COT=6, OA=0, CASDU, IOA, RCO (S/E | QU | RCS).
+/// Tap changer: jalankan select dulu, execute setelah act-con select positif.
+fn build_rc_asdu(casdu: u16, ioa: u32, direction: StepDirection, select: bool, qu: Qu) -> [u8; 10] {
+    [
+        TYPE_C_RC_NA_1, 0x01, COT_ACT, 0x00,
+        (casdu & 0xFF) as u8, (casdu >> 8) as u8,
+        (ioa & 0xFF) as u8, (ioa >> 8) as u8, (ioa >> 16) as u8,
+        (select as u8) << 7 | qu.bits() << 2 | direction.bits(),
+    ]
+}
+
+/// ASDU C_BO_NA_1 act: type 51, VSQ=1, COT=6, OA=0, CASDU, IOA, BSI 32 bit (little endian)
+fn build_bo_asdu(casdu: u16, ioa: u32, value: u32) -> [u8; 13] {
+    let v = value.to_le_bytes();
+    [
+        TYPE_C_BO_NA_1, 0x01, COT_ACT, 0x00,
+        (casdu & 0xFF) as u8, (casdu >> 8) as u8,
+        (ioa & 0xFF) as u8, (ioa >> 8) as u8, (ioa >> 16) as u8,
+        v[0], v[1], v[2], v[3],
+    ]
+}
+
 fn hex(data: &[u8]) -> String {
     data.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ")
 }
 
+/// 0x68 di `i` diikuti LEN sah (4..=253: APCI saja s/d APDU 255 byte), atau LEN belum tiba.
+/// 0x68 dengan LEN mustahil adalah sampah, bukan awal frame.
+fn is_apdu_start(buf: &[u8], i: usize) -> bool {
+    buf[i] == 0x68 && buf.get(i + 1).is_none_or(|len| (4..=253).contains(len))
+}
+
+/// Posisi awal APDU sah pertama setelah awal buffer; panjang buffer bila tidak ada.
+fn resync_point(buf: &[u8]) -> usize {
+    (1..buf.len()).find(|&i| is_apdu_start(buf, i)).unwrap_or(buf.len())
+}
+
+/// Hexdump klasik: offset, 16 byte hex (dibelah 8+8), gutter ASCII. Satu baris per 16 byte.
+fn hexdump(data: &[u8]) -> String {
+    data.chunks(16)
+        .enumerate()
+        .map(|(i, row)| {
+            let cols: Vec<String> = (0..16)
+                .map(|j| row.get(j).map(|b| format!("{:02X}", b)).unwrap_or_else(|| "  ".into()))
+                .collect();
+            let ascii: String = row.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+            format!("{:04X}  {}  {}  |{}|", i * 16, cols[..8].join(" "), cols[8..].join(" "), ascii)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Byte APDU sesuai pilihan tampilan (--hexdump / --annotate-hex / datar), diawali pemisah
+/// (spasi, atau baris baru untuk hexdump multi-baris).
+fn render_apdu(apdu: &[u8], cfg: &Config) -> String {
+    if cfg.hexdump {
+        format!("\n{}", hexdump(apdu))
+    } else if cfg.annotate_hex {
+        format!(" {}", annotate_hex(apdu, cfg.asdu.layout()))
+    } else {
+        format!(" {}", hex(apdu))
+    }
+}
+
+/// Satu frame sebagai pohon bertingkat untuk `--pretty`: APCI, header ASDU, lalu satu baris per objek.
+fn pretty_frame(apdu: &[u8], cfg: &Config) -> String {
+    let mut out = format!("< RX {} bytes:{}", apdu.len(), render_apdu(apdu, cfg));
+    let len = apdu.get(1).copied().unwrap_or(0);
+    match classify_apdu_as(apdu, cfg.asdu.layout()) {
+        Frame::U { ut, control } => out += &format!("\n  U-Frame\n  └─ APCI: start=0x68 len={} control={} fungsi={}", len, hex(&control), ut),
+        Frame::S { nr, control } => out += &format!("\n  S-Frame\n  └─ APCI: start=0x68 len={} control={} N(R)={}", len, hex(&control), nr),
+        Frame::Unknown => out += "\n  (tidak dikenali)",
+        Frame::I { ns, nr, control, asdu } => {
+            out += &format!("\n  I-Frame\n  ├─ APCI: start=0x68 len={} control={} N(S)={} N(R)={}", len, hex(&control), ns, nr);
+            let Some(d) = asdu.map(|a| a.full) else {
+                out += "\n  └─ ASDU: (tidak utuh/pendek)";
+                return out;
+            };
+            out += &format!(
+                "\n  ├─ ASDU: type_id={}{} SQ={} n={} cot={}{} P/N={} T={} oa={} casdu={}{}",
+                d.type_id,
+                asdu_type_name(d.type_id).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                d.sq as u8, d.num_objects, d.cot,
+                cot_name(d.cot).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                d.negative as u8, d.test as u8, d.oa, d.casdu,
+                cfg.names.station(d.casdu).map(|n| format!(" ({})", n)).unwrap_or_default()
+            );
+            match &d.status {
+                AsduStatus::Ok => {}
+                AsduStatus::Truncated { expected, actual } => out += &format!("\n  ├─ Status: terpotong (butuh {} byte, ada {})", expected, actual),
+                AsduStatus::Oversized { expected, actual } => out += &format!("\n  ├─ Status: kelebihan (butuh {} byte, ada {})", expected, actual),
+                AsduStatus::Malformed(why) => out += &format!("\n  ├─ Status: malformed ({})", why),
+            }
+            for why in &d.skipped {
+                out += &format!("\n  ├─ Dilewati: {}", why);
+            }
+            out += &format!("\n  └─ Objek ({})", d.objects.len());
+            for (i, obj) in d.objects.iter().enumerate() {
+                let branch = if i + 1 == d.objects.len() { "└─" } else { "├─" };
+                out += &format!(
+                    "\n     {} IOA={}{} val={} q={}{}",
+                    branch, obj.ioa,
+                    cfg.names.point(d.casdu, obj.ioa).map(|n| format!(" ({})", n)).unwrap_or_default(),
+                    obj.value,
+                    obj.quality.map(|q| q.to_string()).unwrap_or_else(|| "-".into()),
+                    obj.time.map(|t| format!(" t={} utc={}", t, decode::format_epoch_ms(t.utc_epoch_ms(&cfg.rtu_clock))))
+                        .or_else(|| obj.bad_time.as_ref().map(|why| format!(" t=tidak valid ({})", why)))
+                        .unwrap_or_default()
+                );
+            }
+        }
+    }
+    out
+}
+
+/// Hex dengan batas medan APCI/ASDU, untuk `--annotate-hex`:
+/// `68 | 0E | [I ns=3 nr=5] 06 00 0A 00 | T=09 VSQ=01 COT=03 OA=00 CA=0E00 | IOA=...`
+/// Medan COT/CASDU/IOA mengikuti `layout`; COT satu oktet tidak punya OA.
+fn annotate_hex(apdu: &[u8], layout: AsduLayout) -> String {
+    if apdu.len() < 6 { return hex(apdu); }
+    let ctrl = match classify_apdu_as(apdu, layout) {
+        Frame::U { ut, .. } => format!("[U {}]", ut),
+        Frame::S { nr, .. } => format!("[S nr={}]", nr),
+        Frame::I { ns, nr, .. } => format!("[I ns={} nr={}]", ns, nr),
+        Frame::Unknown => "[?]".into(),
+    };
+    let mut parts = vec![format!("{:02X}", apdu[0]), format!("{:02X}", apdu[1]), format!("{} {}", ctrl, hex(&apdu[2..6]))];
+    let asdu = &apdu[6..];
+    let header = layout.header_len();
+    if asdu.len() < header {
+        if !asdu.is_empty() { parts.push(format!("sisa={}", hex(asdu))); }
+        return parts.join(" | ");
+    }
+    let hex_run = |b: &[u8]| b.iter().map(|x| format!("{:02X}", x)).collect::<String>();
+    let ca = &asdu[2 + layout.cot..header];
+    parts.push(if layout.cot == 2 {
+        format!("T={:02X} VSQ={:02X} COT={:02X} OA={:02X} CA={}", asdu[0], asdu[1], asdu[2], asdu[3], hex_run(ca))
+    } else {
+        format!("T={:02X} VSQ={:02X} COT={:02X} CA={}", asdu[0], asdu[1], asdu[2], hex_run(ca))
+    });
+
+    // Objek: IOA (layout.ioa oktet) + elemen; SQ=1 => satu IOA lalu elemen berurutan
+    let type_id = asdu[0];
+    let sq = asdu[1] & 0x80 != 0;
+    let count = (asdu[1] & 0x7F) as usize;
+    let mut rest = &asdu[header..];
+    let Some(size) = decode::element_size(type_id) else {
+        if !rest.is_empty() { parts.push(format!("objek={}", hex(rest))); }
+        return parts.join(" | ");
+    };
+    let elem = |e: &[u8]| {
+        let split = if decode::has_time_tag(type_id) { size - 7 } else { size };
+        if split == size { format!("EL={}", hex(e)) } else { format!("EL={} TM={}", hex(&e[..split]), hex(&e[split..])) }
+    };
+    for i in 0..count {
+        let with_ioa = !sq || i == 0;
+        let need = size + if with_ioa { layout.ioa } else { 0 };
+        if rest.len() < need { break; }
+        let (obj, tail) = rest.split_at(need);
+        parts.push(if with_ioa {
+            let ioa = obj[..layout.ioa].iter().rev().fold(0u32, |acc, b| acc << 8 | *b as u32);
+            format!("IOA={} ({}) {}", hex(&obj[..layout.ioa]), ioa, elem(&obj[layout.ioa..]))
+        } else {
+            elem(obj)
+        });
+        rest = tail;
+    }
+    if !rest.is_empty() { parts.push(format!("sisa={}", hex(rest))); }
+    parts.join(" | ")
+}
+
 // ====== Util sequence (15-bit) ======
 const SEQ_MOD: u16 = 1 << 15; // 32768
 
@@ -396,24 +2585,164 @@ fn seq_distance(a: u16, b: u16) -> u16 {
     ((a as i32 - b as i32 + SEQ_MOD as i32) % SEQ_MOD as i32) as u16
 }
 
+/// `candidate` ada di jendela setengah terbuka [base, base + len) modulo 32768.
+/// Satu-satunya tempat perbandingan "di dalam jendela" 15 bit; len 0 = jendela kosong.
+#[inline]
+fn seq_in_window(candidate: u16, base: u16, len: u16) -> bool {
+    seq_distance(candidate, base) < len
+}
+
 fn asdu_type_name(type_id: u8) -> Option<&'static str> {
     match type_id {
         1  => Some("M_SP_NA_1"),
         3  => Some("M_DP_NA_1"),
+        5  => Some("M_ST_NA_1"),
+        7  => Some("M_BO_NA_1"),
         9  => Some("M_ME_NA_1"),
         11 => Some("M_ME_NB_1"),
         13 => Some("M_ME_NC_1"),
         15 => Some("M_IT_NA_1"),
+        20 => Some("M_PS_NA_1"),
+        21 => Some("M_ME_ND_1"),
         30 => Some("M_SP_TB_1"),
         31 => Some("M_DP_TB_1"),
+        32 => Some("M_ST_TB_1"),
+        33 => Some("M_BO_TB_1"),
         34 => Some("M_ME_TD_1"),
         35 => Some("M_ME_TE_1"),
         36 => Some("M_ME_TF_1"),
         37 => Some("M_IT_TB_1"),
+        38 => Some("M_EP_TD_1"),
+        39 => Some("M_EP_TE_1"),
+        40 => Some("M_EP_TF_1"),
         45 => Some("C_SC_NA_1"),
         46 => Some("C_DC_NA_1"),
         47 => Some("C_RC_NA_1"),
+        48 => Some("C_SE_NA_1"),
+        49 => Some("C_SE_NB_1"),
+        50 => Some("C_SE_NC_1"),
+        51 => Some("C_BO_NA_1"),
+        58 => Some("C_SC_TA_1"),
+        59 => Some("C_DC_TA_1"),
+        60 => Some("C_RC_TA_1"),
+        61 => Some("C_SE_TA_1"),
+        62 => Some("C_SE_TB_1"),
+        63 => Some("C_SE_TC_1"),
+        64 => Some("C_BO_TA_1"),
+        70 => Some("M_EI_NA_1"),
         100 => Some("C_IC_NA_1"),
+        101 => Some("C_CI_NA_1"),
+        102 => Some("C_RD_NA_1"),
+        103 => Some("C_CS_NA_1"),
+        104 => Some("C_TS_NA_1"),
+        105 => Some("C_RP_NA_1"),
+        106 => Some("C_CD_NA_1"),
+        107 => Some("C_TS_TA_1"),
+        110 => Some("P_ME_NA_1"),
+        111 => Some("P_ME_NB_1"),
+        112 => Some("P_ME_NC_1"),
+        113 => Some("P_AC_NA_1"),
+        120 => Some("F_FR_NA_1"),
+        121 => Some("F_SR_NA_1"),
+        122 => Some("F_SC_NA_1"),
+        123 => Some("F_LS_NA_1"),
+        124 => Some("F_AF_NA_1"),
+        125 => Some("F_SG_NA_1"),
+        126 => Some("F_DR_TA_1"),
+        127 => Some("F_SC_NB_1"),
         _ => None,
     }
 }
+
+/// Tipe tanpa nama standar, atau bernama tetapi tanpa decoder objek. Transfer berkas
+/// (120..127) ditangani `filetx`, bukan decoder objek.
+fn is_unknown_type(type_id: u8) -> bool {
+    asdu_type_name(type_id).is_none() || (decode::element_size(type_id).is_none() && !(120..=127).contains(&type_id))
+}
+
+/// COT yang menjawab perintah seorang master (con/term, berkas, interrogasi, read, negatif
+/// "unknown ..."): OA-nya menunjuk master peminta. Spontan/periodik tidak bertuan.
+fn is_reply_cot(cot: u8) -> bool {
+    matches!(cot, 5 | 7 | 9 | 10 | 13 | 20..=41 | 44..=47)
+}
+
+fn cot_name(cot: u8) -> Option<&'static str> {
+    match cot {
+        1  => Some("per/cyc"),
+        2  => Some("back"),
+        3  => Some("spont"),
+        4  => Some("init"),
+        5  => Some("req"),
+        6  => Some("act"),
+        7  => Some("actcon"),
+        8  => Some("deact"),
+        9  => Some("deactcon"),
+        10 => Some("actterm"),
+        11 => Some("retrem"),
+        12 => Some("retloc"),
+        13 => Some("file"),
+        20 => Some("inrogen"),
+        21..=36 => Some("inro(grp)"),
+        37 => Some("reqcogen"),
+        38..=41 => Some("reqco(grp)"),
+        44 => Some("unknown type"),
+        45 => Some("unknown cause"),
+        46 => Some("unknown casdu"),
+        47 => Some("unknown ioa"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod session_tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::net::TcpListener;
+    use std::rc::Rc;
+
+    use crate::decode::ObjValue;
+
+    /// Rekam N(R) tiap S-ACK yang benar-benar ditulis ke socket.
+    struct SAckRecorder(Rc<RefCell<Vec<u16>>>);
+
+    impl FrameHandler for SAckRecorder {
+        fn on_point(&mut self, _point: &DecodedPoint) {}
+        fn on_raw_tx(&mut self, apdu: &[u8]) {
+            if let Frame::S { nr, .. } = classify_apdu(apdu) { self.0.borrow_mut().push(nr); }
+        }
+    }
+
+    #[test]
+    fn snapshot_against_mock_acks_every_w_and_decodes_gi() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        std::thread::spawn(move || mock::serve_on(listener));
+
+        let mut cfg = Config::default();
+        cfg.rtu_addr = addr.to_string();
+        cfg.snapshot = true;
+        cfg.enable_tx = true;
+        cfg.read_timeout = Duration::from_secs(2);
+        cfg.snapshot_timeout = Duration::from_secs(5);
+        cfg.ack.w = 2;
+        let sacks = Rc::new(RefCell::new(Vec::new()));
+        let mut handler = HandlerChain::default();
+        handler.push(Box::new(SAckRecorder(sacks.clone())));
+        let mut col = Collector { cfg, handler,
+            cache: PointCache::new(cache::Deadband::None, Default::default()),
+            ack_stats: AckStats::default(), link: LinkStats::new(), history: FrameHistory::new(0, false),
+            health: None, events: None, files: None, seq_error_reconnects: 0, conn: ConnStats::new(),
+            snapshot: BTreeMap::new(), follow: None,
+        };
+
+        let end = run_session(&mut col).unwrap();
+        assert!(matches!(end, Disconnect::SnapshotDone), "{}", end);
+        // GI: act-con, M_SP_NA_1, M_ME_NC_1, act-term = 4 I-frame; w=2 => S-ACK di N(R) 2 lalu 4
+        assert_eq!(*sacks.borrow(), [2, 4]);
+        let value = |ioa| col.snapshot.get(&(1, ioa)).map(|p| p.obj.value);
+        assert_eq!(value(100), Some(ObjValue::SinglePoint(true)));
+        assert_eq!(value(101), Some(ObjValue::SinglePoint(false)));
+        assert_eq!(value(2000), Some(ObjValue::Float(230.5)));
+        assert_eq!(col.snapshot.len(), 3);
+    }
+}
@@ -0,0 +1,180 @@
+//! RTU tiruan (`--serve <addr>`) untuk uji ujung-ke-ujung tanpa perangkat keras:
+//! balas STARTDT/TESTFR, kirim urutan I-frame terskrip, jawab GI, dan ACK per w/t2.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::AckPolicy;
+use crate::decode::Cp56Time2a;
+use crate::{
+    build_i_frame, build_s_ack, classify_apdu, hex, seq_inc, take_one_apdu, Frame, UType,
+    COT_ACTCON, COT_ACTTERM, COT_INROGEN, TYPE_C_IC_NA_1, U_STARTDT_CON, U_STOPDT_CON, U_TESTFR_CON,
+};
+
+const MOCK_CASDU: u16 = 1;
+const SCRIPT_PERIOD: Duration = Duration::from_millis(500);
+
+/// Layani koneksi satu per satu sampai proses dihentikan.
+pub fn serve(addr: &str) -> std::io::Result<()> {
+    serve_on(TcpListener::bind(addr)?)
+}
+
+/// Seperti `serve`, pada listener yang sudah terikat (uji: port 0 = port bebas).
+pub fn serve_on(listener: TcpListener) -> std::io::Result<()> {
+    logln!("[mock] RTU tiruan mendengarkan di {}", listener.local_addr()?);
+    for conn in listener.incoming() {
+        let stream = conn?;
+        logln!("[mock] koneksi dari {}", stream.peer_addr()?);
+        if let Err(e) = MockSession::new(stream).run() {
+            logln!("[mock] sesi berakhir: {}", e);
+        }
+    }
+    Ok(())
+}
+
+struct MockSession {
+    stream: TcpStream,
+    ns: u16,           // N(S) I-frame kita berikutnya
+    rx_nr: u16,        // N(R) = I-frame master berikutnya yang diharapkan
+    unacked_rx: usize, // I-frame master belum di-ACK (w)
+    ack: AckPolicy,    // default: w/t2 Siemens
+    t2_started: Option<Instant>,
+    started: bool,     // STARTDT con sudah dikirim
+    step: u32,
+}
+
+impl MockSession {
+    fn new(stream: TcpStream) -> Self {
+        Self { stream, ns: 0, rx_nr: 0, unacked_rx: 0, ack: AckPolicy::default(), t2_started: None, started: false, step: 0 }
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        self.stream.set_read_timeout(Some(Duration::from_millis(100)))?;
+        let mut buf: Vec<u8> = Vec::new();
+        let mut tmp = [0u8; 1024];
+        let mut last_script = Instant::now();
+        loop {
+            match self.stream.read(&mut tmp) {
+                Ok(0) => return Ok(()),
+                Ok(n) => buf.extend_from_slice(&tmp[..n]),
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+                Err(e) => return Err(e),
+            }
+            while let Some((apdu, consumed)) = take_one_apdu(&buf) {
+                let apdu = apdu.to_vec();
+                buf.drain(0..consumed);
+                self.on_frame(&apdu)?;
+            }
+            if self.t2_started.is_some_and(|t| t.elapsed() >= self.ack.t2) {
+                self.ack()?;
+            }
+            if self.started && last_script.elapsed() >= SCRIPT_PERIOD {
+                self.script_step()?;
+                last_script = Instant::now();
+            }
+        }
+    }
+
+    fn send(&mut self, apdu: &[u8], what: &str) -> std::io::Result<()> {
+        logln!("[mock] > {}: {}", what, hex(apdu));
+        self.stream.write_all(apdu)
+    }
+
+    fn send_asdu(&mut self, asdu: &[u8], what: &str) -> std::io::Result<()> {
+        let f = build_i_frame(self.ns, self.rx_nr, asdu);
+        self.ns = seq_inc(self.ns);
+        // I-frame kita membawa N(R) => sekaligus ACK
+        self.unacked_rx = 0;
+        self.t2_started = None;
+        self.send(&f, what)
+    }
+
+    fn ack(&mut self) -> std::io::Result<()> {
+        self.unacked_rx = 0;
+        self.t2_started = None;
+        self.send(&build_s_ack(self.rx_nr), "S-ACK")
+    }
+
+    fn on_frame(&mut self, apdu: &[u8]) -> std::io::Result<()> {
+        match classify_apdu(apdu) {
+            Frame::U { ut: UType::StartDtAct, .. } => {
+                self.started = true;
+                self.send(&[0x68, 0x04, U_STARTDT_CON, 0, 0, 0], "STARTDT con")
+            }
+            Frame::U { ut: UType::StopDtAct, .. } => {
+                self.started = false;
+                self.send(&[0x68, 0x04, U_STOPDT_CON, 0, 0, 0], "STOPDT con")
+            }
+            Frame::U { ut: UType::TestFrAct, .. } => self.send(&[0x68, 0x04, U_TESTFR_CON, 0, 0, 0], "TESTFR con"),
+            Frame::I { ns, asdu, .. } => {
+                logln!("[mock] < I-frame N(S)={}", ns);
+                self.rx_nr = seq_inc(ns);
+                self.unacked_rx += 1;
+                if self.t2_started.is_none() { self.t2_started = Some(Instant::now()); }
+                if let Some(a) = asdu {
+                    if a.type_id == TYPE_C_IC_NA_1 {
+                        self.answer_gi(apdu[apdu.len() - 1])?;
+                    }
+                }
+                if self.unacked_rx >= self.ack.w as usize { self.ack()?; }
+                Ok(())
+            }
+            other => {
+                logln!("[mock] < {:?}", other);
+                Ok(())
+            }
+        }
+    }
+
+    fn answer_gi(&mut self, qoi: u8) -> std::io::Result<()> {
+        self.send_asdu(&asdu(TYPE_C_IC_NA_1, 1, COT_ACTCON, &[0, 0, 0, qoi]), "GI act-con")?;
+        let mut objs = Vec::new();
+        for (ioa, on) in [(100u32, true), (101, false)] {
+            objs.extend_from_slice(&ioa.to_le_bytes()[..3]);
+            objs.push(on as u8);
+        }
+        self.send_asdu(&asdu(1, 2, COT_INROGEN, &objs), "GI M_SP_NA_1")?;
+        let mut objs = Vec::new();
+        objs.extend_from_slice(&2000u32.to_le_bytes()[..3]);
+        objs.extend_from_slice(&230.5f32.to_le_bytes());
+        objs.push(0);
+        self.send_asdu(&asdu(13, 1, COT_INROGEN, &objs), "GI M_ME_NC_1")?;
+        self.send_asdu(&asdu(TYPE_C_IC_NA_1, 1, COT_ACTTERM, &[0, 0, 0, qoi]), "GI act-term")
+    }
+
+    /// Satu langkah skrip: bergantian float periodik, SP spontan bertime tag, scaled.
+    fn script_step(&mut self) -> std::io::Result<()> {
+        self.step += 1;
+        let mut obj = Vec::new();
+        match self.step % 3 {
+            0 => {
+                obj.extend_from_slice(&2000u32.to_le_bytes()[..3]);
+                obj.extend_from_slice(&(230.0f32 + self.step as f32 * 0.1).to_le_bytes());
+                obj.push(0);
+                self.send_asdu(&asdu(13, 1, 1, &obj), "M_ME_NC_1 periodik")
+            }
+            1 => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+                obj.extend_from_slice(&100u32.to_le_bytes()[..3]);
+                obj.push((self.step / 3 % 2) as u8);
+                obj.extend_from_slice(&Cp56Time2a::from_epoch_ms(now, 0).to_bytes());
+                self.send_asdu(&asdu(30, 1, 3, &obj), "M_SP_TB_1 spontan")
+            }
+            _ => {
+                obj.extend_from_slice(&3000u32.to_le_bytes()[..3]);
+                obj.extend_from_slice(&(self.step as i16).to_le_bytes());
+                obj.push(0);
+                self.send_asdu(&asdu(11, 1, 3, &obj), "M_ME_NB_1 spontan")
+            }
+        }
+    }
+}
+
+/// Header ASDU (OA=0, casdu tiruan) + objek.
+fn asdu(type_id: u8, vsq: u8, cot: u8, objects: &[u8]) -> Vec<u8> {
+    let ca = MOCK_CASDU.to_le_bytes();
+    let mut a = vec![type_id, vsq, cot, 0, ca[0], ca[1]];
+    a.extend_from_slice(objects);
+    a
+}
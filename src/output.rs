@@ -0,0 +1,277 @@
+//! Format keluaran titik terdekode: teks (default), JSON lines, CSV, Influx line protocol.
+
+use crate::decode::{format_epoch_ms, DecodedPoint, ObjValue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat { Text, Json, Csv, Influx }
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Result<OutputFormat, String> {
+        match s.trim() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "csv" => Ok(OutputFormat::Csv),
+            "influx" => Ok(OutputFormat::Influx),
+            other => Err(format!("format keluaran tidak dikenal: '{}' (text|json|csv|influx)", other)),
+        }
+    }
+}
+
+pub const CSV_HEADER: &str = "casdu,ioa,type_id,cot,origin,value,quality,time,time_utc,time_ms,suspect_time,station,point,transient,value_eng,unit,counter_seq";
+
+/// Representasi kolom time_utc di CSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvTimestamp {
+    Iso,     // 2024-03-15T03:20:30.500Z
+    EpochMs, // 1710472830500
+    EpochS,  // 1710472830.500
+}
+
+/// Dialek CSV. Default = RFC 4180: koma, kutip hanya bila perlu, titik desimal, ISO-8601.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    /// Kutip semua medan, bukan hanya yang mengandung pemisah/kutip/baris baru.
+    pub quote_all: bool,
+    /// Koma desimal (gaya Eropa) untuk value, value_eng dan time_utc epoch_s.
+    pub decimal_comma: bool,
+    pub timestamp: CsvTimestamp,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions { delimiter: ',', quote_all: false, decimal_comma: false, timestamp: CsvTimestamp::Iso }
+    }
+}
+
+impl CsvOptions {
+    /// "," ";" "|" atau "tab"; kutip dan baris baru tidak boleh jadi pemisah.
+    pub fn parse_delimiter(s: &str) -> Result<char, String> {
+        let mut chars = s.chars();
+        let c = match (s, chars.next(), chars.next()) {
+            ("tab" | "\\t", _, _) => '\t',
+            (_, Some(c), None) => c,
+            _ => return Err(format!("pemisah CSV harus satu karakter atau 'tab': '{}'", s)),
+        };
+        if matches!(c, '"' | '\n' | '\r') {
+            return Err(format!("pemisah CSV tidak boleh {:?}", c));
+        }
+        Ok(c)
+    }
+
+    pub fn parse_timestamp(s: &str) -> Result<CsvTimestamp, String> {
+        match s.trim() {
+            "iso" => Ok(CsvTimestamp::Iso),
+            "epoch_ms" => Ok(CsvTimestamp::EpochMs),
+            "epoch_s" => Ok(CsvTimestamp::EpochS),
+            other => Err(format!("format waktu CSV tidak dikenal: '{}' (iso|epoch_ms|epoch_s)", other)),
+        }
+    }
+
+    pub fn header(&self) -> String {
+        self.row(CSV_HEADER.split(',').map(String::from).collect())
+    }
+
+    fn row(&self, fields: Vec<String>) -> String {
+        let quoted: Vec<String> = fields.iter().map(|f| self.field(f)).collect();
+        quoted.join(&self.delimiter.to_string())
+    }
+
+    fn field(&self, s: &str) -> String {
+        if self.quote_all || s.contains([self.delimiter, '"', '\n', '\r']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    }
+
+    fn number(&self, s: String) -> String {
+        if self.decimal_comma { s.replace('.', ",") } else { s }
+    }
+}
+
+/// Opsi format titik yang dibawa handler stdout dan sink berkas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OutputOptions {
+    pub csv: CsvOptions,
+    /// Besaran terukur berkualitas IV/OV diekspor sebagai null (Influx: "NaN"), bukan angka
+    /// di rentang normal yang menyesatkan. Format teks tetap menampilkan nilai mentah + q=.
+    pub invalid_as_null: bool,
+}
+
+impl OutputOptions {
+    fn masks(&self, p: &DecodedPoint) -> bool {
+        self.invalid_as_null
+            && matches!(p.obj.value, ObjValue::Normalized(_) | ObjValue::Scaled(_) | ObjValue::Float(_))
+            && p.obj.quality.is_some_and(|q| q.iv || q.ov)
+    }
+}
+
+/// Nilai sebagai literal JSON/CSV (state -> angka, analog -> angka).
+fn value_literal(v: &ObjValue) -> String {
+    match *v {
+        ObjValue::SinglePoint(b) => (b as u8).to_string(),
+        ObjValue::DoublePoint(d) => d.to_string(),
+        ObjValue::StepPos { pos, .. } => pos.to_string(),
+        ObjValue::Bitstring(b) => b.to_string(),
+        ObjValue::Normalized(f) | ObjValue::Float(f) if !f.is_finite() => "null".into(),
+        ObjValue::Normalized(f) | ObjValue::Float(f) => f.to_string(),
+        ObjValue::Scaled(i) => i.to_string(),
+        ObjValue::Counter { value, .. } => value.to_string(),
+        // Perintah & ASDU sistem: representasi teks sebagai string
+        other => format!("\"{}\"", other),
+    }
+}
+
+fn quality_str(p: &DecodedPoint) -> String {
+    p.obj.quality.map(|q| q.to_string()).unwrap_or_else(|| "-".into())
+}
+
+/// Medan Influx: sama seperti literal JSON, tapi line protocol tidak mengenal null.
+fn influx_value(literal: String) -> String {
+    match literal {
+        s if s == "null" => "\"NaN\"".into(),
+        s => s,
+    }
+}
+
+fn json_str_opt(s: Option<String>) -> String {
+    s.map(|t| format!("\"{}\"", t.replace('\\', "\\\\").replace('"', "\\\""))).unwrap_or_else(|| "null".into())
+}
+
+/// Nilai CSV: seperti literal JSON, tetapi teks perintah/sistem tanpa kutip JSON
+/// (pengutipan diurus dialek CSV).
+fn csv_value(v: &ObjValue) -> String {
+    match value_literal(v) {
+        s if s.starts_with('"') => v.to_string(),
+        s => s,
+    }
+}
+
+/// Tag Influx opsional: spasi, koma dan '=' wajib di-escape.
+fn influx_tag(key: &str, v: Option<&str>) -> String {
+    v.map(|t| format!(",{}={}", key, t.replace(' ', "\\ ").replace(',', "\\,").replace('=', "\\=")))
+        .unwrap_or_default()
+}
+
+pub fn format_point(fmt: OutputFormat, opts: &OutputOptions, p: &DecodedPoint) -> String {
+    let csv = &opts.csv;
+    // "time" = medan mentah seperti dikirim RTU (lokal), "time_utc" = hasil normalisasi,
+    // "time_ms" = time_utc sebagai milidetik epoch Unix (i64) untuk konsumen hilir
+    let time = p.obj.time.map(|t| t.to_string());
+    let time_utc = p.time_utc_ms.map(format_epoch_ms);
+    // Step position: flag transient ikut diekspor agar posisi yang masih bergerak tidak dianggap stabil
+    let step = matches!(p.obj.value, ObjValue::StepPos { .. });
+    let transient = p.obj.value.is_transient();
+    // Counter: SQ freeze ikut diekspor agar siklus freeze yang terlewat bisa diaudit hilir
+    let seq = p.obj.value.counter_seq();
+    // Perintah: bentuk pulsa sebagai medan sendiri, tanpa perlu mem-parse teks nilai
+    // Qualifier set-point (QL) dan parameter (KPA/LPC/POP) juga sebagai medan sendiri
+    let qu = p.obj.value.qu().map(|qu| format!(",\"qu\":\"{}\"", qu)).unwrap_or_default()
+        + &p.obj.value.ql().map(|ql| format!(",\"ql\":\"{}\"", ql)).unwrap_or_default()
+        + &p.obj.value.qpm()
+            .map(|q| format!(",\"kpa\":\"{}\",\"lpc\":{},\"pop\":{}", q.kpa, q.lpc, q.pop))
+            .unwrap_or_default();
+    let masked = opts.masks(p);
+    let eng = p.value_eng.filter(|v| v.is_finite() && !masked);
+    let literal = if masked { "null".to_string() } else { value_literal(&p.obj.value) };
+    match fmt {
+        OutputFormat::Text => format!(
+            "      IOA={}{} val={}{} q={} [{}]{}{}{}",
+            p.obj.ioa, p.point.as_deref().map(|n| format!(" ({})", n)).unwrap_or_default(), p.obj.value,
+            // Teks ringkas: nilai teknik hanya bila ada entri [scaling] yang mengubah nilai/memberi satuan
+            match (eng, p.obj.value.as_f64()) {
+                (Some(e), Some(raw)) if e != raw || p.unit.is_some() =>
+                    format!(" eng={}{}", e, p.unit.as_deref().map(|u| format!(" {}", u)).unwrap_or_default()),
+                _ => String::new(),
+            },
+            quality_str(p), p.origin.as_str(),
+            time.as_deref().map(|t| format!(" t={}", t)).unwrap_or_default(),
+            time_utc.as_deref().map(|t| format!(" utc={}", t)).unwrap_or_default(),
+            if p.suspect_time { " (waktu mencurigakan)" } else { "" }
+        ),
+        OutputFormat::Json => format!(
+            "{{\"casdu\":{},\"station\":{},\"ioa\":{},\"point\":{},\"type_id\":{},\"cot\":{},\"origin\":\"{}\",\"value\":{},\"value_eng\":{},\"unit\":{},\"quality\":\"{}\",\"time\":{},\"time_utc\":{},\"time_ms\":{},\"suspect_time\":{}{}{}{}}}",
+            p.casdu, json_str_opt(p.station.clone()), p.obj.ioa, json_str_opt(p.point.clone()),
+            p.type_id, p.cot, p.origin.as_str(), literal,
+            eng.map(|v| v.to_string()).unwrap_or_else(|| "null".into()), json_str_opt(p.unit.clone()), quality_str(p),
+            json_str_opt(time), json_str_opt(time_utc),
+            p.time_utc_ms.map(|ms| ms.to_string()).unwrap_or_else(|| "null".into()), p.suspect_time,
+            if step { format!(",\"transient\":{}", transient) } else { String::new() },
+            seq.map(|s| format!(",\"counter_seq\":{}", s)).unwrap_or_default(), qu
+        ),
+        OutputFormat::Csv => csv.row(vec![
+            p.casdu.to_string(), p.obj.ioa.to_string(), p.type_id.to_string(), p.cot.to_string(),
+            p.origin.as_str().to_string(), csv.number(if masked { literal } else { csv_value(&p.obj.value) }), quality_str(p),
+            time.unwrap_or_default(),
+            match csv.timestamp {
+                CsvTimestamp::Iso => time_utc.unwrap_or_default(),
+                CsvTimestamp::EpochMs => p.time_utc_ms.map(|ms| ms.to_string()).unwrap_or_default(),
+                CsvTimestamp::EpochS => p.time_utc_ms
+                    .map(|ms| csv.number(format!("{}.{:03}", ms.div_euclid(1000), ms.rem_euclid(1000))))
+                    .unwrap_or_default(),
+            },
+            p.time_utc_ms.map(|ms| ms.to_string()).unwrap_or_default(), p.suspect_time.to_string(),
+            p.station.clone().unwrap_or_default(), p.point.clone().unwrap_or_default(),
+            if step { transient.to_string() } else { String::new() },
+            eng.map(|v| csv.number(v.to_string())).unwrap_or_default(), p.unit.clone().unwrap_or_default(),
+            seq.map(|s| s.to_string()).unwrap_or_default(),
+        ]),
+        // Tanpa time tag, stempel waktu dibiarkan kosong agar server memakai waktu terima.
+        OutputFormat::Influx => format!(
+            "iec104,casdu={},ioa={},type_id={},origin={}{}{} value={}{},quality=\"{}\",cot={}i,suspect_time={}{}{}{}",
+            p.casdu, p.obj.ioa, p.type_id, p.origin.as_str(),
+            influx_tag("station", p.station.as_deref()), influx_tag("point", p.point.as_deref()), influx_value(literal),
+            eng.map(|v| format!(",value_eng={}", v)).unwrap_or_default()
+                + &p.unit.as_deref().map(|u| format!(",unit=\"{}\"", u.replace('"', "\\\""))).unwrap_or_default(),
+            quality_str(p), p.cot,
+            p.suspect_time, if step { format!(",transient={}", transient) } else { String::new() },
+            seq.map(|s| format!(",counter_seq={}i", s)).unwrap_or_default(),
+            p.time_utc_ms.map(|ms| format!(" {}", ms * 1_000_000)).unwrap_or_default()
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{InformationObject, Origin, Quality};
+
+    /// Titik dengan nama konfigurasi yang mengandung pemisah, kutip dan baris baru.
+    fn awkward_point() -> DecodedPoint {
+        DecodedPoint {
+            casdu: 1, type_id: 13, cot: 3, origin: Origin::Spontaneous,
+            obj: InformationObject { ioa: 2000, value: ObjValue::Float(230.5), quality: Some(Quality::default()), time: None, bad_time: None },
+            time_utc_ms: None, suspect_time: false,
+            station: Some("GI \"Utara\", bay 2".into()), point: Some("Trafo 1\nsisi 20kV".into()),
+            value_eng: None, unit: Some("kV;AC".into()),
+        }
+    }
+
+    fn csv(csv: CsvOptions) -> String {
+        format_point(OutputFormat::Csv, &OutputOptions { csv, invalid_as_null: false }, &awkward_point())
+    }
+
+    #[test]
+    fn csv_quotes_fields_with_delimiter_quote_or_newline() {
+        assert_eq!(
+            csv(CsvOptions::default()),
+            "1,2000,13,3,spontaneous,230.5,OK,,,,false,\"GI \"\"Utara\"\", bay 2\",\"Trafo 1\nsisi 20kV\",,,kV;AC,"
+        );
+    }
+
+    #[test]
+    fn csv_quoting_follows_the_dialect() {
+        // Pemisah ';': koma desimal & koma di nama aman, ';' di satuan wajib dikutip
+        let semi = CsvOptions { delimiter: ';', decimal_comma: true, ..CsvOptions::default() };
+        assert_eq!(
+            csv(semi),
+            "1;2000;13;3;spontaneous;230,5;OK;;;;false;\"GI \"\"Utara\"\", bay 2\";\"Trafo 1\nsisi 20kV\";;;\"kV;AC\";"
+        );
+        // Koma desimal dengan pemisah ',' harus dikutip agar kolom tidak bergeser
+        let comma = CsvOptions { decimal_comma: true, ..CsvOptions::default() };
+        assert!(csv(comma).starts_with("1,2000,13,3,spontaneous,\"230,5\",OK,"));
+        let all = CsvOptions { quote_all: true, ..CsvOptions::default() };
+        assert!(csv(all).starts_with("\"1\",\"2000\",\"13\","));
+    }
+}
@@ -0,0 +1,127 @@
+//! Parameter efektif instance setelah default, INI dan CLI digabung: dicetak sebagai banner
+//! saat start dan diekspos lewat `/config` (JSON) serta `iec104_params_info` di `/metrics`.
+//! Jawaban untuk pertanyaan pertama tiap tiket dukungan: instance ini sebenarnya jalan dengan
+//! pengaturan apa?
+
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::config::{AckMode, AsduLayoutPolicy, Config, TypePolicy};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Params {
+    /// Perkiraan k sisi RTU (jendela terima kita) dan k sisi kirim kita.
+    pub k: u16,
+    pub k_tx: u16,
+    pub w: u16,
+    pub t1: Duration,
+    pub t2: Duration,
+    /// t3 supervisi TESTFR; None = mati.
+    pub t3: Option<Duration>,
+    pub ack_mode: AckMode,
+    /// aktif | listen-only | decode-only | pasif
+    pub mode: &'static str,
+    /// I-frame keluar (GI, perintah, berkas) bisa terkirim: bukan ACK_ONLY, bukan decode-only.
+    pub commands: bool,
+    pub ack_only: bool,
+    pub out_types: TypePolicy,
+    pub originator: Option<u8>,
+    /// Tata letak ASDU RX: preset `[asdu]` plus medan yang ditimpa.
+    pub asdu: AsduLayoutPolicy,
+}
+
+impl Params {
+    /// `ack_only` = `Config::ack_only` (konstanta build dan --enable-tx); `t1` konstanta di main.rs.
+    pub fn new(cfg: &Config, ack_only: bool, t1: Duration) -> Params {
+        let mode = if cfg.passive {
+            "pasif"
+        } else if cfg.decode_only {
+            "decode-only"
+        } else if !cfg.startdt {
+            "listen-only"
+        } else {
+            "aktif"
+        };
+        Params {
+            k: cfg.ack.k, k_tx: cfg.max_outstanding_tx, w: cfg.ack.w, t1, t2: cfg.ack.t2,
+            t3: cfg.testfr.enabled.then_some(cfg.testfr.t3),
+            ack_mode: cfg.ack.mode, mode,
+            commands: !ack_only && !cfg.decode_only, ack_only,
+            out_types: cfg.out_types.clone(), originator: cfg.originator,
+            asdu: cfg.asdu,
+        }
+    }
+
+    fn ack_mode_str(&self) -> &'static str {
+        match self.ack_mode {
+            AckMode::Immediate => "immediate",
+            AckMode::Coalesced => "coalesced",
+        }
+    }
+
+    fn type_ids(&self) -> (&'static str, String) {
+        let (kind, ids) = match &self.out_types {
+            TypePolicy::Forbid(ids) => ("forbid", ids),
+            TypePolicy::Allow(ids) => ("allow", ids),
+        };
+        (kind, ids.iter().map(u8::to_string).collect::<Vec<_>>().join(","))
+    }
+
+    /// Baris banner start (tanpa prefiks waktu; dicetak lewat logln!).
+    pub fn banner(&self) -> Vec<String> {
+        vec![
+            format!(
+                "Parameter efektif: k={} (kirim {}) w={} t1={:?} t2={:?} t3={} ack={}",
+                self.k, self.k_tx, self.w, self.t1, self.t2,
+                self.t3.map(|t| format!("{:?}", t)).unwrap_or_else(|| "mati".into()), self.ack_mode_str()
+            ),
+            format!("  ASDU: {}", self.asdu),
+            format!(
+                "  mode={} perintah={} tipe keluar: {} OA={}",
+                self.mode,
+                match (self.commands, self.ack_only) {
+                    (true, _) => "aktif".to_string(),
+                    (false, true) => "mati (ACK-only; --enable-tx)".to_string(),
+                    (false, false) => format!("mati ({})", self.mode),
+                },
+                self.out_types,
+                self.originator.map(|o| o.to_string()).unwrap_or_else(|| "-".into())
+            ),
+        ]
+    }
+
+    /// Satu baris info Prometheus (nilai selalu 1, parameter di label) plus gauge durasi.
+    pub fn metrics(&self) -> String {
+        let (kind, ids) = self.type_ids();
+        let l = self.asdu.layout();
+        let mut out = String::new();
+        let _ = writeln!(out, "# HELP iec104_params_info parameter efektif (nilai di label)\n# TYPE iec104_params_info gauge");
+        let _ = writeln!(
+            out,
+            "iec104_params_info{{k=\"{}\",k_tx=\"{}\",w=\"{}\",ack_mode=\"{}\",mode=\"{}\",commands=\"{}\",{}_types=\"{}\",cot_len=\"{}\",casdu_len=\"{}\",ioa_len=\"{}\",asdu_layout=\"{}\"}} 1",
+            self.k, self.k_tx, self.w, self.ack_mode_str(), self.mode, self.commands, kind, ids, l.cot, l.casdu, l.ioa,
+            self.asdu.preset.name()
+        );
+        let _ = writeln!(out, "# HELP iec104_timer_seconds timer link efektif\n# TYPE iec104_timer_seconds gauge");
+        for (name, t) in [("t1", Some(self.t1)), ("t2", Some(self.t2)), ("t3", self.t3)] {
+            if let Some(t) = t {
+                let _ = writeln!(out, "iec104_timer_seconds{{timer=\"{}\"}} {:.3}", name, t.as_secs_f64());
+            }
+        }
+        out
+    }
+
+    /// Objek JSON untuk `/config`.
+    pub fn json(&self) -> String {
+        let (kind, ids) = self.type_ids();
+        let l = self.asdu.layout();
+        let opt = |v: Option<String>| v.unwrap_or_else(|| "null".into());
+        format!(
+            "{{\"k\":{},\"k_tx\":{},\"w\":{},\"t1_s\":{:.3},\"t2_s\":{:.3},\"t3_s\":{},\"ack_mode\":\"{}\",\"mode\":\"{}\",\"commands\":{},\"{}_types\":[{}],\"originator\":{},\"cot_len\":{},\"casdu_len\":{},\"ioa_len\":{},\"asdu_layout\":\"{}\"}}",
+            self.k, self.k_tx, self.w, self.t1.as_secs_f64(), self.t2.as_secs_f64(),
+            opt(self.t3.map(|t| format!("{:.3}", t.as_secs_f64()))), self.ack_mode_str(), self.mode, self.commands,
+            kind, ids, opt(self.originator.map(|o| o.to_string())), l.cot, l.casdu, l.ioa,
+            self.asdu.preset.name()
+        )
+    }
+}
@@ -0,0 +1,179 @@
+//! Sumber data mode pasif dari berkas capture (libpcap klasik), mis. hasil tcpdump di port
+//! SPAN/tap. Payload TCP arah RTU -> master (port sumber = port RTU) dirakit ulang menjadi
+//! satu aliran byte, lalu diputar lewat socket loopback sehingga read loop biasa memprosesnya
+//! tanpa perubahan. Capture langsung dari antarmuka butuh raw socket (di luar std).
+
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener};
+
+const LINKTYPE_NULL: u32 = 0;
+const LINKTYPE_ETHERNET: u32 = 1;
+const LINKTYPE_RAW: u32 = 101;
+const LINKTYPE_LINUX_SLL: u32 = 113;
+const LINKTYPE_LINUX_SLL2: u32 = 276;
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+const ETHERTYPE_VLAN: [u16; 2] = [0x8100, 0x88A8];
+const IPPROTO_TCP: u8 = 6;
+
+/// Hasil ekstraksi: aliran byte RTU -> master dan catatan untuk operator.
+pub struct Extracted {
+    pub data: Vec<u8>,
+    pub segments: u64,
+    /// Celah urutan TCP (paket hilang dari capture); APDU di sekitarnya bisa rusak.
+    pub gaps: u64,
+    pub flow: Option<(SocketAddr, SocketAddr)>,
+    /// Aliran lain dari port yang sama (koneksi berbeda) yang diabaikan.
+    pub other_flows: u64,
+}
+
+/// Baca berkas pcap dan rakit payload TCP dengan port sumber `port`. Hanya aliran pertama
+/// yang membawa payload dipakai; koneksi berikutnya ke port yang sama diabaikan dan dihitung.
+pub fn extract(path: &str, port: u16) -> Result<Extracted, String> {
+    let raw = std::fs::read(path).map_err(|e| format!("gagal baca {}: {}", path, e))?;
+    if raw.len() < 24 {
+        return Err(format!("{}: terlalu pendek untuk header pcap", path));
+    }
+    let magic = [raw[0], raw[1], raw[2], raw[3]];
+    let le = match magic {
+        [0xD4, 0xC3, 0xB2, 0xA1] | [0x4D, 0x3C, 0xB2, 0xA1] => true,
+        [0xA1, 0xB2, 0xC3, 0xD4] | [0xA1, 0xB2, 0x3C, 0x4D] => false,
+        [0x0A, 0x0D, 0x0D, 0x0A] => return Err(format!("{}: pcapng tidak didukung; konversi dengan 'editcap -F pcap'", path)),
+        _ => return Err(format!("{}: bukan berkas pcap (magic {:02X?})", path, magic)),
+    };
+    let u32_at = |b: &[u8], i: usize| {
+        let v = [b[i], b[i + 1], b[i + 2], b[i + 3]];
+        if le { u32::from_le_bytes(v) } else { u32::from_be_bytes(v) }
+    };
+    let linktype = u32_at(&raw, 20) & 0x0FFF_FFFF;
+    if ![LINKTYPE_NULL, LINKTYPE_ETHERNET, LINKTYPE_RAW, LINKTYPE_LINUX_SLL, LINKTYPE_LINUX_SLL2].contains(&linktype) {
+        return Err(format!("{}: linktype {} tidak didukung (Ethernet, raw IP, Linux SLL)", path, linktype));
+    }
+
+    let mut out = Extracted { data: Vec::new(), segments: 0, gaps: 0, flow: None, other_flows: 0 };
+    let mut next_seq: Option<u32> = None;
+    let mut others: Vec<(SocketAddr, SocketAddr)> = Vec::new();
+    let mut pos = 24;
+    while pos + 16 <= raw.len() {
+        let incl = u32_at(&raw, pos + 8) as usize;
+        let start = pos + 16;
+        let Some(frame) = raw.get(start..start + incl) else { break }; // capture terpotong di akhir
+        pos = start + incl;
+
+        let Some(seg) = link_payload(frame, linktype).and_then(tcp_segment) else { continue };
+        if seg.src.port() != port || seg.payload.is_empty() { continue; }
+        match out.flow {
+            None => out.flow = Some((seg.src, seg.dst)),
+            Some(f) if f != (seg.src, seg.dst) => {
+                if !others.contains(&(seg.src, seg.dst)) {
+                    others.push((seg.src, seg.dst));
+                    out.other_flows += 1;
+                }
+                continue;
+            }
+            Some(_) => {}
+        }
+        // Rakit per nomor urut: retransmisi dilewati, tumpang tindih dipotong, celah dicatat
+        let skip = match next_seq {
+            None => 0,
+            Some(expect) => {
+                let ahead = seg.seq.wrapping_sub(expect) as i32;
+                if ahead > 0 {
+                    out.gaps += 1;
+                    0
+                } else {
+                    (-ahead) as usize
+                }
+            }
+        };
+        if skip >= seg.payload.len() { continue; }
+        out.data.extend_from_slice(&seg.payload[skip..]);
+        out.segments += 1;
+        next_seq = Some(seg.seq.wrapping_add(seg.payload.len() as u32));
+    }
+    Ok(out)
+}
+
+/// Putar `data` ke klien pertama yang tersambung ke alamat loopback yang dikembalikan,
+/// lalu tutup koneksi (read loop melihatnya sebagai peer menutup).
+pub fn replay(data: Vec<u8>) -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let addr = listener.local_addr()?;
+    std::thread::spawn(move || {
+        if let Ok((mut conn, _)) = listener.accept() {
+            let _ = conn.write_all(&data);
+        }
+    });
+    Ok(addr)
+}
+
+struct TcpSegment<'a> {
+    src: SocketAddr,
+    dst: SocketAddr,
+    seq: u32,
+    payload: &'a [u8],
+}
+
+/// Lepas header lapisan link; mengembalikan (ethertype, paket IP).
+fn link_payload(frame: &[u8], linktype: u32) -> Option<(u16, &[u8])> {
+    let be16 = |i: usize| frame.get(i..i + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    match linktype {
+        LINKTYPE_ETHERNET => {
+            let mut off = 12;
+            let mut ethertype = be16(off)?;
+            while ETHERTYPE_VLAN.contains(&ethertype) {
+                off += 4;
+                ethertype = be16(off)?;
+            }
+            Some((ethertype, frame.get(off + 2..)?))
+        }
+        LINKTYPE_LINUX_SLL => Some((be16(14)?, frame.get(16..)?)),
+        LINKTYPE_LINUX_SLL2 => Some((be16(0)?, frame.get(20..)?)),
+        // NULL: famili alamat 4 byte dalam urutan host penangkap; cukup lihat versi IP
+        LINKTYPE_NULL => ip_version(frame.get(4..)?),
+        _ => ip_version(frame),
+    }
+}
+
+fn ip_version(packet: &[u8]) -> Option<(u16, &[u8])> {
+    match packet.first()? >> 4 {
+        4 => Some((ETHERTYPE_IPV4, packet)),
+        6 => Some((ETHERTYPE_IPV6, packet)),
+        _ => None,
+    }
+}
+
+fn tcp_segment((ethertype, ip): (u16, &[u8])) -> Option<TcpSegment<'_>> {
+    let (src_ip, dst_ip, tcp): (IpAddr, IpAddr, &[u8]) = match ethertype {
+        ETHERTYPE_IPV4 => {
+            if ip.len() < 20 { return None; }
+            let ihl = (ip[0] & 0x0F) as usize * 4;
+            let total = u16::from_be_bytes([ip[2], ip[3]]) as usize;
+            // Fragmen IP (offset != 0 atau MF) tidak dirakit
+            if ip[9] != IPPROTO_TCP || u16::from_be_bytes([ip[6], ip[7]]) & 0x3FFF != 0 {
+                return None;
+            }
+            let src = Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]);
+            let dst = Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]);
+            // Panjang total membuang padding Ethernet di ujung frame
+            (src.into(), dst.into(), ip.get(ihl..total.min(ip.len()))?)
+        }
+        ETHERTYPE_IPV6 => {
+            // Tanpa extension header: next header harus langsung TCP
+            if *ip.get(6)? != IPPROTO_TCP { return None; }
+            let len = u16::from_be_bytes([*ip.get(4)?, *ip.get(5)?]) as usize;
+            let addr = |i: usize| -> Option<Ipv6Addr> { <[u8; 16]>::try_from(ip.get(i..i + 16)?).ok().map(Ipv6Addr::from) };
+            (addr(8)?.into(), addr(24)?.into(), ip.get(40..(40 + len).min(ip.len()))?)
+        }
+        _ => return None,
+    };
+    let be16 = |i: usize| tcp.get(i..i + 2).map(|b| u16::from_be_bytes([b[0], b[1]]));
+    let data_off = (*tcp.get(12)? >> 4) as usize * 4;
+    Some(TcpSegment {
+        src: SocketAddr::new(src_ip, be16(0)?),
+        dst: SocketAddr::new(dst_ip, be16(2)?),
+        seq: u32::from_be_bytes(tcp.get(4..8)?.try_into().ok()?),
+        payload: tcp.get(data_off..)?,
+    })
+}
@@ -0,0 +1,86 @@
+//! REPL untuk bring-up protokol: ketik APDU hex, lihat interpretasinya, kirim.
+//! Semua frame lewat `TxPolicy::enforce_rules`; awali baris dengan '!' untuk memaksa kirim.
+
+use std::io::{BufRead, Read, Write};
+use std::net::TcpStream;
+
+use crate::config::TypePolicy;
+use crate::decode::AsduLayout;
+use crate::{annotate_hex, classify_apdu, describe_frame, hex, take_one_apdu, TxPolicy};
+
+const HELP: &str = "\
+Ketik APDU sebagai hex (spasi opsional), mis. 68 04 43 00 00 00
+  !<hex>   kirim walau diblok gatekeeper
+  help     bantuan ini
+  quit     keluar";
+
+/// "68 04 07 00 00 00" / "680407000000" -> bytes
+pub fn parse_hex(line: &str) -> Result<Vec<u8>, String> {
+    let digits: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    if !digits.is_ascii() || digits.is_empty() || !digits.len().is_multiple_of(2) {
+        return Err("jumlah digit hex harus genap dan tidak kosong".into());
+    }
+    (0..digits.len()).step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|_| format!("hex tidak valid: '{}'", &digits[i..i + 2])))
+        .collect()
+}
+
+pub fn run(addr: &str, annotate: bool, types: &TypePolicy, ack_only: bool) -> std::io::Result<()> {
+    let show = move |apdu: &[u8]| if annotate { annotate_hex(apdu, AsduLayout::IEC104) } else { hex(apdu) };
+    logln!("Mode interaktif — menghubungkan ke RTU {} ...", addr);
+    let mut stream = TcpStream::connect(addr)?;
+    stream.set_nodelay(true)?;
+    println!("{}", HELP);
+
+    // Pembaca latar: cetak setiap frame yang diterima
+    let mut reader = stream.try_clone()?;
+    std::thread::spawn(move || {
+        let mut buf: Vec<u8> = Vec::new();
+        let mut tmp = [0u8; 4096];
+        loop {
+            match reader.read(&mut tmp) {
+                Ok(0) => { logln!("< koneksi ditutup peer"); break; }
+                Ok(n) => buf.extend_from_slice(&tmp[..n]),
+                Err(e) => { logln!("< kesalahan baca: {}", e); break; }
+            }
+            while let Some((apdu, consumed)) = take_one_apdu(&buf) {
+                logln!("< RX {}\n    {}", show(apdu), describe_frame(&classify_apdu(apdu)));
+                buf.drain(0..consumed);
+            }
+        }
+    });
+
+    let stdin = std::io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        match line {
+            "" => continue,
+            "quit" | "exit" => break,
+            "help" => { println!("{}", HELP); continue; }
+            _ => {}
+        }
+        let (force, body) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let apdu = match parse_hex(body) {
+            Ok(a) => a,
+            Err(e) => { logln!("(Error) {}", e); continue; }
+        };
+        // Tampilkan interpretasi sebelum kirim agar salah ketik terlihat
+        logln!("  = {}", describe_frame(&classify_apdu(&apdu)));
+        if annotate { logln!("  = {}", annotate_hex(&apdu, AsduLayout::IEC104)); }
+        if apdu.len() >= 2 && apdu[1] as usize + 2 != apdu.len() {
+            logln!("  (Peringatan) byte LEN={} tidak cocok dengan panjang {}", apdu[1], apdu.len());
+        }
+        match TxPolicy::enforce_rules(&apdu, types, ack_only) {
+            Err(e) if !force => { logln!("(Blok) {} — awali dengan '!' untuk paksa.", e); continue; }
+            Err(e) => logln!("(Paksa) mengabaikan gatekeeper: {}", e),
+            Ok(()) => {}
+        }
+        logln!("> TX {}", hex(&apdu));
+        stream.write_all(&apdu)?;
+    }
+    Ok(())
+}
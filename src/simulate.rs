@@ -0,0 +1,523 @@
+//! Subperintah `simulate`: RTU simulasi untuk menguji sink/dashboard hilir tanpa perangkat keras.
+//! Superset `--serve`: STARTDT/STOPDT/TESTFR, ACK w/t2, jendela kirim k dengan t1, ukur siklik
+//! tiap `cycle`, kejadian spontan bertime tag acak (rata-rata `event_rate` per detik), jawaban GI
+//! dan counter interrogation dari set titik terkonfigurasi, serta konfirmasi perintah. APCI
+//! dibentuk builder yang sama dengan client, time tag lewat codec CP56Time2a `decode`.
+
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::{AckPolicy, SimulatePolicy};
+use crate::decode::{self, Cp56Time2a, ObjValue};
+use crate::{
+    build_i_frame, build_s_ack, classify_apdu, hex, seq_distance, seq_inc, take_one_apdu, Frame, UType,
+    COT_ACT, COT_ACTCON, COT_ACTTERM, COT_INROGEN, MAX_ASDU_LEN, T1,
+    U_STARTDT_CON, U_STOPDT_CON, U_TESTFR_CON,
+};
+
+const TICK: Duration = Duration::from_millis(100);
+/// Antrean ASDU menunggu jendela k; lebih = kejadian tertua dibuang (buffer RTU meluap).
+const MAX_PENDING: usize = 1000;
+
+const COT_PERIODIC: u8 = 1;
+const COT_SPONT: u8 = 3;
+const COT_DEACT: u8 = 8;
+const COT_DEACTCON: u8 = 9;
+const COT_RETREM: u8 = 11;
+const COT_REQCOGEN: u8 = 37;
+const COT_UNKNOWN_TYPE: u8 = 44;
+const COT_UNKNOWN_COT: u8 = 45;
+const COT_UNKNOWN_CA: u8 = 46;
+const COT_UNKNOWN_IOA: u8 = 47;
+
+/// Jenis titik simulasi; menentukan type id (tanpa / dengan time tag) dan elemen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimKind {
+    Single,
+    Double,
+    Normalized,
+    Scaled,
+    Float,
+    Counter,
+}
+
+impl SimKind {
+    fn parse(s: &str) -> Result<SimKind, String> {
+        match s.trim() {
+            "sp" => Ok(SimKind::Single),
+            "dp" => Ok(SimKind::Double),
+            "normalized" => Ok(SimKind::Normalized),
+            "scaled" => Ok(SimKind::Scaled),
+            "float" => Ok(SimKind::Float),
+            "counter" => Ok(SimKind::Counter),
+            other => Err(format!("jenis titik simulasi tidak dikenal: '{}' (sp|dp|normalized|scaled|float|counter)", other)),
+        }
+    }
+
+    /// (tanpa time tag, CP56Time2a)
+    fn type_ids(self) -> (u8, u8) {
+        match self {
+            SimKind::Single => (1, 30),
+            SimKind::Double => (3, 31),
+            SimKind::Normalized => (9, 34),
+            SimKind::Scaled => (11, 35),
+            SimKind::Float => (13, 36),
+            SimKind::Counter => (15, 37),
+        }
+    }
+
+    fn initial(self) -> f64 {
+        match self {
+            SimKind::Single | SimKind::Counter => 0.0,
+            SimKind::Double => 1.0,
+            SimKind::Normalized => 0.5,
+            SimKind::Scaled => 1000.0,
+            SimKind::Float => 230.0,
+        }
+    }
+}
+
+/// `<ioa>:<jenis>[:<nilai awal>]`, mis. `2000:float:230.5`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimPoint {
+    pub ioa: u32,
+    pub kind: SimKind,
+    pub initial: f64,
+}
+
+impl SimPoint {
+    pub fn parse(s: &str) -> Result<SimPoint, String> {
+        let bad = || format!("titik simulasi tidak valid: '{}' (ioa:jenis[:nilai awal])", s.trim());
+        let mut parts = s.trim().split(':');
+        let ioa: u32 = parts.next().and_then(|t| t.trim().parse().ok()).ok_or_else(bad)?;
+        let kind = SimKind::parse(parts.next().ok_or_else(bad)?)?;
+        let initial = match parts.next() {
+            Some(v) => v.trim().parse::<f64>().ok().filter(|v| v.is_finite()).ok_or_else(bad)?,
+            None => kind.initial(),
+        };
+        if ioa > 0xFF_FFFF || parts.next().is_some() { return Err(bad()); }
+        Ok(SimPoint { ioa, kind, initial })
+    }
+
+    /// Set titik tanpa konfigurasi: satu per jenis, IOA sama dengan mock.
+    pub fn defaults() -> Vec<SimPoint> {
+        [(100, SimKind::Single), (101, SimKind::Single), (200, SimKind::Double), (1000, SimKind::Normalized),
+            (3000, SimKind::Scaled), (2000, SimKind::Float), (70, SimKind::Counter)]
+            .into_iter()
+            .map(|(ioa, kind)| SimPoint { ioa, kind, initial: kind.initial() })
+            .collect()
+    }
+}
+
+/// Layani master satu per satu sampai proses dihentikan.
+pub fn serve(policy: &SimulatePolicy) -> std::io::Result<()> {
+    let listener = TcpListener::bind(&policy.listen)?;
+    let seed = policy.seed.unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos() as u64).unwrap_or(1));
+    logln!(
+        "[sim] RTU simulasi mendengarkan di {}: casdu {}, {} titik, siklik {}, {:.2} kejadian/s, seed {}",
+        listener.local_addr()?, policy.casdu, policy.points.len(),
+        if policy.cycle.is_zero() { "mati".to_string() } else { format!("{:?}", policy.cycle) }, policy.event_rate, seed
+    );
+    // Nilai titik bertahan antar koneksi, seperti RTU sungguhan
+    let mut points: Vec<Point> = policy.points.iter().map(|p| Point { ioa: p.ioa, kind: p.kind, value: p.initial, frozen: p.initial, seq: 0 }).collect();
+    let mut rng = Rng::new(seed);
+    for conn in listener.incoming() {
+        let stream = conn?;
+        logln!("[sim] koneksi dari {}", stream.peer_addr()?);
+        let mut session = Session::new(stream, policy, &mut points, &mut rng);
+        let result = session.run();
+        let s = &session.sent;
+        logln!(
+            "[sim] sesi berakhir{}: {} I-frame terkirim ({} siklik, {} spontan, {} GI/CI), {} perintah, {} kejadian dibuang",
+            result.err().map(|e| format!(" ({})", e)).unwrap_or_default(),
+            s.frames, s.cyclic, s.spont, s.interrogated, s.commands, s.dropped
+        );
+    }
+    Ok(())
+}
+
+struct Point {
+    ioa: u32,
+    kind: SimKind,
+    /// sp 0/1, dp 1/2, ukur nilai mentah, counter nilai berjalan.
+    value: f64,
+    /// Counter: nilai saat freeze terakhir dan SQ-nya.
+    frozen: f64,
+    seq: u8,
+}
+
+impl Point {
+    /// Elemen tanpa IOA; kualitas selalu OK.
+    fn element(&self) -> Vec<u8> {
+        match self.kind {
+            SimKind::Single => vec![(self.value != 0.0) as u8],
+            SimKind::Double => vec![self.value as u8 & 0x03],
+            SimKind::Normalized => {
+                let raw = (self.value * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+                let b = raw.to_le_bytes();
+                vec![b[0], b[1], 0]
+            }
+            SimKind::Scaled => {
+                let b = (self.value.round().clamp(-32768.0, 32767.0) as i16).to_le_bytes();
+                vec![b[0], b[1], 0]
+            }
+            SimKind::Float => {
+                let mut e = (self.value as f32).to_le_bytes().to_vec();
+                e.push(0);
+                e
+            }
+            SimKind::Counter => {
+                let mut e = (self.frozen as i64 as i32).to_le_bytes().to_vec();
+                e.push(self.seq & 0x1F);
+                e
+            }
+        }
+    }
+
+    /// Langkah acak ukur: kecil untuk siklik, besar untuk kejadian spontan.
+    fn walk(&mut self, rng: &mut Rng, big: bool) {
+        let u = rng.next_f64() - 0.5;
+        let scale = if big { 10.0 } else { 1.0 };
+        self.value = match self.kind {
+            SimKind::Normalized => (self.value + u * 0.01 * scale).clamp(-1.0, 32767.0 / 32768.0),
+            SimKind::Scaled => (self.value + (u * 10.0 * scale).round()).clamp(-32768.0, 32767.0),
+            SimKind::Float => self.value + u * 0.01 * scale * (self.value.abs() + 1.0),
+            _ => return,
+        };
+    }
+}
+
+/// xorshift64*: cukup untuk lalu lintas uji yang bisa diulang dengan `seed`, tanpa crate rand.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng { Rng(seed.max(1)) }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// [0, 1)
+    fn next_f64(&mut self) -> f64 { (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64 }
+
+    fn below(&mut self, n: usize) -> usize { (self.next_u64() % n as u64) as usize }
+}
+
+#[derive(Default)]
+struct Sent {
+    frames: u64,
+    cyclic: u64,
+    spont: u64,
+    interrogated: u64,
+    commands: u64,
+    dropped: u64,
+}
+
+struct Session<'a> {
+    stream: TcpStream,
+    policy: &'a SimulatePolicy,
+    points: &'a mut Vec<Point>,
+    rng: &'a mut Rng,
+    ns: u16,
+    /// N(R) terakhir dari master: I-frame kita sebelum ini sudah di-ACK.
+    acked: u16,
+    /// Sejak kapan I-frame tertua kita belum di-ACK (t1).
+    unacked_since: Option<Instant>,
+    rx_nr: u16,
+    unacked_rx: usize,
+    /// k = jendela kirim kita, w/t2 = ACK I-frame master.
+    ack: AckPolicy,
+    t2_started: Option<Instant>,
+    started: bool,
+    pending: VecDeque<(Vec<u8>, &'static str)>,
+    next_cycle: Instant,
+    next_event: Option<Instant>,
+    sent: Sent,
+}
+
+impl<'a> Session<'a> {
+    fn new(stream: TcpStream, policy: &'a SimulatePolicy, points: &'a mut Vec<Point>, rng: &'a mut Rng) -> Self {
+        Session {
+            stream, policy, points, rng, ns: 0, acked: 0, unacked_since: None, rx_nr: 0, unacked_rx: 0,
+            ack: AckPolicy::default(), t2_started: None, started: false, pending: VecDeque::new(),
+            next_cycle: Instant::now(), next_event: None, sent: Sent::default(),
+        }
+    }
+
+    fn run(&mut self) -> std::io::Result<()> {
+        self.stream.set_read_timeout(Some(TICK))?;
+        let mut buf: Vec<u8> = Vec::new();
+        let mut tmp = [0u8; 1024];
+        loop {
+            match self.stream.read(&mut tmp) {
+                Ok(0) => return Ok(()),
+                Ok(n) => buf.extend_from_slice(&tmp[..n]),
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => {}
+                Err(e) => return Err(e),
+            }
+            while let Some((apdu, consumed)) = take_one_apdu(&buf) {
+                let apdu = apdu.to_vec();
+                buf.drain(0..consumed);
+                self.on_frame(&apdu)?;
+            }
+            if self.t2_started.is_some_and(|t| t.elapsed() >= self.ack.t2) {
+                self.send_ack()?;
+            }
+            if self.unacked_since.is_some_and(|t| t.elapsed() >= T1) {
+                return Err(std::io::Error::other(format!("t1 habis: {} I-frame belum di-ACK master", seq_distance(self.ns, self.acked))));
+            }
+            if self.started {
+                let now = Instant::now();
+                if !self.policy.cycle.is_zero() && now >= self.next_cycle {
+                    self.cyclic();
+                    self.next_cycle = now + self.policy.cycle;
+                }
+                while self.next_event.is_some_and(|t| now >= t) {
+                    self.event();
+                    self.schedule_event();
+                }
+            }
+            self.flush()?;
+        }
+    }
+
+    /// Jeda antar kejadian eksponensial: proses Poisson dengan laju `event_rate`.
+    fn schedule_event(&mut self) {
+        let rate = self.policy.event_rate;
+        let base = self.next_event.unwrap_or_else(Instant::now);
+        self.next_event = (rate > 0.0).then(|| base + Duration::from_secs_f64(-(1.0 - self.rng.next_f64()).ln() / rate));
+    }
+
+    fn send(&mut self, apdu: &[u8], what: &str) -> std::io::Result<()> {
+        logln!("[sim] > {}: {}", what, hex(apdu));
+        self.stream.write_all(apdu)
+    }
+
+    fn send_ack(&mut self) -> std::io::Result<()> {
+        self.unacked_rx = 0;
+        self.t2_started = None;
+        self.send(&build_s_ack(self.rx_nr), "S-ACK")
+    }
+
+    /// ASDU masuk antrean; keluar selama jendela k belum penuh.
+    fn queue(&mut self, asdu: Vec<u8>, what: &'static str) {
+        if self.pending.len() >= MAX_PENDING {
+            self.pending.pop_front();
+            self.sent.dropped += 1;
+        }
+        self.pending.push_back((asdu, what));
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        while seq_distance(self.ns, self.acked) < self.ack.k {
+            let Some((asdu, what)) = self.pending.pop_front() else { break };
+            let f = build_i_frame(self.ns, self.rx_nr, &asdu);
+            self.ns = seq_inc(self.ns);
+            // I-frame kita membawa N(R) => sekaligus ACK
+            self.unacked_rx = 0;
+            self.t2_started = None;
+            if self.unacked_since.is_none() { self.unacked_since = Some(Instant::now()); }
+            self.sent.frames += 1;
+            self.send(&f, what)?;
+        }
+        Ok(())
+    }
+
+    fn on_ack(&mut self, nr: u16) {
+        // N(R) di luar jendela kita diabaikan; master yang salah hitung akan kena t1
+        if seq_distance(nr, self.acked) <= seq_distance(self.ns, self.acked) {
+            self.acked = nr;
+            self.unacked_since = (self.acked != self.ns).then(Instant::now);
+        }
+    }
+
+    fn on_frame(&mut self, apdu: &[u8]) -> std::io::Result<()> {
+        match classify_apdu(apdu) {
+            Frame::U { ut: UType::StartDtAct, .. } => {
+                self.started = true;
+                self.next_cycle = Instant::now() + self.policy.cycle;
+                self.next_event = None;
+                self.schedule_event();
+                self.send(&[0x68, 0x04, U_STARTDT_CON, 0, 0, 0], "STARTDT con")
+            }
+            Frame::U { ut: UType::StopDtAct, .. } => {
+                self.started = false;
+                self.send(&[0x68, 0x04, U_STOPDT_CON, 0, 0, 0], "STOPDT con")
+            }
+            Frame::U { ut: UType::TestFrAct, .. } => self.send(&[0x68, 0x04, U_TESTFR_CON, 0, 0, 0], "TESTFR con"),
+            Frame::S { nr, .. } => {
+                self.on_ack(nr);
+                Ok(())
+            }
+            Frame::I { ns, nr, .. } => {
+                logln!("[sim] < I-frame N(S)={} N(R)={}", ns, nr);
+                self.on_ack(nr);
+                self.rx_nr = seq_inc(ns);
+                self.unacked_rx += 1;
+                if self.t2_started.is_none() { self.t2_started = Some(Instant::now()); }
+                if self.started {
+                    if let Some(asdu) = apdu.get(6..) { self.on_command(asdu); }
+                } else {
+                    logln!("[sim] (Peringatan) I-frame sebelum STARTDT diabaikan");
+                }
+                if self.unacked_rx >= self.ack.w as usize { self.send_ack()?; }
+                Ok(())
+            }
+            other => {
+                logln!("[sim] < {:?}", other);
+                Ok(())
+            }
+        }
+    }
+
+    /// Balasan cermin ASDU master dengan COT lain (act-con, act-term, negatif).
+    fn mirror(&mut self, asdu: &[u8], cot: u8, negative: bool, what: &'static str) {
+        let mut a = asdu.to_vec();
+        a[2] = cot | if negative { 0x40 } else { 0 };
+        self.queue(a, what);
+    }
+
+    fn on_command(&mut self, asdu: &[u8]) {
+        let Some(d) = decode::decode_asdu(asdu) else {
+            logln!("[sim] (Peringatan) ASDU master terlalu pendek: {}", hex(asdu));
+            return;
+        };
+        self.sent.commands += 1;
+        if d.casdu != self.policy.casdu && d.casdu != 0xFFFF {
+            return self.mirror(asdu, COT_UNKNOWN_CA, true, "CASDU tidak dikenal");
+        }
+        if d.cot == COT_DEACT {
+            return self.mirror(asdu, COT_DEACTCON, false, "deact-con");
+        }
+        if d.cot != COT_ACT {
+            return self.mirror(asdu, COT_UNKNOWN_COT, true, "COT tidak dikenal");
+        }
+        let Some(obj) = d.objects.first() else {
+            return self.mirror(asdu, COT_UNKNOWN_TYPE, true, "type tidak dikenal");
+        };
+        match obj.value {
+            ObjValue::Interrogation { qoi } => self.answer_gi(asdu, qoi),
+            ObjValue::CounterInterrogation { qcc } => self.answer_ci(asdu, qcc),
+            ObjValue::ClockSync => self.mirror(asdu, COT_ACTCON, false, "clock sync act-con"),
+            value => self.execute(asdu, d.type_id, obj.ioa, value),
+        }
+    }
+
+    fn answer_gi(&mut self, asdu: &[u8], qoi: u8) {
+        self.mirror(asdu, COT_ACTCON, false, "GI act-con");
+        // Titik simulasi tidak punya grup: GI grup (21..36) hanya act-con + act-term
+        if qoi == 20 {
+            for kind in [SimKind::Single, SimKind::Double, SimKind::Normalized, SimKind::Scaled, SimKind::Float] {
+                let idx: Vec<usize> = (0..self.points.len()).filter(|&i| self.points[i].kind == kind).collect();
+                self.sent.interrogated += self.report(&idx, kind.type_ids().0, COT_INROGEN, None, "GI");
+            }
+        }
+        self.mirror(asdu, COT_ACTTERM, false, "GI act-term");
+    }
+
+    /// QCC: RQT 5 = umum (grup counter 1..4 tidak dipakai), FRZ 0 baca, 1 freeze, 2 freeze +
+    /// reset, 3 reset. Freeze menaikkan SQ tiap counter.
+    fn answer_ci(&mut self, asdu: &[u8], qcc: u8) {
+        self.mirror(asdu, COT_ACTCON, false, "CI act-con");
+        let counters: Vec<usize> = (0..self.points.len()).filter(|&i| self.points[i].kind == SimKind::Counter).collect();
+        for &i in &counters {
+            let p = &mut self.points[i];
+            match qcc >> 6 {
+                1 | 2 => {
+                    p.frozen = p.value;
+                    p.seq = (p.seq + 1) & 0x1F;
+                    if qcc >> 6 == 2 { p.value = 0.0; }
+                }
+                3 => p.value = 0.0,
+                _ => {}
+            }
+        }
+        if qcc >> 6 == 0 && qcc & 0x3F == 5 {
+            self.sent.interrogated += self.report(&counters, SimKind::Counter.type_ids().0, COT_REQCOGEN, None, "CI M_IT_NA_1");
+        }
+        self.mirror(asdu, COT_ACTTERM, false, "CI act-term");
+    }
+
+    /// Perintah ke titik yang jenisnya cocok: act-con, dan untuk execute terapkan nilainya,
+    /// act-term, lalu nilai baru sebagai informasi balik (COT 11) bertime tag.
+    fn execute(&mut self, asdu: &[u8], type_id: u8, ioa: u32, value: ObjValue) {
+        let (kind, select, new) = match value {
+            ObjValue::SingleCommand { on, select, .. } => (SimKind::Single, select, on as u8 as f64),
+            ObjValue::DoubleCommand { dcs, select, .. } => (SimKind::Double, select, dcs as f64),
+            ObjValue::SetpointNormalized { value, select, .. } => (SimKind::Normalized, select, value as f64),
+            ObjValue::SetpointScaled { value, select, .. } => (SimKind::Scaled, select, value as f64),
+            ObjValue::SetpointFloat { value, select, .. } => (SimKind::Float, select, value as f64),
+            _ => {
+                logln!("[sim] (Peringatan) type {} tidak didukung simulasi", type_id);
+                return self.mirror(asdu, COT_UNKNOWN_TYPE, true, "type tidak dikenal");
+            }
+        };
+        let Some(i) = self.points.iter().position(|p| p.ioa == ioa && p.kind == kind) else {
+            return self.mirror(asdu, COT_UNKNOWN_IOA, true, "IOA tidak dikenal");
+        };
+        self.mirror(asdu, COT_ACTCON, false, "perintah act-con");
+        if select { return; }
+        self.points[i].value = new;
+        self.mirror(asdu, COT_ACTTERM, false, "perintah act-term");
+        self.report(&[i], kind.type_ids().1, COT_RETREM, Some(now_ms()), "informasi balik");
+    }
+
+    /// Ukur siklik (COT 1, tanpa time tag); counter berjalan ikut bertambah.
+    fn cyclic(&mut self) {
+        for i in 0..self.points.len() {
+            let p = &mut self.points[i];
+            if p.kind == SimKind::Counter { p.value += self.rng.below(10) as f64; }
+            p.walk(self.rng, false);
+        }
+        for kind in [SimKind::Normalized, SimKind::Scaled, SimKind::Float] {
+            let idx: Vec<usize> = (0..self.points.len()).filter(|&i| self.points[i].kind == kind).collect();
+            self.sent.cyclic += self.report(&idx, kind.type_ids().0, COT_PERIODIC, None, "siklik");
+        }
+    }
+
+    /// Satu titik status/ukur acak berubah, dikirim spontan bertime tag.
+    fn event(&mut self) {
+        let candidates: Vec<usize> = (0..self.points.len()).filter(|&i| self.points[i].kind != SimKind::Counter).collect();
+        if candidates.is_empty() { return; }
+        let i = candidates[self.rng.below(candidates.len())];
+        let p = &mut self.points[i];
+        match p.kind {
+            SimKind::Single => p.value = if p.value != 0.0 { 0.0 } else { 1.0 },
+            SimKind::Double => p.value = if p.value == 2.0 { 1.0 } else { 2.0 },
+            _ => p.walk(self.rng, true),
+        }
+        let type_id = p.kind.type_ids().1;
+        self.sent.spont += self.report(&[i], type_id, COT_SPONT, Some(now_ms()), "spontan");
+    }
+
+    /// Titik `idx` sebagai ASDU SQ=0 (dipecah agar muat `MAX_ASDU_LEN`), opsional bertime tag.
+    /// Mengembalikan jumlah ASDU yang diantre.
+    fn report(&mut self, idx: &[usize], type_id: u8, cot: u8, time_ms: Option<i64>, what: &'static str) -> u64 {
+        let tag = time_ms.map(|ms| Cp56Time2a::from_epoch_ms(ms, 0).to_bytes());
+        let objs: Vec<Vec<u8>> = idx.iter().map(|&i| {
+            let p = &self.points[i];
+            let mut o = p.ioa.to_le_bytes()[..3].to_vec();
+            o.extend_from_slice(&p.element());
+            if let Some(t) = tag { o.extend_from_slice(&t); }
+            o
+        }).collect();
+        let Some(size) = objs.first().map(Vec::len) else { return 0 };
+        let per_asdu = ((MAX_ASDU_LEN - 6) / size).min(127);
+        for chunk in objs.chunks(per_asdu) {
+            let ca = self.policy.casdu.to_le_bytes();
+            let mut a = vec![type_id, chunk.len() as u8, cot, 0, ca[0], ca[1]];
+            for o in chunk { a.extend_from_slice(o); }
+            self.queue(a, what);
+        }
+        objs.chunks(per_asdu).len() as u64
+    }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0)
+}
@@ -0,0 +1,692 @@
+//! Sink: tujuan penyimpanan titik terdekode. Tiap integrasi = satu impl `Sink`,
+//! `SinkSet` menyebarkan titik ke semua sink; penulisan gagal dicoba ulang sesuai
+//! `RetryPolicy`, lalu titiknya masuk dead-letter. `SinkWorker` menjalankan `SinkSet`
+//! di thread sendiri di balik antrean terbatas agar read loop tidak pernah menunggu sink.
+//! Saat proses berhenti antrean dan buffer batch dikuras lebih dulu, dengan batas waktu.
+
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::decode::{format_epoch_ms, DecodedPoint};
+use crate::handler::{ConnState, FrameHandler};
+use crate::output::{self, CsvOptions, OutputFormat, OutputOptions};
+
+#[derive(Debug)]
+pub enum SinkError {
+    Io(std::io::Error),
+    /// Batch yang gagal dikirim, beserta titiknya (untuk retry / dead-letter).
+    Undelivered { source: Box<SinkError>, points: Vec<DecodedPoint> },
+}
+
+impl fmt::Display for SinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SinkError::Io(e) => write!(f, "I/O: {}", e),
+            SinkError::Undelivered { source, points } => write!(f, "{} ({} titik)", source, points.len()),
+        }
+    }
+}
+
+impl From<std::io::Error> for SinkError {
+    fn from(e: std::io::Error) -> Self { SinkError::Io(e) }
+}
+
+pub trait Sink: Send {
+    fn name(&self) -> &str;
+    fn write(&mut self, point: &DecodedPoint) -> Result<(), SinkError>;
+    /// Tulis banyak titik sekaligus; sink jaringan sebaiknya override jadi satu request.
+    fn write_batch(&mut self, points: &[DecodedPoint]) -> Result<(), SinkError> {
+        points.iter().try_for_each(|p| self.write(p))
+    }
+    fn flush(&mut self) -> Result<(), SinkError> { Ok(()) }
+    /// Dipanggil berkala dari read loop (juga saat link sepi) untuk flush berbasis waktu.
+    fn tick(&mut self) -> Result<(), SinkError> { Ok(()) }
+    /// Titik yang ditahan sink (buffer batch) dan belum diteruskan ke tujuan.
+    fn pending(&self) -> usize { 0 }
+}
+
+/// Batching per sink: flush saat `size` titik terkumpul atau `interval` lewat, mana yang dulu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Batching {
+    pub size: usize,
+    pub interval: Option<Duration>,
+}
+
+/// Spesifikasi sink dari konfigurasi: `<format>:<path>[, batch=N][, interval=S]`,
+/// mis. `json:/var/log/points.jsonl, batch=500, interval=2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SinkSpec {
+    pub name: String,
+    pub format: OutputFormat,
+    pub path: String,
+    pub batch: Option<Batching>,
+}
+
+impl SinkSpec {
+    pub fn parse(name: &str, spec: &str) -> Result<SinkSpec, String> {
+        let mut parts = spec.split(',');
+        let target = parts.next().unwrap_or("");
+        let (fmt, path) = target.split_once(':').ok_or_else(|| format!("sink '{}': harus '<format>:<path>'", name))?;
+        if path.trim().is_empty() {
+            return Err(format!("sink '{}': path kosong", name));
+        }
+        let (mut size, mut interval) = (None, None);
+        for opt in parts {
+            let bad = || format!("sink '{}': opsi tidak valid '{}' (batch=N, interval=detik)", name, opt.trim());
+            let (k, v) = opt.split_once('=').ok_or_else(bad)?;
+            match k.trim() {
+                "batch" => size = Some(v.trim().parse::<usize>().ok().filter(|n| *n > 0).ok_or_else(bad)?),
+                "interval" => {
+                    let secs = v.trim().parse::<f64>().ok().filter(|s| s.is_finite() && *s > 0.0).ok_or_else(bad)?;
+                    interval = Some(Duration::from_secs_f64(secs));
+                }
+                _ => return Err(bad()),
+            }
+        }
+        // interval saja = batch tanpa batas jumlah praktis; batch saja = tanpa flush berbasis waktu
+        let batch = (size.is_some() || interval.is_some())
+            .then(|| Batching { size: size.unwrap_or(usize::MAX), interval });
+        Ok(SinkSpec { name: name.to_string(), format: OutputFormat::parse(fmt)?, path: path.trim().to_string(), batch })
+    }
+}
+
+/// Pembungkus batching untuk sink apa pun: titik ditahan di memori lalu diteruskan via
+/// `write_batch`. Sisa buffer di-flush saat putus koneksi dan saat dibuang (keluar proses).
+pub struct BatchSink {
+    inner: Box<dyn Sink>,
+    cfg: Batching,
+    buf: Vec<DecodedPoint>,
+    last_flush: Instant,
+}
+
+impl BatchSink {
+    pub fn new(inner: Box<dyn Sink>, cfg: Batching) -> Self {
+        Self { inner, cfg, buf: Vec::new(), last_flush: Instant::now() }
+    }
+
+    fn flush_buf(&mut self) -> Result<(), SinkError> {
+        self.last_flush = Instant::now();
+        if self.buf.is_empty() { return Ok(()); }
+        // Batch gagal tidak ditahan di sini (buffer tak terbatas lebih berbahaya bagi kolektor);
+        // titiknya dikembalikan ke SinkSet untuk retry / dead-letter
+        let batch = std::mem::take(&mut self.buf);
+        match self.inner.write_batch(&batch).and_then(|_| self.inner.flush()) {
+            Ok(()) => Ok(()),
+            Err(e) => Err(SinkError::Undelivered { source: Box::new(e), points: batch }),
+        }
+    }
+}
+
+impl Sink for BatchSink {
+    fn name(&self) -> &str { self.inner.name() }
+
+    fn write(&mut self, point: &DecodedPoint) -> Result<(), SinkError> {
+        self.buf.push(point.clone());
+        if self.buf.len() >= self.cfg.size { self.flush_buf()?; }
+        Ok(())
+    }
+
+    /// Kirim langsung bersama isi buffer (dipakai SinkSet saat retry).
+    fn write_batch(&mut self, points: &[DecodedPoint]) -> Result<(), SinkError> {
+        self.buf.extend_from_slice(points);
+        self.flush_buf()
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> { self.flush_buf() }
+
+    fn tick(&mut self) -> Result<(), SinkError> {
+        match self.cfg.interval {
+            Some(iv) if self.last_flush.elapsed() >= iv => self.flush_buf(),
+            _ => Ok(()),
+        }
+    }
+
+    fn pending(&self) -> usize { self.buf.len() }
+}
+
+impl Drop for BatchSink {
+    fn drop(&mut self) {
+        // flush_buf sudah mengambil buffer: jumlah yang hilang dibawa Undelivered
+        if let Err(e) = self.flush_buf() {
+            let lost = match &e { SinkError::Undelivered { points, .. } => points.len(), SinkError::Io(_) => 0 };
+            elogln!("(Sink {}) flush akhir gagal, {} titik hilang: {}", self.inner.name(), lost, e);
+        }
+    }
+}
+
+/// Rotasi berkas sink berdasarkan ukuran; berlaku untuk semua sink berkas.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rotation {
+    /// Ukuran segmen maksimum dalam byte; 0 = tanpa rotasi.
+    pub max_bytes: u64,
+    /// Kompres segmen yang dirotasi ke `.gz` di thread latar.
+    pub gzip: bool,
+}
+
+impl Rotation {
+    /// "10485760", "512K", "10M", "1G"; 0/off = mati.
+    pub fn parse_size(s: &str) -> Result<u64, String> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("off") { return Ok(0); }
+        let (num, mult) = match s.char_indices().last() {
+            Some((i, 'K' | 'k')) => (&s[..i], 1 << 10),
+            Some((i, 'M' | 'm')) => (&s[..i], 1 << 20),
+            Some((i, 'G' | 'g')) => (&s[..i], 1 << 30),
+            _ => (s, 1),
+        };
+        num.trim().parse::<u64>().ok().and_then(|n| n.checked_mul(mult))
+            .ok_or_else(|| format!("ukuran tidak valid: '{}' (mis. 10M)", s))
+    }
+}
+
+/// Sink berkas: satu baris per titik (JSON lines / CSV / teks), mode append.
+pub struct FileSink {
+    name: String,
+    format: OutputFormat,
+    opts: OutputOptions,
+    path: PathBuf,
+    out: BufWriter<File>,
+    rotation: Rotation,
+    /// Ukuran segmen aktif (termasuk isi lama saat berkas dibuka).
+    written: u64,
+    /// Thread kompresi segmen terotasi; ditunggu saat sink ditutup agar tidak tersisa `.gz.part`.
+    compressors: Vec<JoinHandle<()>>,
+}
+
+impl FileSink {
+    pub fn open(spec: &SinkSpec, rotation: Rotation, opts: OutputOptions) -> Result<FileSink, SinkError> {
+        let path = PathBuf::from(&spec.path);
+        let (out, written) = Self::open_segment(&path, spec.format, &opts.csv)?;
+        Ok(FileSink { name: spec.name.clone(), format: spec.format, opts, path, out, rotation, written, compressors: Vec::new() })
+    }
+
+    fn open_segment(path: &PathBuf, format: OutputFormat, csv: &CsvOptions) -> Result<(BufWriter<File>, u64), SinkError> {
+        let mut len = path.metadata().map(|m| m.len()).unwrap_or(0);
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut out = BufWriter::new(file);
+        if format == OutputFormat::Csv && len == 0 {
+            let header = csv.header();
+            writeln!(out, "{}", header)?;
+            len = header.len() as u64 + 1;
+        }
+        Ok((out, len))
+    }
+
+    /// Tutup segmen aktif, ganti nama ke `<path>.<waktu UTC>`, buka segmen baru.
+    fn rotate(&mut self) -> Result<(), SinkError> {
+        self.out.flush()?;
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        let stamp: String = format_epoch_ms(now_ms).chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+        let mut rotated = self.path.as_os_str().to_owned();
+        rotated.push(format!(".{}", stamp));
+        let rotated = PathBuf::from(rotated);
+        std::fs::rename(&self.path, &rotated)?;
+        (self.out, self.written) = Self::open_segment(&self.path, self.format, &self.opts.csv)?;
+        logln!("(Sink {}) dirotasi: {}", self.name, rotated.display());
+        if self.rotation.gzip {
+            // Kompresi bisa makan detik untuk segmen besar: jangan tahan read loop
+            let name = self.name.clone();
+            self.compressors.retain(|t| !t.is_finished());
+            self.compressors.push(std::thread::spawn(move || match crate::gzip::compress_file(&rotated) {
+                Ok(gz) => logln!("(Sink {}) dikompres: {}", name, gz.display()),
+                Err(e) => elogln!("(Sink {}) gagal kompres {}: {}", name, rotated.display(), e),
+            }));
+        }
+        Ok(())
+    }
+}
+
+impl Sink for FileSink {
+    fn name(&self) -> &str { &self.name }
+
+    fn write(&mut self, point: &DecodedPoint) -> Result<(), SinkError> {
+        let line = output::format_point(self.format, &self.opts, point);
+        writeln!(self.out, "{}", line)?;
+        self.written += line.len() as u64 + 1;
+        if self.rotation.max_bytes > 0 && self.written >= self.rotation.max_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), SinkError> {
+        self.out.flush()?;
+        Ok(())
+    }
+}
+
+impl Drop for FileSink {
+    fn drop(&mut self) {
+        for t in self.compressors.drain(..) {
+            let _ = t.join();
+        }
+    }
+}
+
+/// Percobaan ulang penulisan sink yang gagal, dengan backoff berlipat ganda.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self { RetryPolicy { retries: 2, backoff: Duration::from_millis(200) } }
+}
+
+/// Berkas JSON lines untuk titik yang gagal dikirim sink setelah semua retry.
+/// Sink tanpa batch menyangga tulisan di BufWriter: bila gagal saat flush, titiknya tidak
+/// lagi diketahui; pakai `batch=N` agar kegagalan bisa dikaitkan ke titik.
+pub struct DeadLetter {
+    out: BufWriter<File>,
+    pub count: u64,
+}
+
+impl DeadLetter {
+    pub fn open(path: &str) -> std::io::Result<DeadLetter> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(DeadLetter { out: BufWriter::new(file), count: 0 })
+    }
+
+    fn write(&mut self, sink: &str, err: &SinkError, points: &[DecodedPoint]) -> std::io::Result<()> {
+        let esc = |s: &str| s.replace('\\', "\\\\").replace('"', "\\\"");
+        for p in points {
+            writeln!(
+                self.out, "{{\"sink\":\"{}\",\"error\":\"{}\",\"point\":{}}}",
+                esc(sink), esc(&err.to_string()), output::format_point(OutputFormat::Json, &OutputOptions::default(), p)
+            )?;
+            self.count += 1;
+        }
+        self.out.flush()
+    }
+}
+
+#[derive(Default)]
+pub struct SinkSet {
+    sinks: Vec<Box<dyn Sink>>,
+    retry: RetryPolicy,
+    dead_letter: Option<DeadLetter>,
+    pub errors: u64,
+}
+
+impl SinkSet {
+    pub fn from_specs(specs: &[SinkSpec], rotation: Rotation, opts: OutputOptions) -> Result<SinkSet, String> {
+        let mut set = SinkSet::default();
+        for spec in specs {
+            let sink = FileSink::open(spec, rotation, opts).map_err(|e| format!("sink '{}': {}", spec.name, e))?;
+            logln!("(Info) sink '{}' aktif: {:?} -> {}", spec.name, spec.format, spec.path);
+            match spec.batch {
+                Some(b) => set.push(Box::new(BatchSink::new(Box::new(sink), b))),
+                None => set.push(Box::new(sink)),
+            }
+        }
+        Ok(set)
+    }
+
+    pub fn push(&mut self, sink: Box<dyn Sink>) { self.sinks.push(sink); }
+
+    pub fn is_empty(&self) -> bool { self.sinks.is_empty() }
+
+    pub fn set_delivery(&mut self, retry: RetryPolicy, dead_letter: Option<DeadLetter>) {
+        self.retry = retry;
+        self.dead_letter = dead_letter;
+    }
+
+    pub fn flush_all(&mut self) {
+        for i in 0..self.sinks.len() {
+            let r = self.sinks[i].flush();
+            self.settle(i, r, &[]);
+        }
+    }
+
+    pub fn pending(&self) -> usize { self.sinks.iter().map(|s| s.pending()).sum() }
+
+    /// Flush akhir sebelum proses keluar: per sink dicatat berapa titik buffer yang diteruskan,
+    /// lalu semua sink (dan berkasnya) ditutup.
+    fn close(mut self) {
+        for i in 0..self.sinks.len() {
+            let n = self.sinks[i].pending();
+            let errors = self.errors;
+            let r = self.sinks[i].flush();
+            self.settle(i, r, &[]);
+            if n > 0 && self.errors == errors {
+                logln!("(Sink {}) flush akhir: {} titik buffer ditulis.", self.sinks[i].name(), n);
+            }
+        }
+        if let Some(dl) = self.dead_letter.as_ref().filter(|dl| dl.count > 0) {
+            logln!("Ringkasan: {} titik masuk dead-letter.", dl.count);
+        }
+    }
+
+    /// Tangani hasil operasi sink ke-i: retry dengan backoff, lalu dead-letter.
+    /// `points` = titik yang ditulis operasi itu (kosong untuk flush sink tanpa buffer).
+    fn settle(&mut self, i: usize, mut result: Result<(), SinkError>, points: &[DecodedPoint]) {
+        let mut attempt = 0;
+        loop {
+            let (err, failed) = match result {
+                Ok(()) => return,
+                Err(SinkError::Undelivered { source, points }) => (*source, points),
+                Err(e) => (e, points.to_vec()),
+            };
+            self.errors += 1;
+            let name = self.sinks[i].name().to_string();
+            if failed.is_empty() {
+                elogln!("(Sink {}) gagal: {}", name, err);
+                return;
+            }
+            if attempt >= self.retry.retries {
+                elogln!("(Sink {}) gagal setelah {} percobaan ulang: {}", name, attempt, err);
+                match self.dead_letter.as_mut().map(|dl| dl.write(&name, &err, &failed)) {
+                    Some(Ok(())) => elogln!("(Sink {}) {} titik ke dead-letter.", name, failed.len()),
+                    Some(Err(e)) => elogln!("(Sink {}) dead-letter gagal, {} titik hilang: {}", name, failed.len(), e),
+                    None => elogln!("(Sink {}) {} titik hilang (dead-letter tidak diatur).", name, failed.len()),
+                }
+                return;
+            }
+            std::thread::sleep(self.retry.backoff * 2u32.saturating_pow(attempt));
+            attempt += 1;
+            result = self.sinks[i].write_batch(&failed);
+        }
+    }
+}
+
+impl FrameHandler for SinkSet {
+    fn on_point(&mut self, point: &DecodedPoint) {
+        for i in 0..self.sinks.len() {
+            let r = self.sinks[i].write(point);
+            self.settle(i, r, std::slice::from_ref(point));
+        }
+    }
+
+    fn on_state_change(&mut self, state: ConnState) {
+        if state == ConnState::Disconnected { self.flush_all(); }
+    }
+
+    fn on_tick(&mut self) {
+        for i in 0..self.sinks.len() {
+            let r = self.sinks[i].tick();
+            self.settle(i, r, &[]);
+        }
+    }
+}
+
+enum SinkMsg {
+    Point(Box<DecodedPoint>),
+    Tick,
+    Flush,
+}
+
+/// Titik yang belum sampai ke tujuan, dibaca saat shutdown untuk melaporkan yang dikuras / hilang.
+#[derive(Default)]
+struct Backlog {
+    /// Di antrean, belum diambil thread sink.
+    queued: AtomicU64,
+    /// Di buffer batch sink (diperbarui thread sink tiap pesan).
+    buffered: AtomicU64,
+}
+
+/// `SinkSet` di thread latar. Antrean penuh = titik dibuang dan dihitung, bukan menunggu.
+pub struct SinkWorker {
+    tx: Option<SyncSender<SinkMsg>>,
+    thread: Option<JoinHandle<()>>,
+    backlog: Arc<Backlog>,
+    /// Batas menunggu thread sink menguras antrean & buffer saat shutdown.
+    shutdown_timeout: Duration,
+    pub dropped: u64,
+    dropped_reported: u64,
+    last_report: Instant,
+}
+
+/// Jarak minimum antar laporan titik terbuang.
+const DROP_REPORT_INTERVAL: Duration = Duration::from_secs(10);
+
+impl SinkWorker {
+    pub fn spawn(mut set: SinkSet, capacity: usize, shutdown_timeout: Duration) -> SinkWorker {
+        let (tx, rx) = sync_channel::<SinkMsg>(capacity);
+        let backlog = Arc::new(Backlog::default());
+        let shared = backlog.clone();
+        let thread = std::thread::spawn(move || {
+            for msg in rx {
+                match msg {
+                    SinkMsg::Point(p) => {
+                        shared.queued.fetch_sub(1, Ordering::Relaxed);
+                        set.on_point(&p)
+                    }
+                    SinkMsg::Tick => set.on_tick(),
+                    SinkMsg::Flush => set.flush_all(),
+                }
+                shared.buffered.store(set.pending() as u64, Ordering::Relaxed);
+            }
+            // Pengirim ditutup (shutdown): antrean sudah habis, kosongkan buffer batch
+            set.close();
+            shared.buffered.store(0, Ordering::Relaxed);
+        });
+        SinkWorker {
+            tx: Some(tx), thread: Some(thread), backlog, shutdown_timeout,
+            dropped: 0, dropped_reported: 0, last_report: Instant::now(),
+        }
+    }
+
+    fn send(&mut self, msg: SinkMsg) {
+        let Some(tx) = &self.tx else { return };
+        let point = matches!(msg, SinkMsg::Point(_));
+        // Dihitung sebelum kirim: thread sink bisa mengambilnya sebelum try_send kembali
+        if point { self.backlog.queued.fetch_add(1, Ordering::Relaxed); }
+        match tx.try_send(msg) {
+            Ok(()) => {}
+            Err(TrySendError::Full(SinkMsg::Point(_))) => {
+                self.backlog.queued.fetch_sub(1, Ordering::Relaxed);
+                self.dropped += 1;
+            }
+            // Tick/flush berikutnya akan datang lagi; thread mati sudah dilaporkan panic-nya
+            Err(_) => if point { self.backlog.queued.fetch_sub(1, Ordering::Relaxed); },
+        }
+    }
+
+    /// Tutup antrean, tunggu thread sink menguras antrean dan buffer batch paling lama
+    /// `shutdown_timeout`. Sink yang macet tidak menahan proses: sisa titik dilaporkan hilang.
+    fn shutdown(&mut self) {
+        let Some(thread) = self.thread.take() else { return };
+        let (queued, buffered) = (self.backlog.queued.load(Ordering::Relaxed), self.backlog.buffered.load(Ordering::Relaxed));
+        if queued + buffered > 0 {
+            logln!(
+                "(Sink) shutdown: menguras {} titik antrean + {} titik buffer batch (batas {:?}) ...",
+                queued, buffered, self.shutdown_timeout
+            );
+        }
+        self.tx = None;
+        let start = Instant::now();
+        while !thread.is_finished() && start.elapsed() < self.shutdown_timeout {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        if thread.is_finished() {
+            let _ = thread.join();
+            logln!(
+                "(Sink) shutdown: {} titik dikuras, semua sink di-flush & ditutup dalam {} ms.",
+                queued + buffered, start.elapsed().as_millis()
+            );
+        } else {
+            // Thread dilepas; proses segera keluar
+            elogln!(
+                "(Sink) shutdown: batas {:?} habis, {} titik antrean + {} titik buffer batch tidak terkirim.",
+                self.shutdown_timeout, self.backlog.queued.load(Ordering::Relaxed), self.backlog.buffered.load(Ordering::Relaxed)
+            );
+        }
+        if self.dropped > 0 {
+            logln!("Ringkasan: {} titik dibuang karena antrean sink penuh.", self.dropped);
+        }
+    }
+}
+
+impl FrameHandler for SinkWorker {
+    fn on_point(&mut self, point: &DecodedPoint) {
+        self.send(SinkMsg::Point(Box::new(point.clone())));
+    }
+
+    fn on_state_change(&mut self, state: ConnState) {
+        if state == ConnState::Disconnected { self.send(SinkMsg::Flush); }
+    }
+
+    fn on_shutdown(&mut self) { self.shutdown(); }
+
+    fn on_tick(&mut self) {
+        self.send(SinkMsg::Tick);
+        if self.dropped > self.dropped_reported && self.last_report.elapsed() >= DROP_REPORT_INTERVAL {
+            let n = self.dropped - self.dropped_reported;
+            elogln!(
+                "(Sink) antrean penuh: {} titik dibuang dalam {:.0}s ({:.1}/s), total {}.",
+                n, self.last_report.elapsed().as_secs_f64(), n as f64 / self.last_report.elapsed().as_secs_f64(), self.dropped
+            );
+            self.dropped_reported = self.dropped;
+            self.last_report = Instant::now();
+        }
+    }
+}
+
+impl Drop for SinkWorker {
+    /// Jalur keluar yang melewatkan `on_shutdown` (mis. error sebelum read loop).
+    fn drop(&mut self) { self.shutdown(); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Mutex;
+
+    use crate::decode::{InformationObject, ObjValue, Origin};
+
+    fn point(ioa: u32, v: f32) -> DecodedPoint {
+        DecodedPoint {
+            casdu: 1, type_id: 13, cot: 3, origin: Origin::Spontaneous,
+            obj: InformationObject { ioa, value: ObjValue::Float(v), quality: None, time: None, bad_time: None },
+            time_utc_ms: None, suspect_time: false, station: None, point: None, value_eng: None, unit: None,
+        }
+    }
+
+    /// Sink memori: IOA tiap batch yang diterima; `fail` = tiap penulisan gagal.
+    #[derive(Clone, Default)]
+    struct MemSink {
+        batches: Arc<Mutex<Vec<Vec<u32>>>>,
+        fail: Arc<AtomicBool>,
+    }
+
+    impl MemSink {
+        fn batches(&self) -> Vec<Vec<u32>> { self.batches.lock().unwrap().clone() }
+    }
+
+    impl Sink for MemSink {
+        fn name(&self) -> &str { "mem" }
+        fn write(&mut self, point: &DecodedPoint) -> Result<(), SinkError> {
+            self.write_batch(std::slice::from_ref(point))
+        }
+        fn write_batch(&mut self, points: &[DecodedPoint]) -> Result<(), SinkError> {
+            if self.fail.load(Ordering::Relaxed) {
+                return Err(std::io::Error::other("tujuan mati").into());
+            }
+            self.batches.lock().unwrap().push(points.iter().map(|p| p.obj.ioa).collect());
+            Ok(())
+        }
+    }
+
+    fn batch(mem: &MemSink, size: usize, interval: Option<Duration>) -> BatchSink {
+        BatchSink::new(Box::new(mem.clone()), Batching { size, interval })
+    }
+
+    #[test]
+    fn batch_flushes_at_size() {
+        let mem = MemSink::default();
+        let mut b = batch(&mem, 3, None);
+        for ioa in 1..=2 { b.write(&point(ioa, 1.0)).unwrap(); }
+        assert_eq!((b.pending(), mem.batches().len()), (2, 0));
+        b.write(&point(3, 1.0)).unwrap();
+        assert_eq!(b.pending(), 0);
+        assert_eq!(mem.batches(), [vec![1, 2, 3]]);
+    }
+
+    #[test]
+    fn batch_flushes_on_tick_after_interval() {
+        let mem = MemSink::default();
+        let mut b = batch(&mem, 100, Some(Duration::from_secs(60)));
+        b.write(&point(1, 1.0)).unwrap();
+        b.tick().unwrap();
+        assert_eq!(b.pending(), 1);
+        b.last_flush = Instant::now() - Duration::from_secs(60);
+        b.tick().unwrap();
+        assert_eq!(b.pending(), 0);
+        assert_eq!(mem.batches(), [vec![1]]);
+    }
+
+    #[test]
+    fn failed_batch_is_returned_not_kept() {
+        let mem = MemSink::default();
+        mem.fail.store(true, Ordering::Relaxed);
+        let mut b = batch(&mem, 2, None);
+        b.write(&point(1, 1.0)).unwrap();
+        match b.write(&point(2, 2.0)) {
+            Err(SinkError::Undelivered { points, .. }) => assert_eq!(points.iter().map(|p| p.obj.ioa).collect::<Vec<_>>(), [1, 2]),
+            other => panic!("harus Undelivered: {:?}", other),
+        }
+        assert_eq!(b.pending(), 0);
+        // Tujuan pulih: batch berikutnya lewat, yang gagal tidak ikut lagi
+        mem.fail.store(false, Ordering::Relaxed);
+        b.write(&point(3, 3.0)).unwrap();
+        b.flush().unwrap();
+        assert_eq!(mem.batches(), [vec![3]]);
+    }
+
+    #[test]
+    fn undelivered_points_land_in_dead_letter_as_replayable_json() {
+        let dir = std::env::temp_dir().join(format!("iec104-deadletter-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("dead.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let mem = MemSink::default();
+        mem.fail.store(true, Ordering::Relaxed);
+        let mut set = SinkSet::default();
+        set.push(Box::new(mem.clone()));
+        set.set_delivery(RetryPolicy { retries: 1, backoff: Duration::ZERO }, Some(DeadLetter::open(path.to_str().unwrap()).unwrap()));
+        let points = [point(2000, 230.5), point(2001, -1.25)];
+        for p in &points { set.on_point(p); }
+        assert_eq!(set.dead_letter.as_ref().map(|dl| dl.count), Some(2));
+
+        // Tiap baris membawa record titik persis seperti baris sink JSON: replay cukup mengambil
+        // medan "point" dan menulisnya ulang ke tujuan
+        let text = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        for (line, p) in lines.iter().zip(&points) {
+            let json = output::format_point(OutputFormat::Json, &OutputOptions::default(), p);
+            assert_eq!(*line, format!("{{\"sink\":\"mem\",\"error\":\"I/O: tujuan mati\",\"point\":{}}}", json));
+        }
+        assert!(mem.batches().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotated_segments_are_fully_compressed_when_sink_closes() {
+        let dir = std::env::temp_dir().join(format!("iec104-rotate-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("points.jsonl");
+        let spec = SinkSpec::parse("f", &format!("json:{}", path.display())).unwrap();
+        let mut sink = FileSink::open(&spec, Rotation { max_bytes: 4096, gzip: true }, OutputOptions::default()).unwrap();
+        // Satu rotasi saja: nama segmen beresolusi milidetik
+        for ioa in 0..25 { sink.write(&point(ioa, 1.5)).unwrap(); }
+        drop(sink);
+
+        let names: Vec<String> = std::fs::read_dir(&dir).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+        assert!(names.iter().any(|n| n.ends_with(".gz")), "{:?}", names);
+        // Setelah sink ditutup tidak ada kompresi setengah jalan atau segmen mentah tersisa
+        assert!(names.iter().all(|n| n == "points.jsonl" || n.ends_with(".gz")), "{:?}", names);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
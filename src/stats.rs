@@ -0,0 +1,171 @@
+//! Snapshot statistik link hanya-baca untuk aplikasi penyemat (mis. supervisor): ACK per
+//! alasan, frame per jenis dan tipe ASDU, pemakaian jendela, status koneksi, aktivitas per
+//! casdu. Diserahkan ke `FrameHandler::on_stats` dan ikut di `/metrics` & `/stations`
+//! endpoint health.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::decode::format_epoch_ms;
+use crate::handler::ConnState;
+
+/// S-ACK terkirim per alasan keputusan ACK.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AckStats {
+    /// w I-frame diterima tanpa ACK.
+    pub w: u64,
+    /// t2 habis.
+    pub t2: u64,
+    /// Jendela RTU hampir mentok k.
+    pub emergency: u64,
+    /// --ack-mode immediate.
+    pub immediate: u64,
+}
+
+impl AckStats {
+    pub fn inc(&mut self, reason: &str) {
+        match reason {
+            "w" => self.w += 1,
+            "t2" => self.t2 += 1,
+            "emergency" => self.emergency += 1,
+            "immediate" => self.immediate += 1,
+            _ => {}
+        }
+    }
+}
+
+/// Aktivitas satu casdu (stasiun di balik gateway), untuk melihat stasiun mana yang diam.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CasduStats {
+    /// I-frame dengan casdu ini.
+    pub frames: u64,
+    /// Titik (IOA) berbeda yang pernah diterima.
+    pub points: u64,
+    /// Objek informasi diterima (termasuk yang ditahan deadband; tanpa yang disaring filter).
+    pub updates: u64,
+    /// Waktu host (epoch ms) objek terakhir.
+    pub last_update_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub state: ConnState,
+    pub acks: AckStats,
+    /// Frame diterima per jenis APCI; `frames_unknown` = tidak bisa diklasifikasi.
+    pub frames_i: u64,
+    pub frames_s: u64,
+    pub frames_u: u64,
+    /// Bagian dari `frames_u` dengan byte kontrol tidak standar.
+    pub frames_u_other: u64,
+    /// Bagian dari `frames_i` tanpa ASDU (LEN=4).
+    pub frames_i_empty: u64,
+    pub frames_unknown: u64,
+    /// Buffer RX melewati batas dan dipangkas sampai 0x68 berikutnya.
+    pub rx_overflows: u64,
+    /// N(R) terakhir dari RTU (S- maupun I-frame piggyback) dan jumlah yang mundur/melompat.
+    pub peer_nr: Option<u16>,
+    pub peer_nr_anomalies: u64,
+    /// STARTDT act diterima dari peer (peran master/RTU kemungkinan tertukar).
+    pub startdt_act_rx: u64,
+    /// TESTFR act tanpa con dalam t1: total, dan beruntun sejak con terakhir.
+    pub testfr_failures: u64,
+    pub testfr_fail_streak: u64,
+    /// M_EI_NA_1 diterima (RTU restart); bagian yang melaporkan parameter lokal berubah (BS1).
+    pub end_of_init: u64,
+    pub end_of_init_changed: u64,
+    /// I-frame diterima per type id ASDU.
+    pub asdu_types: BTreeMap<u8, u64>,
+    /// I-frame RTU yang belum kita ACK, dan k sisi RTU.
+    pub window_used: u16,
+    pub window_k: u16,
+    /// RTT TESTFR act -> con terakhir.
+    pub rtt: Option<Duration>,
+    /// Temuan pemeriksaan integritas per I-frame (panjang, VSQ, rentang medan, urutan).
+    pub integrity_warnings: u64,
+    pub integrity_errors: u64,
+    /// I-frame/s dari RTU dalam `backlog::WINDOW` terakhir.
+    pub i_rate: f64,
+    /// S-ACK didominasi jendela hampir penuh (jenuh): RTU kemungkinan menguras backlog.
+    pub backlog_suspected: bool,
+    pub backlog_episodes: u64,
+    pub casdus: BTreeMap<u16, CasduStats>,
+}
+
+impl Stats {
+    /// Format teks eksposisi Prometheus.
+    pub fn metrics(&self) -> String {
+        let mut out = String::new();
+        let mut family = |name: &str, kind: &str, help: &str, rows: Vec<(String, String)>| {
+            let _ = writeln!(out, "# HELP iec104_{name} {help}\n# TYPE iec104_{name} {kind}");
+            for (labels, value) in rows {
+                let _ = writeln!(out, "iec104_{name}{labels} {value}");
+            }
+        };
+        let label = |k: &str, v: &dyn std::fmt::Display| format!("{{{}=\"{}\"}}", k, v);
+        family("state", "gauge", "status koneksi saat ini (1 = aktif pada label ini)", vec![(label("state", &self.state), "1".into())]);
+        let a = &self.acks;
+        family("acks_total", "counter", "S-ACK terkirim per alasan", vec![
+            (label("reason", &"w"), a.w.to_string()),
+            (label("reason", &"t2"), a.t2.to_string()),
+            (label("reason", &"emergency"), a.emergency.to_string()),
+            (label("reason", &"immediate"), a.immediate.to_string()),
+        ]);
+        family("frames_total", "counter", "frame diterima per jenis APCI", vec![
+            (label("kind", &"i"), self.frames_i.to_string()),
+            (label("kind", &"s"), self.frames_s.to_string()),
+            (label("kind", &"u"), self.frames_u.to_string()),
+            (label("kind", &"unknown"), self.frames_unknown.to_string()),
+        ]);
+        family("u_other_total", "counter", "U-frame dengan byte kontrol tidak standar", vec![(String::new(), self.frames_u_other.to_string())]);
+        family("i_empty_total", "counter", "I-frame tanpa ASDU (APCI saja)", vec![(String::new(), self.frames_i_empty.to_string())]);
+        family("integrity_issues_total", "counter", "temuan integritas I-frame per tingkat", vec![
+            (label("severity", &"warning"), self.integrity_warnings.to_string()),
+            (label("severity", &"error"), self.integrity_errors.to_string()),
+        ]);
+        family("rx_overflows_total", "counter", "buffer RX melewati batas dan dipangkas", vec![(String::new(), self.rx_overflows.to_string())]);
+        family("peer_nr_anomalies_total", "counter", "N(R) RTU mundur atau melompat", vec![(String::new(), self.peer_nr_anomalies.to_string())]);
+        family("startdt_act_received_total", "counter", "STARTDT act diterima dari peer (peran tertukar?)", vec![(String::new(), self.startdt_act_rx.to_string())]);
+        family("end_of_init_total", "counter", "M_EI_NA_1 diterima (RTU restart) per BS1", vec![
+            (label("params_changed", &"false"), (self.end_of_init - self.end_of_init_changed).to_string()),
+            (label("params_changed", &"true"), self.end_of_init_changed.to_string()),
+        ]);
+        family("testfr_failures_total", "counter", "TESTFR act tanpa con dalam t1", vec![(String::new(), self.testfr_failures.to_string())]);
+        family("testfr_consecutive_failures", "gauge", "TESTFR act tanpa con beruntun sejak con terakhir", vec![(String::new(), self.testfr_fail_streak.to_string())]);
+        if let Some(nr) = self.peer_nr {
+            family("peer_nr", "gauge", "N(R) terakhir dari RTU", vec![(String::new(), nr.to_string())]);
+        }
+        family("asdu_total", "counter", "I-frame diterima per type id ASDU",
+            self.asdu_types.iter().map(|(t, n)| (label("type_id", t), n.to_string())).collect());
+        family("window_used", "gauge", "I-frame RTU belum di-ACK", vec![(String::new(), self.window_used.to_string())]);
+        family("window_k", "gauge", "k sisi RTU", vec![(String::new(), self.window_k.to_string())]);
+        family("i_rate", "gauge", "I-frame/s dari RTU (rata-rata jendela geser)", vec![(String::new(), format!("{:.2}", self.i_rate))]);
+        family("rtu_backlog_suspected", "gauge", "1 = jendela RTU jenuh, S-ACK didominasi jendela hampir penuh (RTU menguras backlog?)",
+            vec![(String::new(), u8::from(self.backlog_suspected).to_string())]);
+        family("rtu_backlog_episodes_total", "counter", "episode backlog RTU dicurigai", vec![(String::new(), self.backlog_episodes.to_string())]);
+        if let Some(rtt) = self.rtt {
+            family("rtt_seconds", "gauge", "RTT TESTFR act -> con terakhir", vec![(String::new(), format!("{:.4}", rtt.as_secs_f64()))]);
+        }
+        let per_casdu = |f: &dyn Fn(&CasduStats) -> Option<String>| -> Vec<(String, String)> {
+            self.casdus.iter().filter_map(|(c, s)| f(s).map(|v| (label("casdu", c), v))).collect()
+        };
+        family("casdu_frames_total", "counter", "I-frame per casdu", per_casdu(&|s| Some(s.frames.to_string())));
+        family("casdu_points", "gauge", "titik (IOA) berbeda per casdu", per_casdu(&|s| Some(s.points.to_string())));
+        family("casdu_updates_total", "counter", "objek informasi diterima per casdu", per_casdu(&|s| Some(s.updates.to_string())));
+        family("casdu_last_update_timestamp_seconds", "gauge", "waktu host objek terakhir per casdu (epoch)",
+            per_casdu(&|s| s.last_update_ms.map(|ms| format!("{:.3}", ms as f64 / 1000.0))));
+        out
+    }
+
+    /// Ringkasan per casdu sebagai JSON untuk `/stations`.
+    pub fn casdus_json(&self) -> String {
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as i64).unwrap_or(0);
+        let rows: Vec<String> = self.casdus.iter().map(|(c, s)| format!(
+            "{{\"casdu\":{},\"frames\":{},\"points\":{},\"updates\":{},\"last_update\":{},\"age_s\":{}}}",
+            c, s.frames, s.points, s.updates,
+            s.last_update_ms.map(|ms| format!("\"{}\"", format_epoch_ms(ms))).unwrap_or_else(|| "null".into()),
+            s.last_update_ms.map(|ms| format!("{:.1}", (now_ms - ms) as f64 / 1000.0)).unwrap_or_else(|| "null".into())
+        )).collect();
+        format!("{{\"state\":\"{}\",\"casdus\":[{}]}}", self.state, rows.join(","))
+    }
+}
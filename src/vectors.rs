@@ -0,0 +1,414 @@
+//! Vektor uji kesesuaian: ASDU yang di-encode tangan per tipe (mengikuti contoh standar /
+//! dokumen vendor) beserta hasil dekode yang diharapkan. Tabel yang sama dijalankan `cargo test`
+//! (satu test per kelompok) dan `--self-test` (laporan PASS/FAIL di biner yang terpasang).
+//!
+//! Sekaligus dokumentasi hidup tata letak byte: setiap decoder tipe baru menambah vektornya di sini.
+//! Encoder APCI diuji pulang-pergi: `classify_apdu(build_i_frame(..))` harus mengembalikan input.
+//! Builder C_BO_NA_1 diuji pulang-pergi lewat `decode_asdu` (urutan byte BSI 32 bit).
+//! `seq_in_window` dibandingkan dengan hitungan naif di sekitar batas wrap 32767 -> 0.
+//! Qualifier QOS (set-point) dan QPM (parameter) diuji pulang-pergi untuk semua 256 byte.
+//! `parse_asdu_full` dengan medan alamat lebih pendek: semua vektor dikemas ulang per tata letak
+//! dan harus menghasilkan objek yang sama (IOA dipotong ke lebar medan).
+//! Skenario keputusan S-ACK (`process_frame`) ada di unit test di samping `LoopState`.
+
+use crate::decode::{decode_asdu, describe_coi, element_size, parse_asdu_full, AsduLayout, AsduStatus, ObjValue, ParseError};
+use crate::{build_bo_asdu, build_i_frame, classify_apdu, hex, seq_in_window, Frame, MAX_ASDU_LEN, SEQ_MOD};
+
+/// Objek yang diharapkan: (IOA, nilai, kualitas, time tag) dalam bentuk Display.
+type Expect = (u32, &'static str, &'static str, Option<&'static str>);
+
+struct Vector {
+    name: &'static str,
+    asdu: &'static [u8],
+    objects: &'static [Expect],
+}
+
+// CP56Time2a 2024-03-15 10:20:30.500 (Jumat): ms=30500 | menit | jam | DOW<<5|hari | bulan | tahun
+const T_2024_03_15: [u8; 7] = [0x24, 0x77, 0x14, 0x0A, 0xAF, 0x03, 0x18];
+
+macro_rules! asdu_with_time {
+    ($($b:expr),* ; $t:expr) => {
+        &[$($b,)* $t[0], $t[1], $t[2], $t[3], $t[4], $t[5], $t[6]]
+    };
+}
+
+const VECTORS: &[Vector] = &[
+    Vector {
+        name: "M_SP_NA_1 (1) SQ=0, dua objek, SIQ IV",
+        asdu: &[0x01, 0x02, 0x03, 0x00, 0x01, 0x00, 0xE9, 0x03, 0x00, 0x01, 0xEA, 0x03, 0x00, 0x81],
+        objects: &[(1001, "ON", "OK", None), (1002, "ON", "IV", None)],
+    },
+    Vector {
+        name: "M_DP_NA_1 (3) DPI=2",
+        asdu: &[0x03, 0x01, 0x03, 0x00, 0x01, 0x00, 0x0A, 0x00, 0x00, 0x02],
+        objects: &[(10, "ON", "OK", None)],
+    },
+    Vector {
+        name: "M_ST_NA_1 (5) transient, posisi -3",
+        asdu: &[0x05, 0x01, 0x03, 0x00, 0x01, 0x00, 0x14, 0x00, 0x00, 0xFD, 0x00],
+        objects: &[(20, "-3 (transient)", "OK", None)],
+    },
+    Vector {
+        name: "M_BO_NA_1 (7) bitstring 32",
+        asdu: &[0x07, 0x01, 0x03, 0x00, 0x01, 0x00, 0x1E, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12, 0x00],
+        objects: &[(30, "0x12345678", "OK", None)],
+    },
+    Vector {
+        name: "M_ME_NA_1 (9) normalized 0x4000",
+        asdu: &[0x09, 0x01, 0x01, 0x00, 0x01, 0x00, 0x28, 0x00, 0x00, 0x00, 0x40, 0x00],
+        objects: &[(40, "0.50000", "OK", None)],
+    },
+    Vector {
+        name: "M_ME_NB_1 (11) scaled 32767, QDS OV",
+        asdu: &[0x0B, 0x01, 0x03, 0x00, 0x01, 0x00, 0x32, 0x00, 0x00, 0xFF, 0x7F, 0x01],
+        objects: &[(50, "32767", "OV", None)],
+    },
+    Vector {
+        name: "M_ME_NB_1 (11) SQ=1, IOA berurutan",
+        asdu: &[0x0B, 0x83, 0x14, 0x00, 0x01, 0x00, 0xF4, 0x01, 0x00, 0x0A, 0x00, 0x00, 0x14, 0x00, 0x00, 0x1E, 0x00, 0x00],
+        objects: &[(500, "10", "OK", None), (501, "20", "OK", None), (502, "30", "OK", None)],
+    },
+    Vector {
+        name: "M_ME_NC_1 (13) float 25.0, QDS NT|IV",
+        asdu: &[0x0D, 0x01, 0x03, 0x00, 0x01, 0x00, 0x3C, 0x00, 0x00, 0x00, 0x00, 0xC8, 0x41, 0xC0],
+        objects: &[(60, "25", "IV|NT", None)],
+    },
+    Vector {
+        name: "M_IT_NA_1 (15) counter 12345, SQ=5",
+        asdu: &[0x0F, 0x01, 0x25, 0x00, 0x01, 0x00, 0x46, 0x00, 0x00, 0x39, 0x30, 0x00, 0x00, 0x05],
+        objects: &[(70, "12345 SQ=5", "OK", None)],
+    },
+    Vector {
+        name: "M_SP_TB_1 (30) dengan CP56Time2a",
+        asdu: asdu_with_time!(0x1E, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD3, 0x07, 0x00, 0x01; T_2024_03_15),
+        objects: &[(2003, "ON", "OK", Some("2024-03-15T10:20:30.500"))],
+    },
+    Vector {
+        name: "M_ME_TF_1 (36) float 5.0 dengan CP56Time2a",
+        asdu: asdu_with_time!(0x24, 0x01, 0x03, 0x00, 0x01, 0x00, 0xD1, 0x07, 0x00, 0x00, 0x00, 0xA0, 0x40, 0x00; T_2024_03_15),
+        objects: &[(2001, "5", "OK", Some("2024-03-15T10:20:30.500"))],
+    },
+    Vector {
+        name: "C_SC_NA_1 (45) act, select",
+        asdu: &[0x2D, 0x01, 0x06, 0x00, 0x01, 0x00, 0x88, 0x13, 0x00, 0x81],
+        objects: &[(5000, "SC ON select qu=unspecified", "-", None)],
+    },
+    Vector {
+        name: "C_RC_NA_1 (47) act, execute, HIGHER pulsa panjang",
+        asdu: &[0x2F, 0x01, 0x06, 0x00, 0x01, 0x00, 0x8A, 0x13, 0x00, 0x0A],
+        objects: &[(5002, "RC HIGHER execute qu=long-pulse", "-", None)],
+    },
+    Vector {
+        name: "C_SE_NA_1 (48) act, select, QL default",
+        asdu: &[0x30, 0x01, 0x06, 0x00, 0x01, 0x00, 0x70, 0x17, 0x00, 0x00, 0x40, 0x80],
+        objects: &[(6000, "SP 0.50000 select ql=default", "-", None)],
+    },
+    Vector {
+        name: "C_SE_NC_1 (50) act-con, execute, QL privat 100",
+        asdu: &[0x32, 0x01, 0x07, 0x00, 0x01, 0x00, 0x71, 0x17, 0x00, 0x00, 0x00, 0xC0, 0x3F, 0x64],
+        objects: &[(6001, "SP 1.5 execute ql=private(100)", "-", None)],
+    },
+    Vector {
+        name: "C_BO_NA_1 (51) act-con BSI",
+        asdu: &[0x33, 0x01, 0x07, 0x00, 0x01, 0x00, 0x70, 0x17, 0x00, 0x78, 0x56, 0x34, 0x12],
+        objects: &[(6000, "BO 0x12345678", "-", None)],
+    },
+    Vector {
+        name: "M_EI_NA_1 (70) end of init",
+        asdu: &[0x46, 0x01, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00],
+        objects: &[(0, "COI=0", "-", None)],
+    },
+    Vector {
+        name: "C_IC_NA_1 (100) act-con QOI=20",
+        asdu: &[0x64, 0x01, 0x07, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x14],
+        objects: &[(0, "QOI=20", "-", None)],
+    },
+    Vector {
+        name: "P_ME_NA_1 (110) ambang 0.0625",
+        asdu: &[0x6E, 0x01, 0x06, 0x00, 0x01, 0x00, 0xE9, 0x03, 0x00, 0x00, 0x08, 0x01],
+        objects: &[(1001, "PM 0.06250 threshold", "-", None)],
+    },
+    Vector {
+        name: "P_ME_NB_1 (111) act-con batas bawah, LPC, POP",
+        asdu: &[0x6F, 0x01, 0x07, 0x00, 0x01, 0x00, 0xEA, 0x03, 0x00, 0xF4, 0x01, 0xC3],
+        objects: &[(1002, "PM 500 low-limit local-change not-in-operation", "-", None)],
+    },
+    Vector {
+        name: "P_ME_NC_1 (112) batas atas 90.0",
+        asdu: &[0x70, 0x01, 0x06, 0x00, 0x01, 0x00, 0xEB, 0x03, 0x00, 0x00, 0x00, 0xB4, 0x42, 0x04],
+        objects: &[(1003, "PM 90 high-limit", "-", None)],
+    },
+    Vector {
+        name: "C_CS_NA_1 (103) clock sync",
+        asdu: asdu_with_time!(0x67, 0x01, 0x07, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00; T_2024_03_15),
+        objects: &[(0, "clock-sync", "-", Some("2024-03-15T10:20:30.500"))],
+    },
+];
+
+/// (N(S), N(R)) untuk uji pulang-pergi I-frame: nol, bit rendah/tinggi tiap oktet, batas 15 bit.
+const APCI_SEQ: &[(u16, u16)] = &[(0, 0), (1, 0), (0, 1), (127, 128), (255, 256), (16384, 1), (32767, 32767)];
+
+/// Nilai BSI: nol, semua bit, bit terendah/tertinggi tiap oktet, pola campuran.
+const BSI_VALUES: &[u32] = &[0, u32::MAX, 0x0000_0001, 0x8000_0000, 0x0000_FF00, 0x1234_5678, 0x8001_0080];
+
+/// COI M_EI_NA_1: tiap sebab standar, cadangan, privat, dengan/tanpa BS1.
+const COI_CASES: &[(u8, &str)] = &[
+    (0x00, "0 (power-on lokal, parameter tidak berubah)"),
+    (0x01, "1 (reset manual lokal, parameter tidak berubah)"),
+    (0x82, "130 (reset remote, parameter lokal berubah)"),
+    (0x03, "3 (sebab cadangan 3, parameter tidak berubah)"),
+    (0xA0, "160 (sebab privat 32, parameter lokal berubah)"),
+    (0x7F, "127 (sebab privat 127, parameter tidak berubah)"),
+];
+
+/// Tipe pembawa qualifier yang diuji pulang-pergi: 48 (QOS di byte ke-3), 110 (QPM di byte ke-3).
+const QUALIFIER_TYPES: &[u8] = &[48, 110];
+
+/// Tata letak non-104 (COT, CASDU, IOA) tempat semua vektor dikemas ulang: profil 101 umum dan campuran.
+const LAYOUTS: &[(usize, usize, usize)] = &[(1, 1, 1), (1, 1, 2), (2, 1, 2), (1, 2, 3), (2, 2, 2)];
+
+/// ASDU yang harus ditolak utuh: (nama, tata letak, byte, error).
+const LAYOUT_ERRORS: &[(&str, AsduLayout, &[u8], ParseError)] = &[
+    ("COT 0 oktet", COT0, &[0x01, 0x01, 0x03, 0x01, 0x00], ParseError::Layout(COT0)),
+    ("IOA 4 oktet", IOA4, &[0x01, 0x01, 0x03, 0x00, 0x01, 0x00], ParseError::Layout(IOA4)),
+    ("lebih pendek dari header 101", AsduLayout { cot: 1, casdu: 1, ioa: 2 }, &[0x01, 0x01, 0x03], ParseError::TooShort { len: 3, header: 4 }),
+];
+const COT0: AsduLayout = AsduLayout { cot: 0, casdu: 2, ioa: 3 };
+const IOA4: AsduLayout = AsduLayout { cot: 2, casdu: 2, ioa: 4 };
+
+/// Basis dan panjang jendela untuk `seq_in_window`: nol, tengah, tepat sebelum wrap, dan k/w umum.
+const SEQ_BASES: &[u16] = &[0, 1, 16384, 32755, 32766, 32767];
+const SEQ_LENS: &[u16] = &[0, 1, 2, 8, 12, 13, 32767];
+
+/// Satu kasus uji: nama untuk laporan dan hasil pemeriksaannya.
+type Case = (String, Result<(), String>);
+
+/// Kelompok kasus; dipakai bersama oleh `--self-test` dan `cargo test`.
+const GROUPS: &[fn() -> Vec<Case>] = &[
+    decode_cases, apci_cases, bitstring_cases, seq_window_cases, coi_cases, qualifier_cases, layout_cases,
+    layout_error_cases,
+];
+
+/// Jalankan semua kelompok dan cetak PASS/FAIL per kasus; Err bila ada yang tidak cocok.
+pub fn run() -> std::io::Result<()> {
+    let (mut total, mut failed) = (0, 0);
+    for (name, result) in GROUPS.iter().flat_map(|group| group()) {
+        total += 1;
+        match result {
+            Ok(()) => println!("PASS  {}", name),
+            Err(why) => {
+                failed += 1;
+                println!("FAIL  {}\n      {}", name, why);
+            }
+        }
+    }
+    println!("{} vektor, {} gagal.", total, failed);
+    if failed > 0 {
+        return Err(std::io::Error::other(format!("{} vektor uji gagal", failed)));
+    }
+    Ok(())
+}
+
+fn decode_cases() -> Vec<Case> {
+    VECTORS.iter().map(|v| (v.name.to_string(), check(v).map_err(|why| format!("{}\n      ASDU: {}", why, hex(v.asdu))))).collect()
+}
+
+/// ASDU vektor pertama, dan ASDU terpanjang yang masih muat di LEN.
+fn apci_cases() -> Vec<Case> {
+    let long = [VECTORS[0].asdu, &[0u8; MAX_ASDU_LEN][..]];
+    APCI_SEQ.iter().flat_map(|&(ns, nr)| long.map(|asdu| (
+        format!("I-frame pulang-pergi N(S)={} N(R)={} ASDU {} byte", ns, nr, asdu.len()),
+        check_i_frame(ns, nr, asdu),
+    ))).collect()
+}
+
+fn bitstring_cases() -> Vec<Case> {
+    BSI_VALUES.iter().map(|&value| (format!("C_BO_NA_1 pulang-pergi BSI=0x{:08X}", value), check_bitstring(value))).collect()
+}
+
+fn seq_window_cases() -> Vec<Case> {
+    SEQ_BASES.iter().flat_map(|&base| SEQ_LENS.iter().map(move |&len| {
+        (format!("seq_in_window base={} len={}", base, len), check_seq_window(base, len))
+    })).collect()
+}
+
+fn coi_cases() -> Vec<Case> {
+    COI_CASES.iter().map(|&(coi, expect)| (format!("COI 0x{:02X}", coi), check_coi(coi, expect))).collect()
+}
+
+fn qualifier_cases() -> Vec<Case> {
+    QUALIFIER_TYPES.iter().map(|&type_id| (format!("qualifier pulang-pergi 0..255 type {}", type_id), check_qualifier(type_id))).collect()
+}
+
+fn layout_cases() -> Vec<Case> {
+    LAYOUTS.iter().map(|&(cot, casdu, ioa)| (
+        format!("parse_asdu_full COT={} CASDU={} IOA={}: {} vektor dikemas ulang", cot, casdu, ioa, VECTORS.len()),
+        check_layout(AsduLayout { cot, casdu, ioa }),
+    )).collect()
+}
+
+fn layout_error_cases() -> Vec<Case> {
+    LAYOUT_ERRORS.iter().map(|(name, layout, asdu, want)| (
+        format!("parse_asdu_full ditolak: {}", name),
+        match parse_asdu_full(asdu, *layout) {
+            Err(e) if e == *want => Ok(()),
+            other => Err(format!("dapat {:?}, diharapkan {:?}", other.map(|d| d.objects.len()), want)),
+        },
+    )).collect()
+}
+
+fn check_i_frame(ns: u16, nr: u16, asdu: &[u8]) -> Result<(), String> {
+    let apdu = build_i_frame(ns, nr, asdu);
+    if apdu[1] as usize + 2 != apdu.len() {
+        return Err(format!("LEN={} tidak cocok dengan {} byte", apdu[1], apdu.len()));
+    }
+    match classify_apdu(&apdu) {
+        Frame::I { ns: got_ns, nr: got_nr, asdu: Some(_), control }
+            if (got_ns, got_nr) == (ns, nr) && control == apdu[2..6] && apdu[6..] == *asdu => Ok(()),
+        other => Err(format!("dapat {:?}, APDU {}", other, hex(&apdu))),
+    }
+}
+
+fn check_bitstring(value: u32) -> Result<(), String> {
+    let asdu = build_bo_asdu(1, 6000, value);
+    let d = decode_asdu(&asdu).ok_or("ASDU tidak terdekode")?;
+    match d.objects.as_slice() {
+        [o] if o.ioa == 6000 && o.value == ObjValue::BitstringCommand(value) && d.status == AsduStatus::Ok => Ok(()),
+        other => Err(format!("dapat {:?} (status {:?}), ASDU {}", other, d.status, hex(&asdu))),
+    }
+}
+
+/// COI di dalam M_EI_NA_1 utuh: didekode apa adanya lalu dijelaskan.
+fn check_coi(coi: u8, expect: &str) -> Result<(), String> {
+    let asdu = [0x46, 0x01, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, coi];
+    let d = decode_asdu(&asdu).ok_or("ASDU tidak terdekode")?;
+    match d.objects.as_slice() {
+        [o] if o.value == ObjValue::EndOfInit { coi } && describe_coi(coi) == expect => Ok(()),
+        [o] if o.value == ObjValue::EndOfInit { coi } => Err(format!("dapat '{}', diharapkan '{}'", describe_coi(coi), expect)),
+        other => Err(format!("dapat {:?} (status {:?})", other, d.status)),
+    }
+}
+
+/// Tiap byte qualifier didekode dalam ASDU utuh lalu disusun ulang dari enum-nya: tidak ada bit
+/// yang hilang atau tertukar antara S/E+QL maupun KPA+LPC+POP.
+fn check_qualifier(type_id: u8) -> Result<(), String> {
+    for b in 0..=255u8 {
+        let asdu = [type_id, 0x01, 0x06, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, b];
+        let d = decode_asdu(&asdu).ok_or("ASDU tidak terdekode")?;
+        let [o] = d.objects.as_slice() else { return Err(format!("0x{:02X}: {} objek", b, d.objects.len())) };
+        let back = match o.value {
+            ObjValue::SetpointNormalized { select, ql, .. } => (select as u8) << 7 | ql.bits(),
+            ObjValue::ParameterNormalized { qpm, .. } => qpm.byte(),
+            other => return Err(format!("0x{:02X}: dapat {:?}", b, other)),
+        };
+        if back != b {
+            return Err(format!("0x{:02X}: disusun ulang jadi 0x{:02X} ({})", b, back, o.value));
+        }
+    }
+    Ok(())
+}
+
+/// Kemas ulang ASDU 104 ke `layout` (OA hilang bila COT 1 oktet, alamat dipotong) lalu
+/// bandingkan hasil `parse_asdu_full` dengan dekode 104 aslinya.
+fn check_layout(layout: AsduLayout) -> Result<(), String> {
+    let le = |v: u32, n: usize| v.to_le_bytes()[..n].to_vec();
+    let mask = (1u32 << (8 * layout.ioa)) - 1;
+    for v in VECTORS {
+        let orig = decode_asdu(v.asdu).ok_or("ASDU tidak terdekode")?;
+        let size = element_size(orig.type_id).ok_or("type tanpa decoder")?;
+        let mut asdu = vec![v.asdu[0], v.asdu[1], v.asdu[2]];
+        if layout.cot == 2 { asdu.push(v.asdu[3]); }
+        asdu.extend(le(orig.casdu as u32, layout.casdu));
+        if orig.sq {
+            asdu.extend(le(orig.first_ioa.unwrap_or(0), layout.ioa));
+            asdu.extend_from_slice(&v.asdu[9..]);
+        } else {
+            for obj in v.asdu[6..].chunks(3 + size) {
+                asdu.extend(le(u32::from_le_bytes([obj[0], obj[1], obj[2], 0]), layout.ioa));
+                asdu.extend_from_slice(&obj[3..]);
+            }
+        }
+        let d = parse_asdu_full(&asdu, layout).map_err(|e| format!("{}: {}", v.name, e))?;
+        let want: Vec<_> = orig.objects.iter().map(|o| (o.ioa & mask, o.value, o.quality, o.time)).collect();
+        let got: Vec<_> = d.objects.iter().map(|o| (o.ioa, o.value, o.quality, o.time)).collect();
+        let casdu_mask = if layout.casdu == 1 { 0xFF } else { 0xFFFF };
+        if got != want || d.status != orig.status || d.casdu != orig.casdu & casdu_mask || d.first_ioa != orig.first_ioa.map(|i| i & mask) {
+            return Err(format!("{}: dapat {:?} ({:?}), diharapkan {:?}; ASDU {}", v.name, got, d.status, want, hex(&asdu)));
+        }
+    }
+    Ok(())
+}
+
+/// Kandidat base+k untuk k = -16 .. len+16 (dipotong agar tidak memutar penuh): di dalam
+/// jendela tepat bila 0 <= k < len, dihitung di i32 tanpa wrap.
+fn check_seq_window(base: u16, len: u16) -> Result<(), String> {
+    let m = SEQ_MOD as i32;
+    let lo = -(16.min(m - len as i32));
+    for k in lo..(len as i32 + 16).min(m + lo) {
+        let candidate = (base as i32 + k).rem_euclid(m) as u16;
+        let expect = (0..len as i32).contains(&k);
+        if seq_in_window(candidate, base, len) != expect {
+            return Err(format!("kandidat {}: dapat {}, diharapkan {}", candidate, !expect, expect));
+        }
+    }
+    Ok(())
+}
+
+fn check(v: &Vector) -> Result<(), String> {
+    let d = decode_asdu(v.asdu).ok_or("ASDU tidak terdekode")?;
+    if d.status != AsduStatus::Ok {
+        return Err(format!("status {:?}", d.status));
+    }
+    if d.objects.len() != v.objects.len() {
+        return Err(format!("{} objek, diharapkan {}", d.objects.len(), v.objects.len()));
+    }
+    for (obj, &(ioa, value, quality, time)) in d.objects.iter().zip(v.objects) {
+        let got = (
+            obj.ioa,
+            obj.value.to_string(),
+            obj.quality.map(|q| q.to_string()).unwrap_or_else(|| "-".into()),
+            obj.time.map(|t| t.to_string()),
+        );
+        let want = (ioa, value.to_string(), quality.to_string(), time.map(String::from));
+        if got != want {
+            return Err(format!("dapat {:?}, diharapkan {:?}", got, want));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_cases(cases: Vec<Case>) {
+        assert!(!cases.is_empty());
+        for (name, result) in cases {
+            assert_eq!(result, Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn decode_vectors() { assert_cases(decode_cases()); }
+
+    #[test]
+    fn apci_round_trip() { assert_cases(apci_cases()); }
+
+    #[test]
+    fn bitstring_round_trip() { assert_cases(bitstring_cases()); }
+
+    #[test]
+    fn seq_window() { assert_cases(seq_window_cases()); }
+
+    #[test]
+    fn coi() { assert_cases(coi_cases()); }
+
+    #[test]
+    fn qualifier_round_trip() { assert_cases(qualifier_cases()); }
+
+    #[test]
+    fn layouts() { assert_cases(layout_cases()); }
+
+    #[test]
+    fn layout_errors() { assert_cases(layout_error_cases()); }
+}